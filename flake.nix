@@ -37,6 +37,7 @@
           buildScript = pkgs.writeScriptBin "build" ''
             npm ci
             npx tsc
+            cargo run --release --bin build_dataset
             trunk build --release
           '';
         in pkgs.mkShell {
@@ -0,0 +1,17 @@
+// Reading a dataset from a file outside the browser, for the command-line tools
+use crate::adapters::encoding::decode_text;
+use crate::adapters::error::AdapterError;
+use crate::adapters::game_adapter::detect_game_adapter;
+use crate::model::{dataset::CompiledDataset, localization::DEFAULT_LOCALE};
+
+/// A dataset compiled by `build_dataset`, or a data file of any game an adapter recognizes, read as its default version
+pub fn load_dataset_file(bytes: &[u8]) -> Result<CompiledDataset, AdapterError> {
+    if CompiledDataset::is_compiled(bytes) {
+        return CompiledDataset::from_bytes(bytes).map_err(|e| AdapterError::Parse { message: e.to_string() });
+    }
+    let text = decode_text(bytes)?;
+    let adapter = detect_game_adapter(&text).ok_or_else(|| AdapterError::Parse {
+        message: "not a compiled dataset or a data file of a supported game".to_string(),
+    })?;
+    adapter.dataset_from_text(&text, adapter.default_version().id, DEFAULT_LOCALE)
+}
@@ -1,8 +1,374 @@
 // Dyson Sphere Program adapter module
-// Placeholder for DSP recipe adapter logic
-use crate::model::recipe::Recipe;
+//
+// Reads the item, recipe and building protos extracted from the game (ItemProtoSet, RecipeProtoSet
+// and the buildings' PrefabDesc), as one document:
+//
+//   {"items":     [{"ID": 1001, "Name": "Iron Ore", "Type": "Resource", "StackSize": 100}, ...],
+//    "recipes":   [{"ID": 1, "Name": "Iron Ingot", "Type": "Smelt", "TimeSpend": 60,
+//                   "Items": [1001], "ItemCounts": [1], "Results": [1101], "ResultCounts": [1]}, ...],
+//    "buildings": [{"ID": 2302, "Name": "Arc Smelter", "RecipeType": "Smelt", "Speed": 1.0,
+//                   "WorkEnergyPerTick": 6000}, ...]}
+//
+// Each list may also be a proto set dump, `{"dataArray": [...]}`. `TimeSpend` is in ticks (60 per
+// second), `Speed` is the building's production speed multiplier, and `WorkEnergyPerTick` is in J.
+use crate::adapters::encoding::decode_text;
+use crate::adapters::error::{AdapterError, LoadedRecipes};
+use crate::adapters::game_adapter::{DatasetVersion, GameAdapter};
+use crate::model::recipe::{CraftingMachine, Item, ItemStack, Recipe};
+use serde::Deserialize;
+use std::collections::HashMap;
 
-pub fn load_dsp_recipes_from_json(_path: &str) -> Result<Vec<Recipe>, Box<dyn std::error::Error>> {
-    // TODO: Implement Dyson Sphere Program recipe loading
-    Ok(vec![])
+const TICKS_PER_SECOND: f64 = 60.0;
+
+/// Recipe type of the Fractionator, whose output is probabilistic
+const FRACTIONATE: &str = "Fractionate";
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DspProtoList<T> {
+    List(Vec<T>),
+    ProtoSet {
+        #[serde(rename = "dataArray")]
+        data_array: Vec<T>,
+    },
+}
+
+impl<T> DspProtoList<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            DspProtoList::List(list) => list,
+            DspProtoList::ProtoSet { data_array } => data_array,
+        }
+    }
+}
+
+impl<T> Default for DspProtoList<T> {
+    fn default() -> Self {
+        DspProtoList::List(Vec::new())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DspItem {
+    #[serde(rename = "ID")]
+    pub id: u32,
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// EItemType, e.g. Resource, Material, Component, Production
+    #[serde(rename = "Type", default)]
+    pub item_type: String,
+    #[serde(rename = "StackSize", default)]
+    pub stack_size: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DspRecipe {
+    #[serde(rename = "ID")]
+    pub id: u32,
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// ERecipeType, e.g. Smelt, Assemble, Chemical, Fractionate
+    #[serde(rename = "Type")]
+    pub recipe_type: String,
+    #[serde(rename = "TimeSpend")]
+    pub time_spend: u32,
+    #[serde(rename = "Items", default)]
+    pub items: Vec<u32>,
+    #[serde(rename = "ItemCounts", default)]
+    pub item_counts: Vec<u32>,
+    #[serde(rename = "Results")]
+    pub results: Vec<u32>,
+    #[serde(rename = "ResultCounts")]
+    pub result_counts: Vec<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DspBuilding {
+    #[serde(rename = "ID")]
+    pub id: u32,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "RecipeType")]
+    pub recipe_type: String,
+    /// Production speed multiplier; for the Fractionator, items processed per second
+    #[serde(rename = "Speed", default = "default_speed")]
+    pub speed: f64,
+    #[serde(rename = "WorkEnergyPerTick", default)]
+    pub work_energy_per_tick: f64,
+}
+
+fn default_speed() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Deserialize)]
+struct DspProtos {
+    #[serde(default)]
+    items: DspProtoList<DspItem>,
+    #[serde(default)]
+    recipes: DspProtoList<DspRecipe>,
+    #[serde(default)]
+    buildings: DspProtoList<DspBuilding>,
+}
+
+fn item_id(id: u32) -> String {
+    id.to_string()
+}
+
+fn recipe_id(recipe: &DspRecipe, building: &DspBuilding) -> String {
+    format!("{}@{}", recipe.id, building.id)
+}
+
+fn stacks(
+    recipe: &DspRecipe,
+    ids: &[u32],
+    counts: &[u32],
+    items: &HashMap<u32, &DspItem>,
+    field: &'static str,
+) -> Result<Vec<ItemStack>, AdapterError> {
+    if ids.len() != counts.len() {
+        return Err(AdapterError::InvalidField {
+            class_name: recipe.name.clone(),
+            field,
+            message: format!("{} items but {} counts", ids.len(), counts.len()),
+        });
+    }
+    ids.iter()
+        .zip(counts)
+        .map(|(id, count)| {
+            if !items.contains_key(id) {
+                return Err(AdapterError::UnknownItemClass {
+                    class_name: recipe.name.clone(),
+                    field,
+                    item_class: item_id(*id),
+                });
+            }
+            Ok(ItemStack { item: item_id(*id), quantity: *count })
+        })
+        .collect()
+}
+
+/// One recipe per building tier that can run it; only the fastest tier is enabled by default
+fn dsp_recipe_to_recipes(
+    recipe: &DspRecipe,
+    items: &HashMap<u32, &DspItem>,
+    buildings: &[&DspBuilding],
+) -> Result<Vec<Recipe>, AdapterError> {
+    if buildings.is_empty() {
+        return Err(AdapterError::UnknownMachineClass {
+            class_name: recipe.name.clone(),
+            machine_class: recipe.recipe_type.clone(),
+        });
+    }
+    if recipe.time_spend == 0 {
+        return Err(AdapterError::InvalidField {
+            class_name: recipe.name.clone(),
+            field: "TimeSpend",
+            message: "zero duration".to_string(),
+        });
+    }
+    let mut inputs = stacks(recipe, &recipe.items, &recipe.item_counts, items, "Items")?;
+    let mut outputs = stacks(recipe, &recipe.results, &recipe.result_counts, items, "Results")?;
+    let fractionate = recipe.recipe_type == FRACTIONATE;
+    // Items a Fractionator passes per conversion
+    let mut batch = 0;
+    if fractionate {
+        // Each input item has a 1 in N chance to convert, where N is the input count; the rest pass
+        // through unchanged. Only the net effect is a recipe: one item in, one converted out, taking
+        // as long as the building needs to pass N items.
+        let (Some(input), Some(output)) = (inputs.first_mut(), outputs.first_mut()) else {
+            return Err(AdapterError::MissingField {
+                class_name: recipe.name.clone(),
+                field: "Items",
+            });
+        };
+        batch = input.quantity;
+        input.quantity = 1;
+        output.quantity = 1;
+        inputs.truncate(1);
+        outputs.truncate(1);
+    }
+    let fastest = buildings.iter().map(|b| b.speed).fold(f64::MIN, f64::max);
+    let mut fastest_enabled = false;
+    Ok(buildings
+        .iter()
+        .map(|building| {
+            let seconds = if fractionate {
+                // The Fractionator's speed is its throughput in items per second
+                batch as f64 / building.speed
+            } else {
+                recipe.time_spend as f64 / TICKS_PER_SECOND / building.speed
+            };
+            let enabled = !fastest_enabled && building.speed == fastest;
+            fastest_enabled |= enabled;
+            Recipe {
+                id: recipe_id(recipe, building),
+                name: if buildings.len() > 1 {
+                    format!("{} ({})", recipe.name, building.name)
+                } else {
+                    recipe.name.clone()
+                },
+                inputs: inputs.clone(),
+                outputs: outputs.clone(),
+                machine: CraftingMachine {
+                    id: item_id(building.id),
+                    name: building.name.clone(),
+                    power: building.work_energy_per_tick * TICKS_PER_SECOND / 1e6,
+                },
+                time: (seconds * 1000.0).round() as u32,
+                enabled,
+                source: None,
+            }
+        })
+        .collect())
+}
+
+/// Load every recipe from extracted DSP protos; recipes that fail to convert are skipped and reported as warnings
+pub fn load_dsp_recipes_from_json(json_str: &str) -> Result<LoadedRecipes, AdapterError> {
+    let protos: DspProtos = serde_json::from_str(json_str.trim_start_matches('\u{FEFF}'))?;
+    let items = protos.items.into_vec();
+    let recipes = protos.recipes.into_vec();
+    let buildings = protos.buildings.into_vec();
+    let items_by_id: HashMap<u32, &DspItem> = items.iter().map(|i| (i.id, i)).collect();
+    let mut buildings_by_type: HashMap<&str, Vec<&DspBuilding>> = HashMap::new();
+    for building in &buildings {
+        buildings_by_type.entry(building.recipe_type.as_str()).or_default().push(building);
+    }
+    for tiers in buildings_by_type.values_mut() {
+        tiers.sort_by(|a, b| a.speed.partial_cmp(&b.speed).unwrap_or(std::cmp::Ordering::Equal));
+    }
+
+    let mut loaded = LoadedRecipes::default();
+    for recipe in &recipes {
+        let tiers = buildings_by_type.get(recipe.recipe_type.as_str()).map(Vec::as_slice).unwrap_or_default();
+        match dsp_recipe_to_recipes(recipe, &items_by_id, tiers) {
+            Ok(variants) => loaded.recipes.extend(variants),
+            Err(e) => loaded.warnings.push(e),
+        }
+    }
+    loaded.items = items
+        .iter()
+        .map(|i| Item { id: item_id(i.id), name: i.name.clone() })
+        .collect();
+    loaded.stack_sizes = items
+        .iter()
+        .filter(|i| i.stack_size > 0)
+        .map(|i| (item_id(i.id), i.stack_size))
+        .collect();
+    loaded.raw_resources = items
+        .iter()
+        .filter(|i| i.item_type == "Resource")
+        .map(|i| item_id(i.id))
+        .collect();
+    loaded.machines = buildings
+        .iter()
+        .map(|b| CraftingMachine {
+            id: item_id(b.id),
+            name: b.name.clone(),
+            power: b.work_energy_per_tick * TICKS_PER_SECOND / 1e6,
+        })
+        .collect();
+    Ok(loaded)
+}
+
+/// Load recipes from the protos' raw bytes, whatever their text encoding
+pub fn load_dsp_recipes_from_bytes(bytes: &[u8]) -> Result<LoadedRecipes, AdapterError> {
+    load_dsp_recipes_from_json(&decode_text(bytes)?)
+}
+
+/// Whether a document looks like extracted DSP protos
+pub fn is_dsp_protos(json_str: &str) -> bool {
+    json_str.contains("\"TimeSpend\"") && json_str.contains("\"ResultCounts\"")
+}
+
+pub struct DspAdapter;
+
+impl GameAdapter for DspAdapter {
+    fn id(&self) -> &'static str {
+        "dsp"
+    }
+
+    fn name(&self) -> &'static str {
+        "Dyson Sphere Program"
+    }
+
+    fn data_file(&self) -> &'static str {
+        "extracted item, recipe and building protos"
+    }
+
+    fn versions(&self) -> &'static [DatasetVersion] {
+        &[DatasetVersion { id: "0.10", name: "0.10", bundled_file: None }]
+    }
+
+    fn detect(&self, text: &str) -> bool {
+        is_dsp_protos(text)
+    }
+
+    fn load(&self, text: &str, _version: &str) -> Result<LoadedRecipes, AdapterError> {
+        load_dsp_recipes_from_json(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::breakdown::{build_breakdown, BreakdownInputs};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_load_dsp_protos() {
+        let json_str = r#"{
+            "items": {"dataArray": [
+                {"ID": 1001, "Name": "Iron Ore", "Type": "Resource", "StackSize": 100},
+                {"ID": 1101, "Name": "Iron Ingot", "Type": "Material", "StackSize": 100},
+                {"ID": 1120, "Name": "Hydrogen", "Type": "Material", "StackSize": 100},
+                {"ID": 1121, "Name": "Deuterium", "Type": "Material", "StackSize": 100}
+            ]},
+            "recipes": [
+                {"ID": 1, "Name": "Iron Ingot", "Type": "Smelt", "TimeSpend": 60,
+                 "Items": [1001], "ItemCounts": [1], "Results": [1101], "ResultCounts": [1]},
+                {"ID": 115, "Name": "Deuterium", "Type": "Fractionate", "TimeSpend": 60,
+                 "Items": [1120], "ItemCounts": [100], "Results": [1121], "ResultCounts": [1]}
+            ],
+            "buildings": [
+                {"ID": 2302, "Name": "Arc Smelter", "RecipeType": "Smelt", "Speed": 1.0, "WorkEnergyPerTick": 6000},
+                {"ID": 2315, "Name": "Plane Smelter", "RecipeType": "Smelt", "Speed": 2.0, "WorkEnergyPerTick": 24000},
+                {"ID": 2314, "Name": "Fractionator", "RecipeType": "Fractionate", "Speed": 30.0, "WorkEnergyPerTick": 12000}
+            ]
+        }"#;
+        assert!(is_dsp_protos(json_str));
+        let loaded = load_dsp_recipes_from_json(json_str).unwrap();
+        assert!(loaded.warnings.is_empty());
+        assert_eq!(loaded.raw_resources, vec!["1001"]);
+        assert_eq!(loaded.stack_sizes.get("1101"), Some(&100));
+
+        let ingots: Vec<_> = loaded.recipes.iter().filter(|r| r.outputs[0].item == "1101").collect();
+        assert_eq!(ingots.len(), 2);
+        let plane = ingots.iter().find(|r| r.machine.name == "Plane Smelter").unwrap();
+        assert!(plane.enabled && plane.time == 500);
+        assert_eq!(plane.machine.power, 1.44);
+        assert!(!ingots.iter().find(|r| r.machine.name == "Arc Smelter").unwrap().enabled);
+
+        let deuterium = loaded.recipes.iter().find(|r| r.id == "115@2314").unwrap();
+        assert_eq!(deuterium.inputs, vec![ItemStack { item: "1120".to_string(), quantity: 1 }]);
+        assert_eq!(deuterium.outputs, vec![ItemStack { item: "1121".to_string(), quantity: 1 }]);
+        assert_eq!(deuterium.time, 3333); // 100 items at 30 per second
+
+        // The breakdown asks for one Hydrogen per Deuterium, not the hundred passing through
+        let enabled = loaded.recipes.iter().filter(|r| r.enabled).map(|r| r.id.clone()).collect();
+        let raw_resources = loaded.raw_resources.iter().cloned().collect();
+        let inputs = BreakdownInputs {
+            recipes: &loaded.recipes,
+            enabled: &enabled,
+            raw_resources: &raw_resources,
+            supplied: &HashMap::from([("1120".to_string(), 60.0)]),
+            choices: &BTreeMap::new(),
+            clocks: &BTreeMap::new(),
+        };
+        let tree = &build_breakdown(&[("1121".to_string(), 60.0)], &inputs)[0];
+        assert!((tree.machines_needed.unwrap() - 60.0 / (60_000.0 / 3333.0)).abs() < 1e-9);
+        assert_eq!(tree.children.len(), 1);
+        assert!(tree.children[0].supplied && tree.children[0].rate == 60.0);
+        assert!(!loaded.recipes.iter().any(|r| r.outputs.iter().any(|o| o.item == "1120")));
+    }
 }
@@ -0,0 +1,93 @@
+// Text decoding for data files copied straight from a game install
+use crate::adapters::error::AdapterError;
+
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF16BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+/// Detect the encoding from the BOM, or from the zero bytes of ASCII text in UTF-16 without one
+pub fn detect_encoding(bytes: &[u8]) -> (TextEncoding, usize) {
+    if bytes.starts_with(&UTF8_BOM) {
+        return (TextEncoding::Utf8, UTF8_BOM.len());
+    }
+    if bytes.starts_with(&UTF16LE_BOM) {
+        return (TextEncoding::Utf16Le, UTF16LE_BOM.len());
+    }
+    if bytes.starts_with(&UTF16BE_BOM) {
+        return (TextEncoding::Utf16Be, UTF16BE_BOM.len());
+    }
+    // JSON starts with ASCII ('[' or '{'), which UTF-16 pads with a zero byte
+    match bytes {
+        [a, 0, ..] if *a != 0 => (TextEncoding::Utf16Le, 0),
+        [0, b, ..] if *b != 0 => (TextEncoding::Utf16Be, 0),
+        _ => (TextEncoding::Utf8, 0),
+    }
+}
+
+/// Decode UTF-8, UTF-8 with BOM, or UTF-16LE/BE (with or without BOM) into a string
+pub fn decode_text(bytes: &[u8]) -> Result<String, AdapterError> {
+    let (encoding, bom_len) = detect_encoding(bytes);
+    let body = &bytes[bom_len..];
+    match encoding {
+        TextEncoding::Utf8 => String::from_utf8(body.to_vec()).map_err(|e| AdapterError::Parse {
+            message: format!("invalid UTF-8: {}", e),
+        }),
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            if !body.len().is_multiple_of(2) {
+                return Err(AdapterError::Parse {
+                    message: "UTF-16 data has an odd number of bytes".to_string(),
+                });
+            }
+            let units: Vec<u16> = body
+                .chunks_exact(2)
+                .map(|c| match encoding {
+                    TextEncoding::Utf16Le => u16::from_le_bytes([c[0], c[1]]),
+                    _ => u16::from_be_bytes([c[0], c[1]]),
+                })
+                .collect();
+            String::from_utf16(&units).map_err(|e| AdapterError::Parse {
+                message: format!("invalid UTF-16: {}", e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(s: &str, little_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if bom {
+            bytes.extend(if little_endian { UTF16LE_BOM } else { UTF16BE_BOM });
+        }
+        for unit in s.encode_utf16() {
+            bytes.extend(if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() });
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_decode_all_encodings() {
+        let text = r#"[{"NativeClass":"Schraube"}]"#;
+        let mut utf8_bom = UTF8_BOM.to_vec();
+        utf8_bom.extend(text.as_bytes());
+        for bytes in [
+            text.as_bytes().to_vec(),
+            utf8_bom,
+            utf16(text, true, true),
+            utf16(text, false, true),
+            utf16(text, true, false),
+            utf16(text, false, false),
+        ] {
+            assert_eq!(decode_text(&bytes).unwrap(), text);
+        }
+    }
+}
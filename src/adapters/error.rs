@@ -0,0 +1,83 @@
+// Error type shared by all game adapters
+use std::collections::BTreeMap;
+
+use crate::adapters::game_adapter::GameAdapter;
+use crate::model::dataset::CompiledDataset;
+use crate::model::recipe::{CraftingMachine, Item, Recipe, Schematic};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdapterError {
+    /// The source could not be read at all
+    Io { path: String, message: String },
+    /// The document is not valid JSON or does not have the expected layout
+    Parse { message: String },
+    /// A recipe references an item class that no descriptor defines
+    UnknownItemClass { class_name: String, field: &'static str, item_class: String },
+    /// A recipe references a machine class that no buildable defines
+    UnknownMachineClass { class_name: String, machine_class: String },
+    /// A required field is missing or empty
+    MissingField { class_name: String, field: &'static str },
+    /// A field is present but its value cannot be interpreted
+    InvalidField { class_name: String, field: &'static str, message: String },
+}
+
+impl std::fmt::Display for AdapterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdapterError::Io { path, message } => write!(f, "failed to read {}: {}", path, message),
+            AdapterError::Parse { message } => write!(f, "failed to parse document: {}", message),
+            AdapterError::UnknownItemClass { class_name, field, item_class } => {
+                write!(f, "{}: unknown item class {} in {}", class_name, item_class, field)
+            }
+            AdapterError::UnknownMachineClass { class_name, machine_class } => {
+                write!(f, "{}: unknown machine class {}", class_name, machine_class)
+            }
+            AdapterError::MissingField { class_name, field } => write!(f, "{}: missing {}", class_name, field),
+            AdapterError::InvalidField { class_name, field, message } => {
+                write!(f, "{}: invalid {}: {}", class_name, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdapterError {}
+
+impl From<serde_json::Error> for AdapterError {
+    fn from(e: serde_json::Error) -> Self {
+        AdapterError::Parse { message: e.to_string() }
+    }
+}
+
+/// Recipes that loaded successfully with the items, machines and schematics they use,
+/// plus one warning per recipe that was skipped
+#[derive(Debug, Clone, Default)]
+pub struct LoadedRecipes {
+    pub recipes: Vec<Recipe>,
+    pub items: Vec<Item>,
+    pub machines: Vec<CraftingMachine>,
+    pub schematics: Vec<Schematic>,
+    /// Item ids the game defines as raw resources
+    pub raw_resources: Vec<String>,
+    /// Items per inventory slot by item id, where the game's data says
+    pub stack_sizes: BTreeMap<String, u32>,
+    pub warnings: Vec<AdapterError>,
+}
+
+impl LoadedRecipes {
+    /// The normalized dataset the app and CLI load, with warnings rendered as text
+    pub fn into_dataset(self, adapter: &(impl GameAdapter + ?Sized), version: &str, locale: &str) -> CompiledDataset {
+        CompiledDataset {
+            game: adapter.id().to_string(),
+            version: version.to_string(),
+            locale: locale.to_string(),
+            units: adapter.units(),
+            items: self.items,
+            machines: self.machines,
+            recipes: self.recipes,
+            schematics: self.schematics,
+            raw_resources: self.raw_resources,
+            stack_sizes: self.stack_sizes,
+            warnings: self.warnings.iter().map(|w| w.to_string()).collect(),
+        }
+    }
+}
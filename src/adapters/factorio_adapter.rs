@@ -1,8 +1,430 @@
 // Factorio adapter module
-// Placeholder for Factorio recipe adapter logic
-use crate::model::recipe::Recipe;
+// Reads the `data-raw-dump.json` written by `factorio --dump-data` (script-output/data-raw-dump.json)
+use crate::adapters::encoding::decode_text;
+use crate::adapters::error::{AdapterError, LoadedRecipes};
+use crate::adapters::game_adapter::{DatasetVersion, GameAdapter};
+use crate::model::recipe::{CraftingMachine, Item, ItemStack, Recipe};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-pub fn load_factorio_recipes_from_json(_path: &str) -> Result<Vec<Recipe>, Box<dyn std::error::Error>> {
-    // TODO: Implement Factorio recipe loading
-    Ok(vec![])
+/// Prototype types that run recipes
+const CRAFTING_MACHINE_TYPES: [&str; 3] = ["assembling-machine", "furnace", "rocket-silo"];
+
+/// Recipe set to read; Factorio 1.1 recipes may define both, later versions only have one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FactorioDifficulty {
+    #[default]
+    Normal,
+    Expensive,
+}
+
+impl FactorioDifficulty {
+    fn key(self) -> &'static str {
+        match self {
+            FactorioDifficulty::Normal => "normal",
+            FactorioDifficulty::Expensive => "expensive",
+        }
+    }
+}
+
+/// Machine chosen to run the recipes of a crafting category
+#[derive(Debug, Clone)]
+struct FactorioMachine {
+    name: String,
+    crafting_speed: f64,
+    power: f64,
+}
+
+/// Factorio names are ids; without the locale files the label is the id in sentence case
+fn factorio_label(name: &str) -> String {
+    let spaced = name.replace('-', " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => spaced,
+    }
+}
+
+/// Parse an energy string such as `"75kW"` or `"2.5MW"` into MW
+fn parse_energy_mw(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number_end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let number: f64 = value[..number_end].parse().ok()?;
+    let scale = match value[number_end..].trim_end_matches(['W', 'J']) {
+        "" => 1e-6,
+        "k" => 1e-3,
+        "M" => 1.0,
+        "G" => 1e3,
+        "T" => 1e6,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+fn prototypes<'a>(raw: &'a Value, kind: &str) -> impl Iterator<Item = (&'a String, &'a Value)> {
+    raw.get(kind).and_then(Value::as_object).into_iter().flat_map(Map::iter)
+}
+
+fn num(value: &Value, field: &str) -> Option<f64> {
+    value.get(field).and_then(Value::as_f64)
+}
+
+/// Ingredient or result in either the `["iron-plate", 2]` or the `{name, amount}` form, as (name, expected amount)
+fn parse_stack(value: &Value) -> Option<(String, f64)> {
+    if let Some([name, amount]) = value.as_array().map(Vec::as_slice) {
+        return Some((name.as_str()?.to_string(), amount.as_f64()?));
+    }
+    let name = value.get("name")?.as_str()?.to_string();
+    let amount = match num(value, "amount") {
+        Some(amount) => amount,
+        None => (num(value, "amount_min")? + num(value, "amount_max")?) / 2.0,
+    };
+    let probability = num(value, "probability").unwrap_or(1.0);
+    Some((name, amount * probability))
+}
+
+/// Results of a recipe body, from `results` or from `result` and `result_count`
+fn parse_results(body: &Value) -> Vec<(String, f64)> {
+    if let Some(results) = body.get("results").and_then(Value::as_array) {
+        return results.iter().filter_map(parse_stack).collect();
+    }
+    match body.get("result").and_then(Value::as_str) {
+        Some(result) => vec![(result.to_string(), num(body, "result_count").unwrap_or(1.0))],
+        None => Vec::new(),
+    }
+}
+
+/// Smallest batch (1, 10, 100 or 1000 crafts) whose amounts are whole numbers, since item stacks hold integers
+fn batch_size(amounts: impl Iterator<Item = f64> + Clone) -> f64 {
+    [1.0, 10.0, 100.0, 1000.0]
+        .into_iter()
+        .find(|m| amounts.clone().all(|a| ((a * m) - (a * m).round()).abs() < 1e-6))
+        .unwrap_or(1000.0)
+}
+
+/// Fastest machine per crafting category; ties go to the lowest power draw
+fn machines_by_category(raw: &Value) -> HashMap<String, FactorioMachine> {
+    let mut by_category: HashMap<String, FactorioMachine> = HashMap::new();
+    for kind in CRAFTING_MACHINE_TYPES {
+        for (name, machine) in prototypes(raw, kind) {
+            let candidate = FactorioMachine {
+                name: name.clone(),
+                crafting_speed: num(machine, "crafting_speed").unwrap_or(1.0),
+                power: machine
+                    .get("energy_usage")
+                    .and_then(Value::as_str)
+                    .and_then(parse_energy_mw)
+                    .unwrap_or(0.0),
+            };
+            let categories = machine.get("crafting_categories").and_then(Value::as_array);
+            for category in categories.into_iter().flatten().filter_map(Value::as_str) {
+                let better = match by_category.get(category) {
+                    Some(current) => {
+                        candidate.crafting_speed > current.crafting_speed
+                            || (candidate.crafting_speed == current.crafting_speed && candidate.power < current.power)
+                    }
+                    None => true,
+                };
+                if better {
+                    by_category.insert(category.to_string(), candidate.clone());
+                }
+            }
+        }
+    }
+    by_category
+}
+
+/// Convert one recipe prototype, or `Ok(None)` when it does not exist in this difficulty or is hidden
+fn factorio_recipe_to_recipe(
+    name: &str,
+    prototype: &Value,
+    difficulty: FactorioDifficulty,
+    items: &HashSet<String>,
+    machines: &HashMap<String, FactorioMachine>,
+) -> Result<Option<Recipe>, AdapterError> {
+    // 1.1 recipes may move their body under `normal`/`expensive`; `false` there means "not in this mode"
+    let body = match prototype.get(difficulty.key()) {
+        Some(Value::Bool(false)) => return Ok(None),
+        Some(body @ Value::Object(_)) => body,
+        _ => prototype,
+    };
+    // Like the rest of the body, `hidden` may be set per difficulty
+    let hidden = body.get("hidden").or_else(|| prototype.get("hidden"));
+    if hidden.and_then(Value::as_bool).unwrap_or(false) {
+        return Ok(None);
+    }
+    let category = prototype.get("category").and_then(Value::as_str).unwrap_or("crafting");
+    let machine = machines.get(category).ok_or_else(|| AdapterError::UnknownMachineClass {
+        class_name: name.to_string(),
+        machine_class: category.to_string(),
+    })?;
+    let ingredients: Vec<(String, f64)> = body
+        .get("ingredients")
+        .and_then(Value::as_array)
+        .map(|i| i.iter().filter_map(parse_stack).collect())
+        .unwrap_or_default();
+    let results = parse_results(body);
+    if results.is_empty() {
+        return Err(AdapterError::MissingField {
+            class_name: name.to_string(),
+            field: "results",
+        });
+    }
+    let energy_required = num(body, "energy_required").unwrap_or(0.5);
+    if energy_required <= 0.0 {
+        return Err(AdapterError::InvalidField {
+            class_name: name.to_string(),
+            field: "energy_required",
+            message: format!("non-positive duration {}", energy_required),
+        });
+    }
+    let batch = batch_size(ingredients.iter().chain(results.iter()).map(|(_, a)| *a));
+    let to_stacks = |entries: &[(String, f64)], field: &'static str| -> Result<Vec<ItemStack>, AdapterError> {
+        entries
+            .iter()
+            .filter(|(_, amount)| *amount > 0.0)
+            .map(|(item, amount)| {
+                if !items.contains(item) {
+                    return Err(AdapterError::UnknownItemClass {
+                        class_name: name.to_string(),
+                        field,
+                        item_class: item.clone(),
+                    });
+                }
+                Ok(ItemStack {
+                    item: item.clone(),
+                    quantity: (amount * batch).round() as u32,
+                })
+            })
+            .collect()
+    };
+    let subgroup = prototype.get("subgroup").and_then(Value::as_str).unwrap_or_default();
+    Ok(Some(Recipe {
+        id: name.to_string(),
+        name: factorio_label(name),
+        inputs: to_stacks(&ingredients, "ingredients")?,
+        outputs: to_stacks(&results, "results")?,
+        machine: CraftingMachine {
+            id: machine.name.clone(),
+            name: factorio_label(&machine.name),
+            power: machine.power,
+        },
+        // The machine's speed is folded into the time, as one recipe has one machine
+        time: (energy_required * batch / machine.crafting_speed * 1000.0).round() as u32,
+        // Barrelling only moves fluids around and would form cycles with every fluid recipe
+        enabled: subgroup != "fill-barrel" && subgroup != "empty-barrel",
+        source: None,
+    }))
+}
+
+/// Load every recipe from a Factorio data dump; recipes that fail to convert are skipped and reported as warnings
+pub fn load_factorio_recipes_from_json(json_str: &str, difficulty: FactorioDifficulty) -> Result<LoadedRecipes, AdapterError> {
+    let raw: Value = serde_json::from_str(json_str.trim_start_matches('\u{FEFF}'))?;
+    if raw.get("recipe").and_then(Value::as_object).is_none() {
+        return Err(AdapterError::Parse {
+            message: "no `recipe` prototypes; expected the data-raw-dump.json of `factorio --dump-data`".to_string(),
+        });
+    }
+    // Fluids, and every other prototype with a stack size, can appear in recipes
+    let mut items: HashSet<String> = HashSet::new();
+    let mut stack_sizes: BTreeMap<String, u32> = BTreeMap::new();
+    if let Some(kinds) = raw.as_object() {
+        for (kind, protos) in kinds {
+            for (name, proto) in protos.as_object().into_iter().flatten() {
+                let stack_size = proto.get("stack_size").and_then(Value::as_u64);
+                if kind == "fluid" || stack_size.is_some() {
+                    items.insert(name.clone());
+                }
+                if let Some(size) = stack_size.and_then(|s| u32::try_from(s).ok()) {
+                    stack_sizes.insert(name.clone(), size);
+                }
+            }
+        }
+    }
+    let machines = machines_by_category(&raw);
+    let mut loaded = LoadedRecipes::default();
+    let mut recipes: Vec<(&String, &Value)> = prototypes(&raw, "recipe").collect();
+    recipes.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, prototype) in recipes {
+        match factorio_recipe_to_recipe(name, prototype, difficulty, &items, &machines) {
+            Ok(Some(recipe)) => loaded.recipes.push(recipe),
+            Ok(None) => {}
+            Err(e) => loaded.warnings.push(e),
+        }
+    }
+
+    // Raw resources: what the mining drills can mine, plus what offshore pumps pump
+    let drill_categories: BTreeSet<&str> = prototypes(&raw, "mining-drill")
+        .flat_map(|(_, drill)| drill.get("resource_categories").and_then(Value::as_array).into_iter().flatten())
+        .filter_map(Value::as_str)
+        .collect();
+    let mut raw_resources: BTreeSet<String> = BTreeSet::new();
+    for (_, resource) in prototypes(&raw, "resource") {
+        let category = resource.get("category").and_then(Value::as_str).unwrap_or("basic-solid");
+        if !drill_categories.contains(category) {
+            continue;
+        }
+        if let Some(minable) = resource.get("minable") {
+            raw_resources.extend(parse_results(minable).into_iter().map(|(name, _)| name));
+        }
+    }
+    for (_, pump) in prototypes(&raw, "offshore-pump") {
+        raw_resources.insert(pump.get("fluid").and_then(Value::as_str).unwrap_or("water").to_string());
+    }
+    loaded.raw_resources = raw_resources.into_iter().collect();
+
+    let mut machine_list: Vec<CraftingMachine> = machines
+        .values()
+        .map(|m| (m.name.clone(), m.power))
+        .chain(prototypes(&raw, "mining-drill").map(|(name, drill)| {
+            let power = drill.get("energy_usage").and_then(Value::as_str).and_then(parse_energy_mw);
+            (name.clone(), power.unwrap_or(0.0))
+        }))
+        .collect::<HashMap<_, _>>()
+        .into_iter()
+        .map(|(id, power)| CraftingMachine { name: factorio_label(&id), id, power })
+        .collect();
+    machine_list.sort_by(|a, b| a.id.cmp(&b.id));
+    loaded.machines = machine_list;
+
+    let mut item_ids: Vec<&String> = loaded
+        .recipes
+        .iter()
+        .flat_map(|r| r.inputs.iter().chain(r.outputs.iter()).map(|s| &s.item))
+        .chain(loaded.raw_resources.iter())
+        .collect();
+    item_ids.sort();
+    item_ids.dedup();
+    loaded.items = item_ids
+        .into_iter()
+        .map(|id| Item { id: id.clone(), name: factorio_label(id) })
+        .collect();
+    stack_sizes.retain(|id, _| loaded.items.iter().any(|i| &i.id == id));
+    loaded.stack_sizes = stack_sizes;
+    Ok(loaded)
+}
+
+/// Whether a document looks like a Factorio data dump rather than a Satisfactory Docs file
+pub fn is_factorio_dump(json_str: &str) -> bool {
+    json_str.trim_start_matches('\u{FEFF}').trim_start().starts_with('{') && json_str.contains("\"assembling-machine\"")
+}
+
+/// Load recipes from the dump's raw bytes, whatever its text encoding
+pub fn load_factorio_recipes_from_bytes(bytes: &[u8], difficulty: FactorioDifficulty) -> Result<LoadedRecipes, AdapterError> {
+    load_factorio_recipes_from_json(&decode_text(bytes)?, difficulty)
+}
+
+pub struct FactorioAdapter;
+
+impl GameAdapter for FactorioAdapter {
+    fn id(&self) -> &'static str {
+        "factorio"
+    }
+
+    fn name(&self) -> &'static str {
+        "Factorio"
+    }
+
+    fn data_file(&self) -> &'static str {
+        "data-raw-dump.json from factorio --dump-data"
+    }
+
+    /// Version ids are the difficulty keys
+    fn versions(&self) -> &'static [DatasetVersion] {
+        &[
+            DatasetVersion { id: "normal", name: "1.1 (normal)", bundled_file: None },
+            DatasetVersion { id: "expensive", name: "1.1 (expensive)", bundled_file: None },
+        ]
+    }
+
+    fn detect(&self, text: &str) -> bool {
+        is_factorio_dump(text)
+    }
+
+    fn load(&self, text: &str, version: &str) -> Result<LoadedRecipes, AdapterError> {
+        let difficulty = if version == FactorioDifficulty::Expensive.key() {
+            FactorioDifficulty::Expensive
+        } else {
+            FactorioDifficulty::Normal
+        };
+        load_factorio_recipes_from_json(text, difficulty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"{
+        "item": {
+            "iron-ore": {"stack_size": 50},
+            "uranium-ore": {"stack_size": 50},
+            "iron-plate": {"stack_size": 100},
+            "iron-gear-wheel": {"stack_size": 100},
+            "uranium-235": {"stack_size": 100},
+            "uranium-238": {"stack_size": 100}
+        },
+        "fluid": {"water": {}, "sulfuric-acid": {}},
+        "recipe": {
+            "iron-plate": {"category": "smelting", "energy_required": 3.2, "ingredients": [["iron-ore", 1]], "result": "iron-plate"},
+            "iron-gear-wheel": {
+                "normal": {"ingredients": [["iron-plate", 2]], "result": "iron-gear-wheel"},
+                "expensive": {"ingredients": [["iron-plate", 4]], "result": "iron-gear-wheel"}
+            },
+            "gear-recycling": {
+                "normal": {"ingredients": [["iron-gear-wheel", 1]], "result": "iron-plate", "result_count": 2},
+                "expensive": {"hidden": true, "ingredients": [["iron-gear-wheel", 1]], "result": "iron-plate", "result_count": 4}
+            },
+            "uranium-processing": {"category": "centrifuging", "energy_required": 12,
+                "ingredients": [{"type": "item", "name": "uranium-ore", "amount": 10}],
+                "results": [
+                    {"type": "item", "name": "uranium-235", "amount": 1, "probability": 0.007},
+                    {"type": "item", "name": "uranium-238", "amount": 1, "probability": 0.993}
+                ]},
+            "handcraft-only": {"category": "nowhere", "ingredients": [], "result": "iron-plate"}
+        },
+        "assembling-machine": {
+            "assembling-machine-1": {"crafting_categories": ["crafting"], "crafting_speed": 0.5, "energy_usage": "75kW"},
+            "assembling-machine-2": {"crafting_categories": ["crafting"], "crafting_speed": 0.75, "energy_usage": "150kW"},
+            "centrifuge": {"crafting_categories": ["centrifuging"], "crafting_speed": 1, "energy_usage": "350kW"}
+        },
+        "furnace": {"stone-furnace": {"crafting_categories": ["smelting"], "crafting_speed": 1, "energy_usage": "90kW"}},
+        "mining-drill": {"burner-mining-drill": {"resource_categories": ["basic-solid"], "energy_usage": "150kW"}},
+        "resource": {
+            "iron-ore": {"minable": {"mining_time": 1, "result": "iron-ore"}},
+            "uranium-ore": {"category": "basic-solid", "minable": {"mining_time": 2, "result": "uranium-ore", "required_fluid": "sulfuric-acid"}},
+            "crude-oil": {"category": "basic-fluid", "minable": {"results": [{"type": "fluid", "name": "crude-oil", "amount_min": 10, "amount_max": 10}]}}
+        },
+        "offshore-pump": {"offshore-pump": {"fluid": "water"}}
+    }"#;
+
+    #[test]
+    fn test_load_factorio_dump() {
+        assert!(is_factorio_dump(DUMP));
+        let loaded = load_factorio_recipes_from_json(DUMP, FactorioDifficulty::Normal).unwrap();
+        let recipe = |id: &str| loaded.recipes.iter().find(|r| r.id == id).unwrap();
+        let gear = recipe("iron-gear-wheel");
+        assert_eq!(gear.inputs[0].quantity, 2);
+        assert_eq!(gear.machine.id, "assembling-machine-2");
+        assert_eq!(gear.time, 667); // 0.5 s at crafting speed 0.75
+        assert_eq!(recipe("iron-plate").machine.power, 0.09);
+        // 1000 crafts so the 0.7% chance becomes whole items
+        let uranium = recipe("uranium-processing");
+        assert_eq!(uranium.inputs[0].quantity, 10_000);
+        assert_eq!(uranium.outputs.iter().map(|o| o.quantity).collect::<Vec<_>>(), vec![7, 993]);
+        assert_eq!(uranium.time, 12_000_000);
+        assert!(matches!(&loaded.warnings[..], [AdapterError::UnknownMachineClass { machine_class, .. }] if machine_class == "nowhere"));
+        assert_eq!(loaded.raw_resources, vec!["iron-ore", "uranium-ore", "water"]);
+        assert_eq!(loaded.stack_sizes.get("iron-gear-wheel"), Some(&100));
+        assert!(!loaded.stack_sizes.contains_key("water"));
+
+        let expensive = load_factorio_recipes_from_json(DUMP, FactorioDifficulty::Expensive).unwrap();
+        let gear = expensive.recipes.iter().find(|r| r.id == "iron-gear-wheel").unwrap();
+        assert_eq!(gear.inputs[0].quantity, 4);
+        // Hidden in expensive mode only
+        assert!(recipe("gear-recycling").enabled);
+        assert!(!expensive.recipes.iter().any(|r| r.id == "gear-recycling"));
+    }
 }
@@ -0,0 +1,110 @@
+// Common interface of the game adapters and the registry the app and CLI pick them from
+use crate::adapters::dsp_adapter::DspAdapter;
+use crate::adapters::encoding::decode_text;
+use crate::adapters::error::{AdapterError, LoadedRecipes};
+use crate::adapters::factorio_adapter::FactorioAdapter;
+use crate::adapters::generic_dataset::GenericAdapter;
+use crate::adapters::satisfactory_adapter::SatisfactoryAdapter;
+use crate::model::dataset::{CompiledDataset, DatasetUnits};
+
+/// A flavour of a game's data an adapter can read, e.g. a release or a difficulty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatasetVersion {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Compiled dataset served next to the app, if one is bundled
+    pub bundled_file: Option<&'static str>,
+}
+
+pub trait GameAdapter: Sync {
+    /// Stable id stored in datasets, e.g. `satisfactory`
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    /// The file users load for this game, as named in the UI
+    fn data_file(&self) -> &'static str;
+
+    /// Supported versions, the default first
+    fn versions(&self) -> &'static [DatasetVersion];
+
+    fn units(&self) -> DatasetUnits {
+        DatasetUnits::default()
+    }
+
+    /// Whether a document looks like this game's data
+    fn detect(&self, text: &str) -> bool;
+
+    /// Read every recipe, item, machine and raw resource from a document
+    fn load(&self, text: &str, version: &str) -> Result<LoadedRecipes, AdapterError>;
+
+    /// Whether `load_with_mods` can layer mod documents on top of the base document
+    fn supports_mods(&self) -> bool {
+        false
+    }
+
+    /// Read a base document with mod documents on top, each given as mod name and text
+    fn load_with_mods(&self, text: &str, mods: &[(String, String)], version: &str) -> Result<LoadedRecipes, AdapterError> {
+        if mods.is_empty() {
+            return self.load(text, version);
+        }
+        Err(AdapterError::Parse {
+            message: format!("{} datasets do not support mods", self.name()),
+        })
+    }
+
+    /// Read a document into the normalized dataset
+    fn dataset_from_text(&self, text: &str, version: &str, locale: &str) -> Result<CompiledDataset, AdapterError> {
+        Ok(self.load(text, version)?.into_dataset(self, version, locale))
+    }
+
+    /// Read a document in any text encoding into the normalized dataset
+    fn load_dataset(&self, bytes: &[u8], version: &str, locale: &str) -> Result<CompiledDataset, AdapterError> {
+        self.dataset_from_text(&decode_text(bytes)?, version, locale)
+    }
+
+    fn default_version(&self) -> &'static DatasetVersion {
+        &self.versions()[0]
+    }
+
+    fn version(&self, id: &str) -> Option<&'static DatasetVersion> {
+        self.versions().iter().find(|v| v.id == id)
+    }
+}
+
+/// Every supported game, in the order the UI lists them; the generic format comes last so detection tries the games first
+pub static GAME_ADAPTERS: [&dyn GameAdapter; 4] = [&SatisfactoryAdapter, &FactorioAdapter, &DspAdapter, &GenericAdapter];
+
+pub fn game_adapter(id: &str) -> Option<&'static dyn GameAdapter> {
+    GAME_ADAPTERS.iter().copied().find(|a| a.id() == id)
+}
+
+/// The adapter whose format a document matches
+pub fn detect_game_adapter(text: &str) -> Option<&'static dyn GameAdapter> {
+    GAME_ADAPTERS.iter().copied().find(|a| a.detect(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let ids: Vec<_> = GAME_ADAPTERS.iter().map(|a| a.id()).collect();
+        assert_eq!(ids, vec!["satisfactory", "factorio", "dsp", "generic"]);
+        assert!(GAME_ADAPTERS.iter().all(|a| !a.versions().is_empty()));
+        assert_eq!(game_adapter("factorio").unwrap().version("expensive").unwrap().name, "1.1 (expensive)");
+        assert!(game_adapter("minecraft").is_none());
+
+        let docs = r#"[{"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGRecipe'", "Classes": []}]"#;
+        assert_eq!(detect_game_adapter(docs).unwrap().id(), "satisfactory");
+        let dump = r#"{"recipe": {}, "item": {}, "assembling-machine": {}}"#;
+        assert_eq!(detect_game_adapter(dump).unwrap().id(), "factorio");
+        assert!(detect_game_adapter("{}").is_none());
+        // Other games' files are not Docs files for mentioning the game or holding lists of objects
+        let generic = r#"{"format": 1, "name": "FactoryGame port", "items": [{"id": "Desc_OreIron_C", "name": "Iron Ore"}]}"#;
+        assert_eq!(detect_game_adapter(generic).unwrap().id(), "generic");
+        let protos = r#"{"items": [{"ID": 1}], "recipes": [{"ID": 1, "TimeSpend": 60, "ResultCounts": [1]}]}"#;
+        assert_eq!(detect_game_adapter(protos).unwrap().id(), "dsp");
+    }
+}
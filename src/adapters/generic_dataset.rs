@@ -0,0 +1,380 @@
+//! Generic dataset format, for games without an adapter
+//!
+//! A dataset is a TOML or JSON document that maps one-to-one onto the planner's model, so it can be
+//! written by hand or generated by a script. In TOML:
+//!
+//! ```toml
+//! format = 1                      # required, the version of this format
+//! name = "Captain of Industry"    # optional, describes the dataset
+//! raw_resources = ["iron_ore"]    # item ids the map provides
+//!
+//! [units]                         # optional, labels only; defaults to items/min and MW
+//! rate = "items/min"
+//! power = "MW"
+//!
+//! [[items]]                       # `Item`
+//! id = "iron_ore"
+//! name = "Iron Ore"
+//! stack_size = 50                 # optional, items per inventory slot; omit for fluids
+//!
+//! [[machines]]                    # `CraftingMachine`, power in MW
+//! id = "furnace"
+//! name = "Blast Furnace"
+//! power = 0.5
+//!
+//! [[recipes]]                     # `Recipe`, with the machine given by id
+//! id = "iron_smelting"
+//! name = "Iron Smelting"
+//! machine = "furnace"
+//! time = 20000                    # milliseconds per craft
+//! enabled = true                  # optional, defaults to true
+//! inputs = [{ item = "iron_ore", quantity = 12 }]
+//! outputs = [{ item = "iron", quantity = 8 }]
+//! ```
+//!
+//! The JSON form has the same keys. Ids must be unique per list; every item and machine a recipe
+//! uses and every raw resource must be declared. `validate_generic_dataset` lists all violations;
+//! the loader rejects dataset-level ones and skips invalid recipes with a warning, like the game adapters.
+use crate::adapters::error::{AdapterError, LoadedRecipes};
+use crate::adapters::game_adapter::{DatasetVersion, GameAdapter};
+use crate::model::dataset::{CompiledDataset, DatasetUnits};
+use crate::model::recipe::{CraftingMachine, Item, ItemStack, Recipe};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Version of the format this build reads
+pub const GENERIC_DATASET_FORMAT: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenericItem {
+    pub id: String,
+    pub name: String,
+    /// Items per inventory slot, used to size logistics; none for fluids
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_size: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenericRecipe {
+    pub id: String,
+    pub name: String,
+    /// Id of one of the dataset's machines
+    pub machine: String,
+    /// Crafting time in milliseconds
+    pub time: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub inputs: Vec<ItemStack>,
+    pub outputs: Vec<ItemStack>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenericDataset {
+    pub format: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub raw_resources: Vec<String>,
+    #[serde(default)]
+    pub units: DatasetUnits,
+    #[serde(default)]
+    pub items: Vec<GenericItem>,
+    #[serde(default)]
+    pub machines: Vec<CraftingMachine>,
+    #[serde(default)]
+    pub recipes: Vec<GenericRecipe>,
+}
+
+/// Parse a dataset, as JSON if it is an object and as TOML otherwise
+pub fn parse_generic_dataset(text: &str) -> Result<GenericDataset, AdapterError> {
+    let text = text.trim_start_matches('\u{FEFF}');
+    if text.trim_start().starts_with('{') {
+        Ok(serde_json::from_str(text)?)
+    } else {
+        toml::from_str(text).map_err(|e| AdapterError::Parse { message: e.to_string() })
+    }
+}
+
+fn duplicate_ids<'a>(list: &'static str, ids: impl Iterator<Item = &'a str>) -> Vec<AdapterError> {
+    let mut seen = HashSet::new();
+    ids.filter(|id| !seen.insert(*id))
+        .map(|id| AdapterError::InvalidField {
+            class_name: id.to_string(),
+            field: list,
+            message: "duplicate id".to_string(),
+        })
+        .collect()
+}
+
+/// Problems that make the whole dataset unusable
+fn dataset_errors(dataset: &GenericDataset) -> Vec<AdapterError> {
+    let mut errors = Vec::new();
+    if dataset.format != GENERIC_DATASET_FORMAT {
+        errors.push(AdapterError::InvalidField {
+            class_name: "dataset".to_string(),
+            field: "format",
+            message: format!("version {} is not supported (expected {})", dataset.format, GENERIC_DATASET_FORMAT),
+        });
+    }
+    errors.extend(duplicate_ids("items", dataset.items.iter().map(|i| i.id.as_str())));
+    errors.extend(duplicate_ids("machines", dataset.machines.iter().map(|m| m.id.as_str())));
+    errors.extend(duplicate_ids("recipes", dataset.recipes.iter().map(|r| r.id.as_str())));
+    let items: HashSet<&str> = dataset.items.iter().map(|i| i.id.as_str()).collect();
+    for resource in dataset.raw_resources.iter().filter(|r| !items.contains(r.as_str())) {
+        errors.push(AdapterError::UnknownItemClass {
+            class_name: "dataset".to_string(),
+            field: "raw_resources",
+            item_class: resource.clone(),
+        });
+    }
+    errors
+}
+
+fn check_stacks(
+    recipe: &GenericRecipe,
+    stacks: &[ItemStack],
+    items: &HashSet<&str>,
+    field: &'static str,
+) -> Result<(), AdapterError> {
+    for stack in stacks {
+        if !items.contains(stack.item.as_str()) {
+            return Err(AdapterError::UnknownItemClass {
+                class_name: recipe.id.clone(),
+                field,
+                item_class: stack.item.clone(),
+            });
+        }
+        if stack.quantity == 0 {
+            return Err(AdapterError::InvalidField {
+                class_name: recipe.id.clone(),
+                field,
+                message: format!("zero quantity of {}", stack.item),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn generic_recipe_to_recipe(
+    recipe: &GenericRecipe,
+    items: &HashSet<&str>,
+    machines: &HashMap<&str, &CraftingMachine>,
+) -> Result<Recipe, AdapterError> {
+    let machine = machines.get(recipe.machine.as_str()).ok_or_else(|| AdapterError::UnknownMachineClass {
+        class_name: recipe.id.clone(),
+        machine_class: recipe.machine.clone(),
+    })?;
+    if recipe.outputs.is_empty() {
+        return Err(AdapterError::MissingField {
+            class_name: recipe.id.clone(),
+            field: "outputs",
+        });
+    }
+    if recipe.time == 0 {
+        return Err(AdapterError::InvalidField {
+            class_name: recipe.id.clone(),
+            field: "time",
+            message: "zero duration".to_string(),
+        });
+    }
+    check_stacks(recipe, &recipe.inputs, items, "inputs")?;
+    check_stacks(recipe, &recipe.outputs, items, "outputs")?;
+    Ok(Recipe {
+        id: recipe.id.clone(),
+        name: recipe.name.clone(),
+        inputs: recipe.inputs.clone(),
+        outputs: recipe.outputs.clone(),
+        machine: (*machine).clone(),
+        time: recipe.time,
+        enabled: recipe.enabled,
+        source: None,
+    })
+}
+
+/// Every violation of the format's rules; empty for a valid dataset
+pub fn validate_generic_dataset(dataset: &GenericDataset) -> Vec<AdapterError> {
+    let items: HashSet<&str> = dataset.items.iter().map(|i| i.id.as_str()).collect();
+    let machines: HashMap<&str, &CraftingMachine> = dataset.machines.iter().map(|m| (m.id.as_str(), m)).collect();
+    let mut errors = dataset_errors(dataset);
+    errors.extend(
+        dataset
+            .recipes
+            .iter()
+            .filter_map(|r| generic_recipe_to_recipe(r, &items, &machines).err()),
+    );
+    errors
+}
+
+/// Convert a parsed dataset; dataset-level problems are errors, invalid recipes are skipped and reported as warnings
+pub fn load_generic_dataset(dataset: &GenericDataset) -> Result<LoadedRecipes, AdapterError> {
+    if let Some(error) = dataset_errors(dataset).into_iter().next() {
+        return Err(error);
+    }
+    let items: HashSet<&str> = dataset.items.iter().map(|i| i.id.as_str()).collect();
+    let machines: HashMap<&str, &CraftingMachine> = dataset.machines.iter().map(|m| (m.id.as_str(), m)).collect();
+    let mut loaded = LoadedRecipes {
+        items: dataset.items.iter().map(|i| Item { id: i.id.clone(), name: i.name.clone() }).collect(),
+        machines: dataset.machines.clone(),
+        raw_resources: dataset.raw_resources.clone(),
+        stack_sizes: dataset.items.iter().filter_map(|i| Some((i.id.clone(), i.stack_size?))).collect(),
+        ..Default::default()
+    };
+    for recipe in &dataset.recipes {
+        match generic_recipe_to_recipe(recipe, &items, &machines) {
+            Ok(recipe) => loaded.recipes.push(recipe),
+            Err(e) => loaded.warnings.push(e),
+        }
+    }
+    Ok(loaded)
+}
+
+/// Whether a document looks like a generic dataset: a JSON object with a `"format"` key, or TOML
+/// with a top-level `format` key before its first table. Only the text is scanned, nothing is parsed.
+pub fn is_generic_dataset(text: &str) -> bool {
+    let text = text.trim_start_matches('\u{FEFF}').trim_start();
+    if text.starts_with('{') {
+        return text
+            .match_indices("\"format\"")
+            .any(|(at, key)| text[at + key.len()..].trim_start().starts_with(':'));
+    }
+    text.lines()
+        .map(str::trim_start)
+        .take_while(|line| !line.starts_with('['))
+        .any(|line| line.strip_prefix("format").is_some_and(|rest| rest.trim_start().starts_with('=')))
+}
+
+pub struct GenericAdapter;
+
+impl GameAdapter for GenericAdapter {
+    fn id(&self) -> &'static str {
+        "generic"
+    }
+
+    fn name(&self) -> &'static str {
+        "Other (generic dataset)"
+    }
+
+    fn data_file(&self) -> &'static str {
+        "a dataset in the generic TOML or JSON format"
+    }
+
+    fn versions(&self) -> &'static [DatasetVersion] {
+        &[DatasetVersion { id: "1", name: "Format 1", bundled_file: None }]
+    }
+
+    fn detect(&self, text: &str) -> bool {
+        is_generic_dataset(text)
+    }
+
+    fn load(&self, text: &str, _version: &str) -> Result<LoadedRecipes, AdapterError> {
+        load_generic_dataset(&parse_generic_dataset(text)?)
+    }
+
+    /// The file's own units replace the adapter's defaults
+    fn dataset_from_text(&self, text: &str, version: &str, locale: &str) -> Result<CompiledDataset, AdapterError> {
+        let dataset = parse_generic_dataset(text)?;
+        Ok(CompiledDataset {
+            units: dataset.units.clone(),
+            ..load_generic_dataset(&dataset)?.into_dataset(self, version, locale)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const DATASET: &str = r#"
+        format = 1
+        name = "Captain of Industry"
+        raw_resources = ["iron_ore", "coal"]
+
+        [units]
+        rate = "items/60s"
+        power = "MW"
+
+        [[items]]
+        id = "iron_ore"
+        name = "Iron Ore"
+        stack_size = 50
+
+        [[items]]
+        id = "coal"
+        name = "Coal"
+        stack_size = 100
+
+        [[items]]
+        id = "iron"
+        name = "Molten Iron"
+
+        [[machines]]
+        id = "furnace"
+        name = "Blast Furnace"
+        power = 0.5
+
+        [[recipes]]
+        id = "iron_smelting"
+        name = "Iron Smelting"
+        machine = "furnace"
+        time = 20000
+        inputs = [{ item = "iron_ore", quantity = 12 }, { item = "coal", quantity = 3 }]
+        outputs = [{ item = "iron", quantity = 8 }]
+
+        [[recipes]]
+        id = "iron_smelting_slag"
+        name = "Iron Smelting (slag)"
+        machine = "furnace"
+        time = 20000
+        inputs = [{ item = "iron_ore", quantity = 12 }]
+        outputs = [{ item = "slag", quantity = 4 }]
+    "#;
+
+    #[test]
+    fn test_load_and_validate_generic_dataset() {
+        let dataset = parse_generic_dataset(DATASET).unwrap();
+        assert_eq!(
+            validate_generic_dataset(&dataset),
+            vec![AdapterError::UnknownItemClass {
+                class_name: "iron_smelting_slag".to_string(),
+                field: "outputs",
+                item_class: "slag".to_string(),
+            }]
+        );
+
+        let compiled = GenericAdapter.dataset_from_text(DATASET, "1", "en-US").unwrap();
+        assert_eq!(compiled.game, "generic");
+        assert_eq!(compiled.units.rate, "items/60s");
+        assert_eq!(compiled.recipes.len(), 1);
+        assert_eq!(compiled.recipes[0].machine.power, 0.5);
+        assert!(compiled.recipes[0].enabled);
+        assert_eq!(compiled.warnings.len(), 1);
+        // Molten iron is a fluid and has no stack size
+        assert_eq!(
+            compiled.stack_sizes,
+            BTreeMap::from([("coal".to_string(), 100), ("iron_ore".to_string(), 50)])
+        );
+
+        // The JSON form of the same dataset loads identically
+        let json = serde_json::to_string(&dataset).unwrap();
+        assert!(GenericAdapter.detect(&json));
+        assert!(GenericAdapter.detect(DATASET));
+        // A `format` inside a table, or only in a value, is not the header
+        assert!(!GenericAdapter.detect("[units]\nformat = 1"));
+        assert!(!GenericAdapter.detect(r#"{"name": "format"}"#));
+        assert_eq!(GenericAdapter.dataset_from_text(&json, "1", "en-US").unwrap(), compiled);
+
+        let mut duplicate = dataset.clone();
+        duplicate.format = 2;
+        duplicate.items.push(duplicate.items[0].clone());
+        let errors = validate_generic_dataset(&duplicate);
+        assert!(matches!(&errors[0], AdapterError::InvalidField { field: "format", .. }));
+        assert!(matches!(&errors[1], AdapterError::InvalidField { field: "items", .. }));
+        assert!(load_generic_dataset(&duplicate).is_err());
+    }
+}
@@ -1,6 +1,13 @@
 pub mod satisfactory_adapter;
 pub mod satisfactory_asset;
+pub mod satisfactory_docs;
+pub mod satisfactory_mods;
 pub mod factorio_adapter;
 pub mod dsp_adapter;
+pub mod encoding;
+pub mod game_adapter;
+pub mod generic_dataset;
+pub mod error;
+pub mod dataset_file;
 
 // Only keep generic and placeholder logic here. All Satisfactory/Factorio/DSP-specific code has been moved to their respective modules.
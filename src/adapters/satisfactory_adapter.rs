@@ -2,12 +2,18 @@
 // (No need to pub use crate::satisfactory_adapter;)
 
 // Satisfactory adapter logic moved from adapters.rs
-use crate::model::recipe::{CraftingMachine, ItemStack, Recipe};
-use regex;
+use crate::adapters::error::{AdapterError, LoadedRecipes};
+use crate::adapters::game_adapter::{DatasetVersion, GameAdapter};
+use crate::model::dataset::CompiledDataset;
+use crate::model::localization::DatasetLabels;
+use crate::model::recipe::{CraftingMachine, Item, ItemStack, Recipe, Schematic};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::adapters::satisfactory_asset::SatisfactoryAsset;
+use crate::adapters::encoding::decode_text;
+use crate::adapters::satisfactory_asset::{parse_satisfactory_assets, SatisfactoryAsset};
+use crate::adapters::satisfactory_mods::{load_satisfactory_recipes_from_layers, DocsLayer};
+use crate::adapters::satisfactory_docs::{DocsItem, DocsManufacturer, DocsRecipe, DocsSchematic, SatisfactoryDocs};
 
 #[derive(Debug, Deserialize)]
 pub struct SatisfactoryJsonIngredient {
@@ -29,55 +35,13 @@ pub struct SatisfactoryJsonRecipe {
     pub alternate: Option<bool>, // Add this field to match the JSON
 }
 
-fn de_str_or_float_opt<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    use serde::de::Error;
-    let val: Option<serde_json::Value> = Option::deserialize(deserializer).unwrap_or(None);
-    match val {
-        Some(serde_json::Value::String(s)) => s.parse::<f64>().map(Some).map_err(Error::custom),
-        Some(serde_json::Value::Number(n)) => n
-            .as_f64()
-            .ok_or_else(|| Error::custom("Invalid number"))
-            .map(Some),
-        Some(serde_json::Value::Null) | None => Ok(None),
-        _ => Err(Error::custom(
-            "Expected string or float for mManufactoringDuration",
-        )),
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct SatisfactoryClass {
-    #[serde(rename = "ClassName")]
-    pub class_name: String,
-    #[serde(rename = "mDisplayName")]
-    pub display_name: Option<String>,
-    #[serde(rename = "mIngredients")]
-    pub m_ingredients: Option<String>,
-    #[serde(rename = "mProduct")]
-    pub m_product: Option<String>,
-    #[serde(rename = "mProducedIn")]
-    pub m_produced_in: Option<String>,
-    #[serde(
-        rename = "mManufactoringDuration",
-        default,
-        deserialize_with = "de_str_or_float_opt"
-    )]
-    pub m_manufactoring_duration: Option<f64>,
-    #[serde(rename = "mPowerConsumption")]
-    pub m_power_consumption: Option<String>,
-}
-
-#[derive(Debug, Clone)]
-pub struct SatisfactoryIngredientEntry {
-    pub item_class: String,
-    pub amount: f64,
-}
-
-pub fn satisfactory_json_to_recipe(json: &SatisfactoryJsonRecipe) -> Recipe {
-    Recipe {
+pub fn satisfactory_json_to_recipe(json: &SatisfactoryJsonRecipe) -> Result<Recipe, AdapterError> {
+    let machine = json.produced_in.first().ok_or_else(|| AdapterError::MissingField {
+        class_name: json.class_name.clone(),
+        field: "producedIn",
+    })?;
+    Ok(Recipe {
+        id: json.class_name.clone(),
         name: json.display_name.clone(),
         inputs: json
             .ingredients
@@ -96,205 +60,263 @@ pub fn satisfactory_json_to_recipe(json: &SatisfactoryJsonRecipe) -> Recipe {
             })
             .collect(),
         machine: CraftingMachine {
-            name: json
-                .produced_in
-                .get(0)
-                .unwrap().to_string(),
+            id: machine.to_string(),
+            name: machine.to_string(),
+            power: 0.0,
         },
         time: (json.duration * 1000.0) as u32,
         enabled: !json.alternate.unwrap_or(false), // Default: alternate recipes are disabled
-    }
+        source: None,
+    })
 }
 
+/// Display name by class name, over every class of every category
 pub fn build_display_name_map_from_assets(assets: &[SatisfactoryAsset]) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for asset in assets {
         for class in &asset.classes {
-            if let Some(name) = class.display_name.clone() {
-                let short = class.class_name.clone();
-                map.insert(short.clone(), name.clone());
-                // Add all possible suffixes ending with the class name
-                // e.g. .../Desc_IronIngot.Desc_IronIngot_C'
-                let class_name = class.class_name.clone();
-                for prefix in [
-                    "/Script/Engine.BlueprintGeneratedClass'",
-                    "",
-                    "/Game/FactoryGame/Resource/Parts/",
-                    "/Game/FactoryGame/Resource/RawResources/",
-                    "/Game/FactoryGame/Buildable/Factory/",
-                    "/Script/FactoryGame.",
-                ] {
-                    let full = format!("{}{}", prefix, class_name);
-                    map.insert(full.clone(), name.clone());
-                    // Also try with trailing single quote
-                    map.insert(format!("{}'", full), name.clone());
-                }
-                // Also map any substring ending with the class name (for robust fallback)
-                // e.g. ...Desc_IronIngot_C'
-                map.insert(format!("{}'", class_name), name.clone());
+            let class_name = class.get("ClassName").and_then(|v| v.as_str());
+            let display_name = class.get("mDisplayName").and_then(|v| v.as_str());
+            if let (Some(class_name), Some(display_name)) = (class_name, display_name) {
+                map.insert(class_name.to_string(), display_name.to_string());
             }
         }
     }
     map
 }
 
-fn calc_quantity(amount: u32) -> u32 {
-    if amount >= 1000 { //fluids
-        amount / 1000
-    } else {
-        amount
+/// Producers of hand-crafted and build gun recipes; they are engine components, not Docs classes
+const MANUAL_PRODUCERS: [&str; 4] = ["BP_WorkBenchComponent_C", "BP_WorkshopComponent_C", "BP_BuildGun_C", "FGBuildGun"];
+
+/// Resolve ingredient entries to item stacks keyed by class id, failing on the first unknown item class.
+/// Fluid amounts are converted from the Docs' thousandths to cubic metres.
+fn resolve_item_stacks(
+    entries: &[(String, f64)],
+    items: &HashMap<&str, &DocsItem>,
+    class_name: &str,
+    field: &'static str,
+) -> Result<Vec<ItemStack>, AdapterError> {
+    entries
+        .iter()
+        .map(|(item_class, amount)| {
+            let item = items.get(item_class.as_str()).ok_or_else(|| AdapterError::UnknownItemClass {
+                class_name: class_name.to_string(),
+                field,
+                item_class: item_class.clone(),
+            })?;
+            let amount = if item.form.is_fluid() { amount / 1000.0 } else { *amount };
+            Ok(ItemStack {
+                item: item_class.clone(),
+                quantity: amount.round() as u32,
+            })
+        })
+        .collect()
+}
+
+/// Convert one recipe, or `Ok(None)` when it is only built by hand or by the build gun
+fn docs_recipe_to_recipe(
+    recipe: &DocsRecipe,
+    items: &HashMap<&str, &DocsItem>,
+    manufacturers: &HashMap<&str, &DocsManufacturer>,
+    alternate_recipes: &HashSet<&str>,
+) -> Result<Option<Recipe>, AdapterError> {
+    let machine_class = recipe
+        .produced_in
+        .iter()
+        .find(|p| !MANUAL_PRODUCERS.contains(&p.as_str()));
+    let Some(machine_class) = machine_class else {
+        return Ok(None); // skip if only workbench/workshop
+    };
+    let machine = manufacturers
+        .get(machine_class.as_str())
+        .ok_or_else(|| AdapterError::UnknownMachineClass {
+            class_name: recipe.class_name.clone(),
+            machine_class: machine_class.clone(),
+        })?;
+    if recipe.duration <= 0.0 {
+        return Err(AdapterError::InvalidField {
+            class_name: recipe.class_name.clone(),
+            field: "mManufactoringDuration",
+            message: format!("non-positive duration {}", recipe.duration),
+        });
     }
+    // Variable power buildings draw between constant and constant + factor over a cycle
+    let power = match recipe.variable_power {
+        Some((constant, factor)) => constant + factor / 2.0,
+        None => machine.power_consumption,
+    };
+    Ok(Some(Recipe {
+        id: recipe.class_name.clone(),
+        name: recipe.display_name.clone(),
+        inputs: resolve_item_stacks(&recipe.ingredients, items, &recipe.class_name, "mIngredients")?,
+        outputs: resolve_item_stacks(&recipe.products, items, &recipe.class_name, "mProduct")?,
+        machine: CraftingMachine {
+            id: machine.class_name.clone(),
+            name: machine.display_name.clone(),
+            power,
+        },
+        time: (recipe.duration * 1000.0) as u32,
+        // Default: alternate recipes are disabled
+        enabled: !alternate_recipes.contains(recipe.class_name.as_str()) && !recipe.display_name.starts_with("Alternate"),
+        source: None,
+    }))
 }
 
-pub fn load_satisfactory_recipes_from_json(
-    json_str: &str,
-) -> Result<Vec<Recipe>, Box<dyn std::error::Error>> {
-    let assets: Vec<SatisfactoryAsset> = serde_json::from_str(json_str)?;
-    let display_name_map = build_display_name_map_from_assets(&assets);
-    let mut recipes = Vec::new();
-    for asset in assets {
-        for class in asset.classes {
-            if class.class_name.starts_with("Recipe_") {
-                let machine_classes = class
-                    .m_produced_in
-                    .as_ref()
-                    .map(|s| parse_produced_in_tuple(s))
-                    .unwrap_or_default();
-                let filtered_machines: Vec<_> = machine_classes
-                    .iter()
-                    .filter(|mc| {
-                        *mc != "BP_WorkBenchComponent_C"
-                            && *mc != "BP_WorkshopComponent_C"
-                            && *mc != "BP_BuildGun_C"
-                            && *mc != "FGBuildGun"
-                    })
-                    .collect();
-                if filtered_machines.is_empty() {
-                    continue; // skip if only workbench/workshop
-                }
-                let machine_name = filtered_machines
-                    .get(0)
-                    .and_then(|mc| display_name_map.get(*mc).cloned())
-                    .unwrap_or_default();
-                let recipe_display = class
-                    .display_name
-                    .clone()
-                    .unwrap_or_else(|| class.class_name.clone());
-                let inputs = class
-                    .m_ingredients
-                    .as_ref()
-                    .map(|s| parse_ingredient_tuples(s))
-                    .unwrap_or_default();
-                let outputs = class
-                    .m_product
-                    .as_ref()
-                    .map(|s| parse_ingredient_tuples(s))
-                    .unwrap_or_default();
-                let time = class.m_manufactoring_duration.unwrap_or(1.0);
-                recipes.push(Recipe {
-                    name: recipe_display.clone(),
-                    inputs: inputs
-                        .iter()
-                        .map(|ing| {
-                            let item_name = display_name_map
-                                .get(&ing.item_class)
-                                .cloned()
-                                .unwrap();
-                            ItemStack {
-                                item: item_name.clone(),
-                                quantity: calc_quantity( ing.amount.round() as u32),
-                            }
-                        })
-                        .collect(),
-                    outputs: outputs
-                        .iter()
-                        .map(|prod| {
-                            let item_name = display_name_map
-                                .get(&prod.item_class)
-                                .cloned()
-                                .unwrap();
-                            ItemStack {
-                                item: item_name.clone(),
-                                quantity: calc_quantity( prod.amount.round() as u32),
-                            }
-                        })
-                        .collect(),
-                    machine: CraftingMachine { name: machine_name },
-                    time: (time * 1000.0) as u32,
-                    enabled: recipe_display.starts_with("Alternate") == false, // Default: alternate recipes are disabled
-                });
-            }
-        }
+fn docs_schematic_to_schematic(schematic: &DocsSchematic) -> Schematic {
+    Schematic {
+        id: schematic.class_name.clone(),
+        name: schematic.display_name.clone(),
+        tier: schematic.tech_tier,
+        unlocked_recipes: schematic.unlocked_recipes.clone(),
     }
-    Ok(recipes)
 }
 
-fn extract_short_class_name(item_class: &str) -> String {
-    // Handles both Unreal path and plain class name
-    if let Some(pos) = item_class.rfind('/') {
-        let after_slash = &item_class[pos + 1..];
-        if let Some(dot_pos) = after_slash.rfind('.') {
-            after_slash[dot_pos + 1..].to_string()
-        } else {
-            after_slash.to_string()
+/// Convert parsed Docs classes to the recipe model; classes that fail to read or convert are skipped and reported as warnings
+pub fn load_satisfactory_recipes_from_docs(docs: &SatisfactoryDocs) -> LoadedRecipes {
+    let items: HashMap<&str, &DocsItem> = docs.all_items().map(|i| (i.class_name.as_str(), i)).collect();
+    let manufacturers: HashMap<&str, &DocsManufacturer> =
+        docs.manufacturers.iter().map(|m| (m.class_name.as_str(), m)).collect();
+    let alternate_recipes: HashSet<&str> = docs
+        .schematics
+        .iter()
+        .filter(|s| s.is_alternate())
+        .flat_map(|s| s.unlocked_recipes.iter().map(String::as_str))
+        .collect();
+    let mut loaded = LoadedRecipes {
+        warnings: docs.warnings.clone(),
+        ..Default::default()
+    };
+    for recipe in &docs.recipes {
+        match docs_recipe_to_recipe(recipe, &items, &manufacturers, &alternate_recipes) {
+            Ok(Some(recipe)) => loaded.recipes.push(recipe),
+            Ok(None) => {}
+            Err(e) => loaded.warnings.push(e),
         }
-    } else if let Some(dot_pos) = item_class.rfind('.') {
-        item_class[dot_pos + 1..].to_string()
-    } else {
-        item_class.to_string()
     }
+    loaded.schematics = docs.schematics.iter().map(docs_schematic_to_schematic).collect();
+    loaded.raw_resources = docs.resources.iter().map(|r| r.class_name.clone()).collect();
+    loaded.raw_resources.sort();
+    let mut machines: HashMap<&str, &CraftingMachine> = HashMap::new();
+    for recipe in &loaded.recipes {
+        machines.entry(recipe.machine.id.as_str()).or_insert(&recipe.machine);
+    }
+    let mut machines: Vec<CraftingMachine> = machines.into_values().cloned().collect();
+    machines.sort_by(|a, b| a.id.cmp(&b.id));
+    loaded.machines = machines;
+    let mut item_ids: Vec<&String> = loaded
+        .recipes
+        .iter()
+        .flat_map(|r| r.inputs.iter().chain(r.outputs.iter()).map(|s| &s.item))
+        .collect();
+    item_ids.sort();
+    item_ids.dedup();
+    loaded.items = item_ids
+        .into_iter()
+        .map(|id| Item {
+            id: id.clone(),
+            name: items.get(id.as_str()).map(|i| i.display_name.clone()).unwrap_or_else(|| id.clone()),
+        })
+        .collect();
+    loaded.stack_sizes = loaded
+        .items
+        .iter()
+        .filter_map(|i| items.get(i.id.as_str()).and_then(|d| d.stack_size).map(|size| (i.id.clone(), size)))
+        .collect();
+    loaded
 }
 
-fn parse_ingredient_tuples(s: &str) -> Vec<SatisfactoryIngredientEntry> {
-    // Example: ((ItemClass="...",Amount=3),(ItemClass="...",Amount=2))
-    let mut entries = Vec::new();
-    let re = regex::Regex::new(r#"ItemClass=\\?\"([^\"]+)\\?\",Amount=([0-9.]+)"#).unwrap();
-    for cap in re.captures_iter(s) {
-        let item_class_full = &cap[1];
-        let item_class = extract_short_class_name(item_class_full);
-        let amount: f64 = cap[2].parse().unwrap_or(1.0);
-        entries.push(SatisfactoryIngredientEntry { item_class, amount });
-    }
-    entries
+/// Load every recipe from a Docs JSON document; recipes that fail to convert are skipped and reported as warnings
+pub fn load_satisfactory_recipes_from_json(json_str: &str) -> Result<LoadedRecipes, AdapterError> {
+    let assets = parse_satisfactory_assets(json_str)?;
+    Ok(load_satisfactory_recipes_from_docs(&SatisfactoryDocs::from_assets(&assets)))
 }
 
-fn parse_produced_in_tuple(s: &str) -> Vec<String> {
-    // Match everything after the last dot to the end of the string, for each entry inside parentheses
-    let trimmed = s.trim();
-    let mut result = Vec::new();
-    if trimmed.starts_with('(') && trimmed.ends_with(')') {
-        let inner = &trimmed[1..trimmed.len() - 1];
-        for part in inner.split(',') {
-            let part = part.trim().trim_matches('"');
-            if let Some(dot_pos) = part.rfind('.') {
-                let class_name = &part[dot_pos + 1..];
-                if !class_name.is_empty() {
-                    result.push(class_name.to_string());
-                }
-            } else if !part.is_empty() {
-                result.push(part.to_string());
-            }
-        }
+/// Load recipes from a Docs file's raw bytes, whatever its text encoding
+pub fn load_satisfactory_recipes_from_bytes(bytes: &[u8]) -> Result<LoadedRecipes, AdapterError> {
+    load_satisfactory_recipes_from_json(&decode_text(bytes)?)
+}
+
+/// Whether a document has a Docs layout: groups of classes that each have a `ClassName`, in any
+/// layout `parse_satisfactory_assets` accepts. Merely mentioning the game's paths is not enough.
+pub fn is_satisfactory_docs(json_str: &str) -> bool {
+    if !json_str.contains("Class") {
+        return false;
     }
-    result
+    let Ok(assets) = parse_satisfactory_assets(json_str) else {
+        return false;
+    };
+    let named = |class: &serde_json::Value| class.get("ClassName").is_some_and(serde_json::Value::is_string);
+    assets.iter().all(|a| a.classes.iter().all(named))
+        && assets.iter().any(|a| a.native_class.is_some() || !a.classes.is_empty())
 }
 
-/// Parse machine power consumption from Satisfactory assets
-pub fn build_machine_power_map_from_assets(assets: &[SatisfactoryAsset]) -> std::collections::HashMap<String, f64> {
-    let mut map = std::collections::HashMap::new();
-    for asset in assets {
-        for class in &asset.classes {
-            if let Some(power_str) = &class.m_power_consumption {
-                if let Ok(power) = power_str.parse::<f64>() {
-                    if let Some(name) = &class.display_name {
-                        map.insert(name.clone(), power);
-                    }
-                }
-            }
+pub struct SatisfactoryAdapter;
+
+impl GameAdapter for SatisfactoryAdapter {
+    fn id(&self) -> &'static str {
+        "satisfactory"
+    }
+
+    fn name(&self) -> &'static str {
+        "Satisfactory"
+    }
+
+    fn data_file(&self) -> &'static str {
+        "a Docs file, e.g. CommunityResources/Docs/en-US.json"
+    }
+
+    fn versions(&self) -> &'static [DatasetVersion] {
+        // The bundled file is `compiled_dataset_file(DEFAULT_LOCALE)`, written by `build_dataset`
+        &[DatasetVersion { id: "1.0", name: "1.0", bundled_file: Some("satisfactory_en-US.dataset") }]
+    }
+
+    fn detect(&self, text: &str) -> bool {
+        is_satisfactory_docs(text)
+    }
+
+    fn load(&self, text: &str, _version: &str) -> Result<LoadedRecipes, AdapterError> {
+        load_satisfactory_recipes_from_json(text)
+    }
+
+    fn supports_mods(&self) -> bool {
+        true
+    }
+
+    fn load_with_mods(&self, text: &str, mods: &[(String, String)], _version: &str) -> Result<LoadedRecipes, AdapterError> {
+        let mut layers = vec![DocsLayer::parse(None, text)?];
+        for (name, mod_text) in mods {
+            layers.push(DocsLayer::parse(Some(name.clone()), mod_text)?);
         }
+        Ok(load_satisfactory_recipes_from_layers(&layers))
     }
-    println!("{:?}", map);
-    map
+}
+
+/// Compile a Docs file's raw bytes into the normalized dataset loaded by the app and CLI
+pub fn compile_satisfactory_dataset(bytes: &[u8], locale: &str) -> Result<CompiledDataset, AdapterError> {
+    SatisfactoryAdapter.load_dataset(bytes, SatisfactoryAdapter.default_version().id, locale)
+}
+
+/// Load only the display names of a (possibly localized) Docs file, keyed by the same class ids as the recipes
+pub fn load_satisfactory_labels_from_json(json_str: &str) -> Result<DatasetLabels, AdapterError> {
+    let assets = parse_satisfactory_assets(json_str)?;
+    Ok(DatasetLabels {
+        names: build_display_name_map_from_assets(&assets),
+    })
+}
+
+/// Load the display names from a Docs file's raw bytes, whatever its text encoding
+pub fn load_satisfactory_labels_from_bytes(bytes: &[u8]) -> Result<DatasetLabels, AdapterError> {
+    load_satisfactory_labels_from_json(&decode_text(bytes)?)
+}
+
+/// Machine power consumption (MW) of manufacturers and extractors by class name
+pub fn build_machine_power_map_from_assets(assets: &[SatisfactoryAsset]) -> HashMap<String, f64> {
+    let docs = SatisfactoryDocs::from_assets(assets);
+    docs.manufacturers
+        .iter()
+        .map(|m| (m.class_name.clone(), m.power_consumption))
+        .chain(docs.extractors.iter().map(|e| (e.class_name.clone(), e.power_consumption)))
+        .collect()
 }
 
 #[cfg(test)]
@@ -305,15 +327,14 @@ mod tests {
         let path = "assets/satisfactory_en-US.json";
         let json_str = std::fs::read_to_string(path).expect("Failed to read JSON file");
         let recipes =
-            load_satisfactory_recipes_from_json(&json_str).expect("Failed to load recipes");
-        let assets: Vec<SatisfactoryAsset> =
-            serde_json::from_str(&json_str).expect("Failed to parse JSON");
+            load_satisfactory_recipes_from_json(&json_str).expect("Failed to load recipes").recipes;
+        let assets = parse_satisfactory_assets(&json_str).expect("Failed to parse JSON");
         let display_name_map = build_display_name_map_from_assets(&assets);
         let mut recipes = recipes;
         use std::collections::HashMap;
         recipes.sort_by(|a, b| {
-            let a_name = a.outputs.get(0).map(|o| o.item.as_str()).unwrap_or("");
-            let b_name = b.outputs.get(0).map(|o| o.item.as_str()).unwrap_or("");
+            let a_name = a.outputs.first().map(|o| o.item.as_str()).unwrap_or("");
+            let b_name = b.outputs.first().map(|o| o.item.as_str()).unwrap_or("");
             a_name.cmp(b_name)
         });
         println!(
@@ -368,17 +389,80 @@ mod tests {
         }
         println!("\nRecipe count per building type:");
         let mut building_counts_vec: Vec<_> = building_counts.into_iter().collect();
-        building_counts_vec.sort_by(|a, b| b.1.cmp(&a.1));
+        building_counts_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
         for (building, count) in building_counts_vec {
             println!("{:<30} : {}", building, count);
         }
     }
 
+    #[test]
+    fn test_unknown_item_class_skips_recipe() {
+        let json_str = r#"[
+        {"NativeClass": "FGItemDescriptor", "Classes": [
+            {"ClassName": "Desc_IronIngot_C", "mDisplayName": "Iron Ingot", "mForm": "RF_SOLID"}
+        ]},
+        {"NativeClass": "FGBuildableManufacturer", "Classes": [
+            {"ClassName": "Build_ConstructorMk1_C", "mDisplayName": "Constructor", "mPowerConsumption": "4.000000"}
+        ]},
+        {"NativeClass": "FGRecipe", "Classes": [
+            {"ClassName": "Recipe_IronRod_C", "mDisplayName": "Iron Rod",
+             "mIngredients": "((ItemClass=\"/Game/FactoryGame/Resource/Parts/IronIngot/Desc_IronIngot.Desc_IronIngot_C\",Amount=1))",
+             "mProduct": "((ItemClass=\"/Game/FactoryGame/Resource/Parts/IronRod/Desc_IronRod.Desc_IronRod_C\",Amount=1))",
+             "mProducedIn": "(\"/Game/FactoryGame/Buildable/Factory/ConstructorMk1/Build_ConstructorMk1.Build_ConstructorMk1_C\")",
+             "mManufactoringDuration": "4.000000"}
+        ]}]"#;
+        let loaded = load_satisfactory_recipes_from_json(json_str).expect("document should parse");
+        assert!(loaded.recipes.is_empty());
+        assert_eq!(
+            loaded.warnings,
+            vec![AdapterError::UnknownItemClass {
+                class_name: "Recipe_IronRod_C".to_string(),
+                field: "mProduct",
+                item_class: "Desc_IronRod_C".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_schematic_unlocks() {
+        let json_str = r#"[{"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGSchematic'", "Classes": [
+            {"ClassName": "Schematic_1-1_C", "mDisplayName": "Base Building", "mTechTier": "1",
+             "mUnlocks": [
+                {"Class": "BP_UnlockRecipe_C", "mRecipes": "(\"/Script/Engine.BlueprintGeneratedClass'/Game/FactoryGame/Recipes/Buildings/Recipe_ConstructorMk1.Recipe_ConstructorMk1_C'\",\"/Script/Engine.BlueprintGeneratedClass'/Game/FactoryGame/Recipes/Constructor/Recipe_IronRod.Recipe_IronRod_C'\")"},
+                {"Class": "BP_UnlockInventorySlot_C", "mNumInventorySlotsToUnlock": "3"}
+             ]}
+        ]}]"#;
+        let loaded = load_satisfactory_recipes_from_json(json_str).expect("document should parse");
+        assert_eq!(
+            loaded.schematics,
+            vec![Schematic {
+                id: "Schematic_1-1_C".to_string(),
+                name: "Base Building".to_string(),
+                tier: 1,
+                unlocked_recipes: vec!["Recipe_ConstructorMk1_C".to_string(), "Recipe_IronRod_C".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_raw_resources_from_resource_descriptors() {
+        let json_str = r#"[
+        {"NativeClass": "FGResourceDescriptor", "Classes": [
+            {"ClassName": "Desc_OreIron_C", "mDisplayName": "Iron Ore", "mForm": "RF_SOLID"},
+            {"ClassName": "Desc_OreModded_C", "mDisplayName": "Modded Ore", "mForm": "RF_SOLID"}
+        ]},
+        {"NativeClass": "FGItemDescriptor", "Classes": [
+            {"ClassName": "Desc_IronIngot_C", "mDisplayName": "Iron Ingot", "mForm": "RF_SOLID"}
+        ]}]"#;
+        let loaded = load_satisfactory_recipes_from_json(json_str).expect("document should parse");
+        assert_eq!(loaded.raw_resources, vec!["Desc_OreIron_C".to_string(), "Desc_OreModded_C".to_string()]);
+    }
+
     #[test]
     fn test_machine_power_map() {
         let path = "assets/satisfactory_en-US.json";
         let json_str = std::fs::read_to_string(path).expect("Failed to read JSON file");
-        let assets: Vec<SatisfactoryAsset> = serde_json::from_str(&json_str).expect("Failed to parse JSON");
+        let assets = parse_satisfactory_assets(&json_str).expect("Failed to parse JSON");
         let machine_power_map = build_machine_power_map_from_assets(&assets);
         assert!(!machine_power_map.is_empty(), "Machine power map should not be empty");
         // Print a few entries for manual inspection
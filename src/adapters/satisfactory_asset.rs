@@ -1,7 +1,122 @@
 use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::encoding::decode_text;
+use super::error::AdapterError;
 
 #[derive(Debug, Deserialize)]
 pub struct SatisfactoryAsset {
-    #[serde(rename = "Classes")]
-    pub classes: Vec<super::satisfactory_adapter::SatisfactoryClass>,
+    #[serde(rename = "NativeClass", alias = "nativeClass", default)]
+    pub native_class: Option<String>,
+    /// Raw classes; `SatisfactoryDocs` reads the ones of the categories the planner uses
+    #[serde(rename = "Classes", alias = "classes")]
+    pub classes: Vec<serde_json::Value>,
+}
+
+/// Top-level layouts seen in Docs files across game versions and export tools
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SatisfactoryDocsLayout {
+    /// `[{"NativeClass": ..., "Classes": [...]}, ...]`, as shipped by the game
+    Assets(Vec<SatisfactoryAsset>),
+    /// A single `{"NativeClass": ..., "Classes": [...]}` group
+    Single(SatisfactoryAsset),
+    /// `{"<NativeClass>": [...], ...}`, as written by some export tools
+    ByNativeClass(HashMap<String, Vec<serde_json::Value>>),
+}
+
+/// Parse a Docs document in any supported layout
+pub fn parse_satisfactory_assets(json_str: &str) -> Result<Vec<SatisfactoryAsset>, AdapterError> {
+    let json_str = json_str.trim_start_matches('\u{FEFF}');
+    // Parse to a value first so syntax errors keep their position instead of the untagged enum's message
+    let value: serde_json::Value = serde_json::from_str(json_str)?;
+    let layout = SatisfactoryDocsLayout::deserialize(value).map_err(|e| AdapterError::Parse {
+        message: format!("unrecognized Docs layout: {}", e),
+    })?;
+    Ok(match layout {
+        SatisfactoryDocsLayout::Assets(assets) => assets,
+        SatisfactoryDocsLayout::Single(asset) => vec![asset],
+        SatisfactoryDocsLayout::ByNativeClass(map) => map
+            .into_iter()
+            .map(|(native_class, classes)| SatisfactoryAsset {
+                native_class: Some(native_class),
+                classes,
+            })
+            .collect(),
+    })
+}
+
+/// Parse a Docs file from raw bytes in UTF-8, UTF-8 with BOM, or UTF-16LE/BE, as copied from the game install
+pub fn parse_satisfactory_assets_from_bytes(bytes: &[u8]) -> Result<Vec<SatisfactoryAsset>, AdapterError> {
+    parse_satisfactory_assets(&decode_text(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE_CLASS: &str = "/Script/CoreUObject.Class'/Script/FactoryGame.FGRecipe'";
+
+    /// Native class and class names of each group, sorted since the by-class layout is a map
+    fn groups(assets: &[SatisfactoryAsset]) -> Vec<(Option<String>, Vec<String>)> {
+        let mut groups: Vec<_> = assets
+            .iter()
+            .map(|a| (a.native_class.clone(), a.classes.iter().map(|c| c["ClassName"].as_str().unwrap().to_string()).collect()))
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    #[test]
+    fn test_game_layout() {
+        let json = format!(
+            r#"[{{"NativeClass": "{0}", "Classes": [{{"ClassName": "Recipe_IngotIron_C"}}]}},
+                {{"nativeClass": "{0}", "classes": [{{"ClassName": "Recipe_IronPlate_C"}}]}}]"#,
+            RECIPE_CLASS
+        );
+        assert_eq!(
+            groups(&parse_satisfactory_assets(&json).unwrap()),
+            vec![
+                (Some(RECIPE_CLASS.to_string()), vec!["Recipe_IngotIron_C".to_string()]),
+                (Some(RECIPE_CLASS.to_string()), vec!["Recipe_IronPlate_C".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_group_layout() {
+        // Without a native class, with a BOM as some editors save it
+        let json = "\u{FEFF}{\"Classes\": [{\"ClassName\": \"Recipe_IngotIron_C\"}]}";
+        assert_eq!(groups(&parse_satisfactory_assets(json).unwrap()), vec![(None, vec!["Recipe_IngotIron_C".to_string()])]);
+    }
+
+    #[test]
+    fn test_by_native_class_layout() {
+        let json = format!(
+            r#"{{"{}": [{{"ClassName": "Recipe_IngotIron_C"}}, {{"ClassName": "Recipe_IronPlate_C"}}],
+                "FGItemDescriptor": [{{"ClassName": "Desc_IronIngot_C"}}]}}"#,
+            RECIPE_CLASS
+        );
+        assert_eq!(
+            groups(&parse_satisfactory_assets(&json).unwrap()),
+            vec![
+                (Some(RECIPE_CLASS.to_string()), vec!["Recipe_IngotIron_C".to_string(), "Recipe_IronPlate_C".to_string()]),
+                (Some("FGItemDescriptor".to_string()), vec!["Desc_IronIngot_C".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_utf16_bytes_and_unknown_layout() {
+        // The game ships its Docs files as UTF-16LE with a BOM
+        let json = r#"[{"NativeClass": "FGItemDescriptor", "Classes": [{"ClassName": "Desc_IronIngot_C"}]}]"#;
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(json.encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(parse_satisfactory_assets_from_bytes(&bytes).unwrap()[0].classes.len(), 1);
+
+        assert!(matches!(
+            parse_satisfactory_assets(r#"{"Classes": 3}"#),
+            Err(AdapterError::Parse { message }) if message.starts_with("unrecognized Docs layout")
+        ));
+    }
 }
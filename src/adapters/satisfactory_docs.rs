@@ -0,0 +1,525 @@
+//! Typed model of the Docs NativeClass categories the planner uses.
+//!
+//! Classes are sorted by the NativeClass group they appear in, never by their class name, and
+//! keep the game fields later features need. Groups of any other NativeClass are ignored.
+
+use serde_json::Value;
+
+use crate::adapters::error::AdapterError;
+use crate::adapters::satisfactory_asset::SatisfactoryAsset;
+
+/// Docs categories the planner reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocsCategory {
+    Item,
+    Resource,
+    Manufacturer,
+    Generator,
+    Extractor,
+    Schematic,
+    Recipe,
+}
+
+/// Category of a NativeClass, given either the full `/Script/CoreUObject.Class'/Script/FactoryGame.FGRecipe'`
+/// reference or just `FGRecipe`
+pub fn docs_category(native_class: &str) -> Option<DocsCategory> {
+    match extract_short_class_name(native_class).as_str() {
+        "FGRecipe" => Some(DocsCategory::Recipe),
+        "FGSchematic" => Some(DocsCategory::Schematic),
+        "FGResourceDescriptor" => Some(DocsCategory::Resource),
+        "FGItemDescriptor"
+        | "FGItemDescriptorBiomass"
+        | "FGItemDescriptorNuclearFuel"
+        | "FGItemDescriptorPowerBoosterFuel"
+        | "FGPowerShardDescriptor"
+        | "FGConsumableDescriptor"
+        | "FGEquipmentDescriptor"
+        | "FGAmmoTypeProjectile"
+        | "FGAmmoTypeSpreadshot"
+        | "FGAmmoTypeInstantHit" => Some(DocsCategory::Item),
+        "FGBuildableManufacturer" | "FGBuildableManufacturerVariablePower" => Some(DocsCategory::Manufacturer),
+        "FGBuildableGeneratorFuel" | "FGBuildableGeneratorNuclear" | "FGBuildableGeneratorGeoThermal" => {
+            Some(DocsCategory::Generator)
+        }
+        "FGBuildableResourceExtractor"
+        | "FGBuildableWaterPump"
+        | "FGBuildableFrackingExtractor"
+        | "FGBuildableFrackingActivator" => Some(DocsCategory::Extractor),
+        _ => None,
+    }
+}
+
+/// Category of a class in a group without a NativeClass, from the fields only that category has
+fn category_from_fields(class: &Value) -> Option<DocsCategory> {
+    let has = |field: &str| class.get(field).is_some();
+    if has("mProduct") {
+        Some(DocsCategory::Recipe)
+    } else if has("mUnlocks") {
+        Some(DocsCategory::Schematic)
+    } else if has("mStackSize") {
+        Some(DocsCategory::Item)
+    } else if has("mExtractCycleTime") {
+        Some(DocsCategory::Extractor)
+    } else if has("mPowerProduction") {
+        Some(DocsCategory::Generator)
+    } else if has("mManufacturingSpeed") {
+        Some(DocsCategory::Manufacturer)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemForm {
+    Solid,
+    Liquid,
+    Gas,
+    Invalid,
+}
+
+impl ItemForm {
+    fn from_docs(value: Option<&str>) -> Self {
+        match value {
+            Some("RF_SOLID") => ItemForm::Solid,
+            Some("RF_LIQUID") => ItemForm::Liquid,
+            Some("RF_GAS") => ItemForm::Gas,
+            _ => ItemForm::Invalid,
+        }
+    }
+
+    /// Fluids are stored in the Docs in thousandths of a cubic metre
+    pub fn is_fluid(self) -> bool {
+        matches!(self, ItemForm::Liquid | ItemForm::Gas)
+    }
+}
+
+/// An item or resource descriptor
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsItem {
+    pub class_name: String,
+    pub display_name: String,
+    pub description: String,
+    /// Items per inventory slot; fluids have none
+    pub stack_size: Option<u32>,
+    pub sink_points: u32,
+    /// Energy in MJ per item (or per m³ for fluids) when burned
+    pub energy_value: f64,
+    pub form: ItemForm,
+    pub radioactive_decay: f64,
+}
+
+/// A building that runs recipes
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsManufacturer {
+    pub class_name: String,
+    pub display_name: String,
+    /// MW; for variable power buildings, the recipe decides
+    pub power_consumption: f64,
+    pub power_consumption_exponent: f64,
+    pub manufacturing_speed: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsGeneratorFuel {
+    pub fuel_class: String,
+    pub supplemental_resource_class: Option<String>,
+    pub byproduct_class: Option<String>,
+    pub byproduct_amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsGenerator {
+    pub class_name: String,
+    pub display_name: String,
+    /// MW
+    pub power_production: f64,
+    pub fuels: Vec<DocsGeneratorFuel>,
+    /// Supplemental resource (m³) per MJ produced
+    pub supplemental_to_power_ratio: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsExtractor {
+    pub class_name: String,
+    pub display_name: String,
+    /// MW
+    pub power_consumption: f64,
+    /// Seconds per extraction cycle
+    pub extract_cycle_time: f64,
+    pub items_per_cycle: f64,
+    pub allowed_forms: Vec<ItemForm>,
+    /// Resource classes it may extract; empty when any resource of an allowed form will do
+    pub allowed_resources: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsSchematic {
+    pub class_name: String,
+    pub display_name: String,
+    /// e.g. EST_Milestone, EST_Alternate, EST_MAM
+    pub schematic_type: String,
+    pub tech_tier: u32,
+    pub cost: Vec<(String, f64)>,
+    pub unlocked_recipes: Vec<String>,
+}
+
+impl DocsSchematic {
+    pub fn is_alternate(&self) -> bool {
+        self.schematic_type == "EST_Alternate"
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocsRecipe {
+    pub class_name: String,
+    pub display_name: String,
+    /// (item class, amount); fluid amounts are in thousandths of a cubic metre
+    pub ingredients: Vec<(String, f64)>,
+    pub products: Vec<(String, f64)>,
+    /// Building, workbench and build gun classes that can run the recipe
+    pub produced_in: Vec<String>,
+    /// Seconds
+    pub duration: f64,
+    /// Constant and varying part of the power use (MW) of recipes in variable power buildings
+    pub variable_power: Option<(f64, f64)>,
+}
+
+/// Every class of the categories the planner reads, plus one warning per class that could not be read
+#[derive(Debug, Clone, Default)]
+pub struct SatisfactoryDocs {
+    pub items: Vec<DocsItem>,
+    pub resources: Vec<DocsItem>,
+    pub manufacturers: Vec<DocsManufacturer>,
+    pub generators: Vec<DocsGenerator>,
+    pub extractors: Vec<DocsExtractor>,
+    pub schematics: Vec<DocsSchematic>,
+    pub recipes: Vec<DocsRecipe>,
+    pub warnings: Vec<AdapterError>,
+}
+
+impl SatisfactoryDocs {
+    pub fn from_assets(assets: &[SatisfactoryAsset]) -> Self {
+        let mut docs = SatisfactoryDocs::default();
+        for asset in assets {
+            let group_category = asset.native_class.as_deref().map(docs_category);
+            for class in &asset.classes {
+                let category = match group_category {
+                    Some(category) => category,
+                    None => category_from_fields(class),
+                };
+                if let Some(category) = category
+                    && let Err(e) = docs.push(category, class)
+                {
+                    docs.warnings.push(e);
+                }
+            }
+        }
+        docs
+    }
+
+    fn push(&mut self, category: DocsCategory, class: &Value) -> Result<(), AdapterError> {
+        match category {
+            DocsCategory::Item => self.items.push(parse_item(class)?),
+            DocsCategory::Resource => self.resources.push(parse_item(class)?),
+            DocsCategory::Manufacturer => self.manufacturers.push(parse_manufacturer(class)?),
+            DocsCategory::Generator => self.generators.push(parse_generator(class)?),
+            DocsCategory::Extractor => self.extractors.push(parse_extractor(class)?),
+            DocsCategory::Schematic => self.schematics.push(parse_schematic(class)?),
+            DocsCategory::Recipe => self.recipes.push(parse_recipe(class)?),
+        }
+        Ok(())
+    }
+
+    /// Item and resource descriptors together
+    pub fn all_items(&self) -> impl Iterator<Item = &DocsItem> {
+        self.items.iter().chain(self.resources.iter())
+    }
+}
+
+struct ClassFields<'a> {
+    class: &'a Value,
+    class_name: String,
+}
+
+impl<'a> ClassFields<'a> {
+    fn new(class: &'a Value) -> Result<Self, AdapterError> {
+        let class_name = class
+            .get("ClassName")
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| AdapterError::MissingField {
+                class_name: "<unnamed class>".to_string(),
+                field: "ClassName",
+            })?
+            .to_string();
+        Ok(ClassFields { class, class_name })
+    }
+
+    /// A non-empty string field
+    fn str(&self, field: &str) -> Option<&'a str> {
+        self.class.get(field).and_then(Value::as_str).filter(|s| !s.is_empty())
+    }
+
+    fn display_name(&self) -> String {
+        self.str("mDisplayName").unwrap_or(&self.class_name).to_string()
+    }
+
+    /// A number, written by the game as a string; empty strings count as absent
+    fn num(&self, field: &'static str) -> Result<Option<f64>, AdapterError> {
+        match self.class.get(field) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::Number(n)) => Ok(n.as_f64()),
+            Some(Value::String(s)) if s.trim().is_empty() => Ok(None),
+            Some(Value::String(s)) => s.trim().parse::<f64>().map(Some).map_err(|e| self.invalid(field, e.to_string())),
+            Some(other) => Err(self.invalid(field, format!("expected a number, found {}", other))),
+        }
+    }
+
+    fn num_or(&self, field: &'static str, default: f64) -> Result<f64, AdapterError> {
+        Ok(self.num(field)?.unwrap_or(default))
+    }
+
+    fn invalid(&self, field: &'static str, message: String) -> AdapterError {
+        AdapterError::InvalidField {
+            class_name: self.class_name.clone(),
+            field,
+            message,
+        }
+    }
+}
+
+/// Items per slot for the game's stack size classes
+fn stack_size(value: &str) -> Option<u32> {
+    match value {
+        "SS_ONE" => Some(1),
+        "SS_SMALL" => Some(50),
+        "SS_MEDIUM" => Some(100),
+        "SS_BIG" => Some(200),
+        "SS_HUGE" => Some(500),
+        _ => None, // SS_FLUID and unknown classes
+    }
+}
+
+fn parse_item(class: &Value) -> Result<DocsItem, AdapterError> {
+    let f = ClassFields::new(class)?;
+    Ok(DocsItem {
+        display_name: f.display_name(),
+        description: f.str("mDescription").unwrap_or_default().to_string(),
+        stack_size: f.str("mStackSize").and_then(stack_size),
+        sink_points: f.num_or("mResourceSinkPoints", 0.0)?.max(0.0) as u32,
+        energy_value: f.num_or("mEnergyValue", 0.0)?,
+        form: ItemForm::from_docs(f.str("mForm")),
+        radioactive_decay: f.num_or("mRadioactiveDecay", 0.0)?,
+        class_name: f.class_name,
+    })
+}
+
+fn parse_manufacturer(class: &Value) -> Result<DocsManufacturer, AdapterError> {
+    let f = ClassFields::new(class)?;
+    Ok(DocsManufacturer {
+        display_name: f.display_name(),
+        power_consumption: f.num_or("mPowerConsumption", 0.0)?,
+        power_consumption_exponent: f.num_or("mPowerConsumptionExponent", 1.321929)?,
+        manufacturing_speed: f.num_or("mManufacturingSpeed", 1.0)?,
+        class_name: f.class_name,
+    })
+}
+
+fn parse_generator(class: &Value) -> Result<DocsGenerator, AdapterError> {
+    let f = ClassFields::new(class)?;
+    let fuels = match class.get("mFuel") {
+        Some(Value::Array(fuels)) => fuels
+            .iter()
+            .filter_map(|fuel| {
+                let field = |name: &str| {
+                    fuel.get(name)
+                        .and_then(Value::as_str)
+                        .filter(|s| !s.is_empty())
+                        .map(extract_short_class_name)
+                };
+                Some(DocsGeneratorFuel {
+                    fuel_class: field("mFuelClass")?,
+                    supplemental_resource_class: field("mSupplementalResourceClass"),
+                    byproduct_class: field("mByproduct"),
+                    byproduct_amount: fuel
+                        .get("mByproductAmount")
+                        .and_then(Value::as_str)
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(0.0),
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(DocsGenerator {
+        display_name: f.display_name(),
+        power_production: f.num_or("mPowerProduction", 0.0)?,
+        fuels,
+        supplemental_to_power_ratio: f.num_or("mSupplementalToPowerRatio", 0.0)?,
+        class_name: f.class_name,
+    })
+}
+
+fn parse_extractor(class: &Value) -> Result<DocsExtractor, AdapterError> {
+    let f = ClassFields::new(class)?;
+    let only_certain = f.str("mOnlyAllowCertainResources").is_some_and(|s| s.eq_ignore_ascii_case("true"));
+    Ok(DocsExtractor {
+        display_name: f.display_name(),
+        power_consumption: f.num_or("mPowerConsumption", 0.0)?,
+        extract_cycle_time: f.num_or("mExtractCycleTime", 1.0)?,
+        items_per_cycle: f.num_or("mItemsPerCycle", 1.0)?,
+        allowed_forms: f
+            .str("mAllowedResourceForms")
+            .map(|s| {
+                s.trim_matches(|c| c == '(' || c == ')')
+                    .split(',')
+                    .map(|form| ItemForm::from_docs(Some(form.trim())))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        allowed_resources: if only_certain {
+            f.str("mAllowedResources").map(parse_produced_in_tuple).unwrap_or_default()
+        } else {
+            Vec::new()
+        },
+        class_name: f.class_name,
+    })
+}
+
+fn parse_schematic(class: &Value) -> Result<DocsSchematic, AdapterError> {
+    let f = ClassFields::new(class)?;
+    // `mUnlocks` of unexpected shape yields no unlocks rather than failing the class
+    let unlocked_recipes = match class.get("mUnlocks") {
+        Some(Value::Array(unlocks)) => unlocks
+            .iter()
+            .filter_map(|u| u.get("mRecipes").and_then(Value::as_str))
+            .flat_map(parse_produced_in_tuple)
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(DocsSchematic {
+        display_name: f.display_name(),
+        schematic_type: f.str("mType").unwrap_or_default().to_string(),
+        tech_tier: f.num_or("mTechTier", 0.0)?.max(0.0) as u32,
+        cost: f.str("mCost").map(parse_ingredient_tuples).unwrap_or_default(),
+        unlocked_recipes,
+        class_name: f.class_name,
+    })
+}
+
+fn parse_recipe(class: &Value) -> Result<DocsRecipe, AdapterError> {
+    let f = ClassFields::new(class)?;
+    let products = f.str("mProduct").map(parse_ingredient_tuples).ok_or_else(|| AdapterError::MissingField {
+        class_name: f.class_name.clone(),
+        field: "mProduct",
+    })?;
+    let variable_power = match f.num("mVariablePowerConsumptionFactor")? {
+        Some(factor) if factor > 0.0 => Some((f.num_or("mVariablePowerConsumptionConstant", 0.0)?, factor)),
+        _ => None,
+    };
+    Ok(DocsRecipe {
+        display_name: f.display_name(),
+        ingredients: f.str("mIngredients").map(parse_ingredient_tuples).unwrap_or_default(),
+        products,
+        produced_in: f.str("mProducedIn").map(parse_produced_in_tuple).unwrap_or_default(),
+        duration: f.num_or("mManufactoringDuration", 1.0)?,
+        variable_power,
+        class_name: f.class_name,
+    })
+}
+
+pub(crate) fn extract_short_class_name(item_class: &str) -> String {
+    // Handles both Unreal path and plain class name, with or without the trailing quote of
+    // /Script/Engine.BlueprintGeneratedClass'...' references
+    let item_class = item_class.trim_end_matches('\'');
+    if let Some(pos) = item_class.rfind('/') {
+        let after_slash = &item_class[pos + 1..];
+        if let Some(dot_pos) = after_slash.rfind('.') {
+            after_slash[dot_pos + 1..].to_string()
+        } else {
+            after_slash.to_string()
+        }
+    } else if let Some(dot_pos) = item_class.rfind('.') {
+        item_class[dot_pos + 1..].to_string()
+    } else {
+        item_class.to_string()
+    }
+}
+
+pub(crate) fn parse_ingredient_tuples(s: &str) -> Vec<(String, f64)> {
+    // Example: ((ItemClass="...",Amount=3),(ItemClass="...",Amount=2))
+    let re = regex::Regex::new(r#"ItemClass=\\?\"([^\"]+)\\?\",Amount=([0-9.]+)"#).unwrap();
+    re.captures_iter(s)
+        .map(|cap| (extract_short_class_name(&cap[1]), cap[2].parse().unwrap_or(1.0)))
+        .collect()
+}
+
+pub(crate) fn parse_produced_in_tuple(s: &str) -> Vec<String> {
+    // Match everything after the last dot to the end of the string, for each entry inside parentheses
+    let trimmed = s.trim();
+    let mut result = Vec::new();
+    if trimmed.starts_with('(') && trimmed.ends_with(')') {
+        let inner = &trimmed[1..trimmed.len() - 1];
+        for part in inner.split(',') {
+            let part = part.trim().trim_matches(|c| c == '"' || c == '\'');
+            if let Some(dot_pos) = part.rfind('.') {
+                let class_name = &part[dot_pos + 1..];
+                if !class_name.is_empty() {
+                    result.push(class_name.to_string());
+                }
+            } else if !part.is_empty() {
+                result.push(part.to_string());
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::satisfactory_asset::parse_satisfactory_assets;
+
+    #[test]
+    fn test_classes_sorted_by_native_class() {
+        let json_str = r#"[
+            {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGResourceDescriptor'", "Classes": [
+                {"ClassName": "Desc_OreIron_C", "mDisplayName": "Iron Ore", "mStackSize": "SS_HUGE",
+                 "mResourceSinkPoints": "1", "mEnergyValue": "0.000000", "mForm": "RF_SOLID"}
+            ]},
+            {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGItemDescriptorBiomass'", "Classes": [
+                {"ClassName": "Desc_Biofuel_C", "mDisplayName": "Solid Biofuel", "mStackSize": "SS_BIG",
+                 "mResourceSinkPoints": "0", "mEnergyValue": "450.000000", "mForm": "RF_SOLID"}
+            ]},
+            {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGBuildableResourceExtractor'", "Classes": [
+                {"ClassName": "Build_MinerMk1_C", "mDisplayName": "Miner Mk.1", "mPowerConsumption": "5.000000",
+                 "mExtractCycleTime": "1.000000", "mItemsPerCycle": "1", "mAllowedResourceForms": "(RF_SOLID)",
+                 "mOnlyAllowCertainResources": "False", "mAllowedResources": ""}
+            ]},
+            {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGBuildableGeneratorFuel'", "Classes": [
+                {"ClassName": "Build_GeneratorBiomass_Automated_C", "mDisplayName": "Biomass Burner", "mPowerProduction": "30.000000",
+                 "mFuel": [{"mFuelClass": "Desc_Biofuel_C", "mSupplementalResourceClass": "", "mByproduct": "", "mByproductAmount": ""}]}
+            ]},
+            {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGRecipe'", "Classes": [
+                {"ClassName": "Recipe_Broken_C", "mProduct": "((ItemClass=\"/Game/Desc_OreIron.Desc_OreIron_C\",Amount=1))",
+                 "mManufactoringDuration": "fast"}
+            ]},
+            {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGEmote'", "Classes": [
+                {"ClassName": "Emote_Wave_C", "mDisplayName": "Wave"}
+            ]}
+        ]"#;
+        let docs = SatisfactoryDocs::from_assets(&parse_satisfactory_assets(json_str).unwrap());
+        assert_eq!(docs.resources.len(), 1);
+        assert_eq!(docs.resources[0].stack_size, Some(500));
+        assert_eq!(docs.items.len(), 1);
+        assert_eq!(docs.items[0].energy_value, 450.0);
+        assert_eq!(docs.extractors[0].allowed_forms, vec![ItemForm::Solid]);
+        assert!(docs.extractors[0].allowed_resources.is_empty());
+        assert_eq!(docs.generators[0].fuels[0].fuel_class, "Desc_Biofuel_C");
+        assert!(docs.recipes.is_empty());
+        assert!(matches!(
+            &docs.warnings[..],
+            [AdapterError::InvalidField { field: "mManufactoringDuration", .. }]
+        ));
+    }
+}
@@ -0,0 +1,187 @@
+//! Layered Docs files: the base game plus mods that add, change or remove classes.
+//!
+//! Layers apply in order. A class whose ClassName an earlier layer defined is merged field by field,
+//! so a mod only lists the fields it changes; a class with `"Removed": true` drops the earlier one.
+//! References resolve across layers, e.g. a mod recipe may use base game items, and every class
+//! remembers the mod that last added or changed it.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::adapters::error::{AdapterError, LoadedRecipes};
+use crate::adapters::satisfactory_adapter::load_satisfactory_recipes_from_docs;
+use crate::adapters::satisfactory_asset::{parse_satisfactory_assets, SatisfactoryAsset};
+use crate::adapters::satisfactory_docs::SatisfactoryDocs;
+
+/// Field that marks a class in a mod layer as removed
+pub const REMOVED_FIELD: &str = "Removed";
+
+/// One Docs-style document and the mod it comes from; `None` for the base game
+#[derive(Debug)]
+pub struct DocsLayer {
+    pub source: Option<String>,
+    pub assets: Vec<SatisfactoryAsset>,
+}
+
+impl DocsLayer {
+    pub fn parse(source: Option<String>, json_str: &str) -> Result<Self, AdapterError> {
+        Ok(DocsLayer {
+            source,
+            assets: parse_satisfactory_assets(json_str)?,
+        })
+    }
+}
+
+/// The layers merged into one document
+#[derive(Debug, Default)]
+pub struct LayeredDocs {
+    pub assets: Vec<SatisfactoryAsset>,
+    /// Mod that last added or changed each class, by class name; base game classes are absent
+    pub sources: HashMap<String, String>,
+    pub warnings: Vec<AdapterError>,
+}
+
+fn class_name(class: &Value) -> Option<&str> {
+    class.get("ClassName").and_then(Value::as_str).filter(|s| !s.is_empty())
+}
+
+/// Merge the layers in order; see the module docs for the override and removal rules
+pub fn merge_docs_layers(layers: &[DocsLayer]) -> LayeredDocs {
+    // Merged classes in first-definition order, with the NativeClass of the group that defined them
+    let mut classes: Vec<Option<(Option<String>, Value)>> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut merged = LayeredDocs::default();
+    for layer in layers {
+        for asset in &layer.assets {
+            for class in &asset.classes {
+                let Some(name) = class_name(class).map(str::to_string) else {
+                    // Left for the typed parser to report
+                    classes.push(Some((asset.native_class.clone(), class.clone())));
+                    continue;
+                };
+                let removed = class.get(REMOVED_FIELD).and_then(Value::as_bool).unwrap_or(false);
+                match (index.get(&name).copied(), removed) {
+                    (Some(i), true) => {
+                        classes[i] = None;
+                        index.remove(&name);
+                        merged.sources.remove(&name);
+                    }
+                    (None, true) => merged.warnings.push(AdapterError::InvalidField {
+                        class_name: name.clone(),
+                        field: REMOVED_FIELD,
+                        message: "no earlier layer defines this class".to_string(),
+                    }),
+                    (Some(i), false) => {
+                        let (native_class, existing) = classes[i].as_mut().expect("indexed classes are present");
+                        if native_class.is_none() {
+                            native_class.clone_from(&asset.native_class);
+                        }
+                        if let (Some(existing), Some(fields)) = (existing.as_object_mut(), class.as_object()) {
+                            existing.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+                        }
+                    }
+                    (None, false) => {
+                        index.insert(name.clone(), classes.len());
+                        classes.push(Some((asset.native_class.clone(), class.clone())));
+                    }
+                }
+                if !removed {
+                    match &layer.source {
+                        Some(source) => merged.sources.insert(name, source.clone()),
+                        None => merged.sources.remove(&name),
+                    };
+                }
+            }
+        }
+    }
+    // Regroup consecutive classes of the same NativeClass
+    for (native_class, class) in classes.into_iter().flatten() {
+        match merged.assets.last_mut() {
+            Some(asset) if asset.native_class == native_class => asset.classes.push(class),
+            _ => merged.assets.push(SatisfactoryAsset {
+                native_class,
+                classes: vec![class],
+            }),
+        }
+    }
+    merged
+}
+
+/// Load recipes from the base game's Docs file with mod layers on top; each recipe is tagged with its mod
+pub fn load_satisfactory_recipes_from_layers(layers: &[DocsLayer]) -> LoadedRecipes {
+    let merged = merge_docs_layers(layers);
+    let mut loaded = load_satisfactory_recipes_from_docs(&SatisfactoryDocs::from_assets(&merged.assets));
+    for recipe in loaded.recipes.iter_mut() {
+        recipe.source = merged.sources.get(&recipe.id).cloned();
+    }
+    loaded.warnings.extend(merged.warnings);
+    loaded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"[
+        {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGItemDescriptor'", "Classes": [
+            {"ClassName": "Desc_OreIron_C", "mDisplayName": "Iron Ore", "mForm": "RF_SOLID"},
+            {"ClassName": "Desc_IronIngot_C", "mDisplayName": "Iron Ingot", "mForm": "RF_SOLID"}
+        ]},
+        {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGBuildableManufacturer'", "Classes": [
+            {"ClassName": "Build_SmelterMk1_C", "mDisplayName": "Smelter", "mPowerConsumption": "4.000000", "mManufacturingSpeed": "1.000000"}
+        ]},
+        {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGRecipe'", "Classes": [
+            {"ClassName": "Recipe_IngotIron_C", "mDisplayName": "Iron Ingot",
+             "mIngredients": "((ItemClass=\"/Script/Engine.BlueprintGeneratedClass'/Game/FactoryGame/Resource/RawResources/OreIron/Desc_OreIron.Desc_OreIron_C'\",Amount=1))",
+             "mProduct": "((ItemClass=\"/Script/Engine.BlueprintGeneratedClass'/Game/FactoryGame/Resource/Parts/IronIngot/Desc_IronIngot.Desc_IronIngot_C'\",Amount=1))",
+             "mManufactoringDuration": "2.000000",
+             "mProducedIn": "(\"/Game/FactoryGame/Buildable/Factory/SmelterMk1/Build_SmelterMk1.Build_SmelterMk1_C\")"},
+            {"ClassName": "Recipe_IngotIronSlow_C", "mDisplayName": "Slow Iron Ingot",
+             "mIngredients": "((ItemClass=\"/Script/Engine.BlueprintGeneratedClass'/Game/FactoryGame/Resource/RawResources/OreIron/Desc_OreIron.Desc_OreIron_C'\",Amount=1))",
+             "mProduct": "((ItemClass=\"/Script/Engine.BlueprintGeneratedClass'/Game/FactoryGame/Resource/Parts/IronIngot/Desc_IronIngot.Desc_IronIngot_C'\",Amount=1))",
+             "mManufactoringDuration": "8.000000",
+             "mProducedIn": "(\"/Game/FactoryGame/Buildable/Factory/SmelterMk1/Build_SmelterMk1.Build_SmelterMk1_C\")"}
+        ]}
+    ]"#;
+
+    // Patches the base recipe's duration, removes one recipe and adds a recipe using a base item
+    const MOD: &str = r#"[
+        {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGItemDescriptor'", "Classes": [
+            {"ClassName": "Desc_IronPellet_C", "mDisplayName": "Iron Pellet", "mForm": "RF_SOLID"}
+        ]},
+        {"NativeClass": "/Script/CoreUObject.Class'/Script/FactoryGame.FGRecipe'", "Classes": [
+            {"ClassName": "Recipe_IngotIron_C", "mManufactoringDuration": "1.000000"},
+            {"ClassName": "Recipe_IngotIronSlow_C", "Removed": true},
+            {"ClassName": "Recipe_Pellet_C", "mDisplayName": "Iron Pellet",
+             "mIngredients": "((ItemClass=\"/Script/Engine.BlueprintGeneratedClass'/Game/FactoryGame/Resource/Parts/IronIngot/Desc_IronIngot.Desc_IronIngot_C'\",Amount=1))",
+             "mProduct": "((ItemClass=\"/Script/Engine.BlueprintGeneratedClass'/Game/Pellets/Desc_IronPellet.Desc_IronPellet_C'\",Amount=2))",
+             "mManufactoringDuration": "4.000000",
+             "mProducedIn": "(\"/Game/FactoryGame/Buildable/Factory/SmelterMk1/Build_SmelterMk1.Build_SmelterMk1_C\")"}
+        ]}
+    ]"#;
+
+    #[test]
+    fn test_mod_layers_override_remove_and_tag() {
+        let layers = [
+            DocsLayer::parse(None, BASE).unwrap(),
+            DocsLayer::parse(Some("Pellets".to_string()), MOD).unwrap(),
+        ];
+        let loaded = load_satisfactory_recipes_from_layers(&layers);
+        assert!(loaded.warnings.is_empty(), "{:?}", loaded.warnings);
+        let ids: Vec<_> = loaded.recipes.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["Recipe_IngotIron_C", "Recipe_Pellet_C"]);
+
+        let ingot = &loaded.recipes[0];
+        assert_eq!((ingot.name.as_str(), ingot.time), ("Iron Ingot", 1000));
+        assert_eq!(ingot.source.as_deref(), Some("Pellets"));
+        let pellet = &loaded.recipes[1];
+        assert_eq!(pellet.inputs[0].item, "Desc_IronIngot_C");
+        assert_eq!(pellet.source.as_deref(), Some("Pellets"));
+
+        // Without the mod the base recipes are untagged
+        let base = load_satisfactory_recipes_from_layers(&layers[..1]);
+        assert_eq!(base.recipes.len(), 2);
+        assert!(base.recipes.iter().all(|r| r.source.is_none()));
+    }
+}
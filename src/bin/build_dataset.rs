@@ -0,0 +1,23 @@
+use factory_planner::{adapters::satisfactory_adapter::compile_satisfactory_dataset, model::{dataset::compiled_dataset_file, localization::{satisfactory_docs_path, DEFAULT_LOCALE}}};
+
+// Compiles assets/satisfactory_<locale>.json into assets/satisfactory_<locale>.dataset.
+// Run before `trunk build`; the app fetches the compiled file instead of parsing the Docs file.
+fn main() {
+        let locale = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+        let path = satisfactory_docs_path(&locale);
+        let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e));
+        let dataset = compile_satisfactory_dataset(&bytes, &locale).expect("Failed to compile dataset");
+        for warning in &dataset.warnings {
+            eprintln!("Skipped recipe: {}", warning);
+        }
+        let out_path = format!("assets/{}", compiled_dataset_file(&locale));
+        std::fs::write(&out_path, dataset.to_bytes()).expect("Failed to write dataset");
+        println!(
+            "Wrote {}: {} items, {} machines, {} recipes, {} schematics",
+            out_path,
+            dataset.items.len(),
+            dataset.machines.len(),
+            dataset.recipes.len(),
+            dataset.schematics.len(),
+        );
+    }
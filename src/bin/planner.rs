@@ -0,0 +1,486 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use factory_planner::{
+    adapters::{dataset_file::load_dataset_file, encoding::decode_text},
+    model::{
+        breakdown::{collect_unproducible, factory_breakdown, flatten_tree, leaf_totals, machine_totals, product_rate, BreakdownNode},
+        compute_item_analysis,
+        dataset::CompiledDataset,
+        diagnostics::compute_unreachable_items,
+        flow_graph::build_flow_graph,
+        graph_export::{export_flow_graph, GraphFormat},
+        item_label, machine_power_map_from_recipes,
+        plan::{FactoryPlan, PlanState},
+        plan_file::PlanFile,
+        recipe::{ItemStack, Recipe},
+        report::{Report, ReportFormat},
+    },
+};
+
+const USAGE: &str = "Usage: planner <command> [options]
+
+Commands:
+  plan <dataset> [plan file]              Break a plan down into recipes and machines
+      --make ITEM=RATE                    A product to make besides the plan file's; repeatable
+      --supply ITEM=RATE                  An item brought in besides the plan file's; repeatable
+      --factory NAME                      Factory of the plan file (default the first)
+      --view tree|machines|resources      The breakdown, the machines it needs or its raw resources and
+                                          supplies (default tree)
+  analyze <dataset> [plan file]           Weighted points and energy per item with the enabled recipes
+      --item ITEM                         The recipes making one item instead
+  recipes <dataset> [plan file]           Recipes, enabled as the plan file has them
+      --search TEXT                       Id or name contains TEXT
+      --makes ITEM                        Has ITEM among its products
+      --uses ITEM                         Has ITEM among its ingredients
+      --machine NAME                      Made in the machine NAME
+      --enabled true|false                Only enabled or only disabled recipes
+  items <dataset>                         Items of the dataset
+      --search TEXT                       Id or name contains TEXT
+  diff <dataset> <plan file> <plan file>  Outputs, machines and resources that differ between two plans
+      --factory NAME                      Factory compared in both plans (default the first)
+  graph <dataset> [plan file]             The plan's production graph; the plan file is needed without --item
+      --factory NAME                      Factory of the plan file (default the first)
+      --item ITEM                         The graph making ITEM, at the rate the whole plan uses it at
+      --rate RATE                         Rate of ITEM when the plan does not use it (default 60)
+
+Every command takes:
+      -h, --help                          Print this text
+      --format FORMAT                     table, json or csv; dot or mermaid for graph (default table, dot)
+      --output FILE                       Write to a file instead of standard output
+
+A dataset is a file compiled by build_dataset or a data file of a supported game. Items are given by
+id or by name.";
+
+/// Why a command failed; only mistakes in the arguments are followed by the usage text
+enum CliError {
+    /// `--help` was given: the usage text alone, and success
+    Help,
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::Failed(message)
+    }
+}
+
+fn usage(message: String) -> CliError {
+    CliError::Usage(message)
+}
+
+/// Positional arguments and `--name value` options, in any order
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+        let mut parsed = Args { positional: Vec::new(), options: Vec::new() };
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(CliError::Help);
+            }
+            match arg.strip_prefix("--") {
+                Some(name) => {
+                    let value = args.next().ok_or_else(|| usage(format!("--{} needs a value", name)))?;
+                    parsed.options.push((name.to_string(), value));
+                }
+                None => parsed.positional.push(arg),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn positional(&self, index: usize, name: &str) -> Result<&str, CliError> {
+        self.positional.get(index).map(String::as_str).ok_or_else(|| usage(format!("missing {}", name)))
+    }
+
+    /// The last value of an option
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    /// Every value of a repeatable option, in order
+    fn all(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.options.iter().filter(move |(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn report_format(&self) -> Result<ReportFormat, CliError> {
+        match self.option("format") {
+            Some(id) => ReportFormat::from_id(id).ok_or_else(|| usage(format!("unknown format {}", id))),
+            None => Ok(ReportFormat::Table),
+        }
+    }
+}
+
+/// A rate, which has to be a number greater than zero
+fn parse_rate(rate: &str) -> Result<f64, CliError> {
+    match rate.parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(value),
+        _ => Err(usage(format!("invalid rate {}; rates are numbers greater than zero", rate))),
+    }
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("failed to read {}: {}", path, e))
+}
+
+/// A dataset with the lookups every command needs
+struct Loaded {
+    dataset: CompiledDataset,
+    item_names: HashMap<String, String>,
+    raw_resources: HashSet<String>,
+}
+
+impl Loaded {
+    fn read(path: &str) -> Result<Self, String> {
+        let dataset = load_dataset_file(&read(path)?).map_err(|e| format!("{}: {}", path, e))?;
+        for warning in &dataset.warnings {
+            eprintln!("Skipped recipe: {}", warning);
+        }
+        Ok(Loaded {
+            item_names: dataset.items.iter().map(|i| (i.id.clone(), i.name.clone())).collect(),
+            raw_resources: dataset.raw_resources.iter().cloned().collect(),
+            dataset,
+        })
+    }
+
+    /// Read a plan file against the dataset, returning the plan and the recipes with the plan's flags.
+    /// Without a file the plan is empty and recipes keep the dataset's flags.
+    fn plan(&self, path: Option<&str>) -> Result<(PlanState, Vec<Recipe>), String> {
+        let mut recipes = self.dataset.recipes.clone();
+        let Some(path) = path else {
+            return Ok((PlanState::default(), recipes));
+        };
+        let text = decode_text(&read(path)?).map_err(|e| format!("{}: {}", path, e))?;
+        let file = PlanFile::parse(&text).map_err(|e| format!("{}: {}", path, e))?;
+        let (plan, warnings) = file
+            .to_plan(&self.dataset.game, &self.dataset.recipes, &self.item_names)
+            .map_err(|e| format!("{}: {}", path, e))?;
+        for warning in warnings {
+            eprintln!("{}: {}", path, warning);
+        }
+        plan.apply_enabled(&mut recipes);
+        Ok((plan, recipes))
+    }
+
+    /// An item id from an id or a display name
+    fn item(&self, item: &str) -> Result<String, String> {
+        self.dataset
+            .items
+            .iter()
+            .find(|i| i.id == item || i.name.eq_ignore_ascii_case(item))
+            .map(|i| i.id.clone())
+            .ok_or_else(|| format!("unknown item {}", item))
+    }
+
+    /// An `ITEM=RATE` option
+    fn item_rate(&self, spec: &str) -> Result<(String, f64), CliError> {
+        let (item, rate) = spec.rsplit_once('=').ok_or_else(|| usage(format!("expected ITEM=RATE, got {}", spec)))?;
+        Ok((self.item(item.trim())?, parse_rate(rate.trim())?))
+    }
+
+    fn label(&self, item: &str) -> String {
+        item_label(&self.item_names, item)
+    }
+
+    fn breakdown(&self, factory: &FactoryPlan, recipes: &[Recipe]) -> Vec<BreakdownNode> {
+        factory_breakdown(factory, recipes, &enabled_ids(recipes), &self.raw_resources)
+    }
+
+    /// Warn about the products of a breakdown that no enabled recipe makes
+    fn warn_unproducible(&self, breakdown: &[BreakdownNode]) {
+        let mut unproducible = Vec::new();
+        for node in breakdown {
+            collect_unproducible(node, &self.raw_resources, &mut unproducible);
+        }
+        for item in unproducible {
+            eprintln!("No enabled recipe makes {}", self.label(&item));
+        }
+    }
+}
+
+fn enabled_ids(recipes: &[Recipe]) -> HashSet<String> {
+    recipes.iter().filter(|r| r.enabled).map(|r| r.id.clone()).collect()
+}
+
+fn factory<'a>(plan: &'a PlanState, name: Option<&str>) -> Result<&'a FactoryPlan, String> {
+    match name {
+        Some(name) => plan.factories.iter().find(|f| f.name == name).ok_or_else(|| format!("no factory named {}", name)),
+        None => Ok(&plan.factories[0]),
+    }
+}
+
+fn stacks(loaded: &Loaded, stacks: &[ItemStack]) -> String {
+    stacks.iter().map(|s| format!("{} × {}", s.quantity, loaded.label(&s.item))).collect::<Vec<_>>().join(", ")
+}
+
+fn matches_search(search: Option<&str>, id: &str, name: &str) -> bool {
+    search.is_none_or(|search| {
+        let search = search.to_lowercase();
+        id.to_lowercase().contains(&search) || name.to_lowercase().contains(&search)
+    })
+}
+
+fn plan(args: &Args) -> Result<String, CliError> {
+    let format = args.report_format()?;
+    let loaded = Loaded::read(args.positional(1, "dataset")?)?;
+    let (plan, recipes) = loaded.plan(args.positional.get(2).map(String::as_str))?;
+    let mut factory = factory(&plan, args.option("factory"))?.clone();
+    for spec in args.all("make") {
+        factory.outputs.push(loaded.item_rate(spec)?);
+    }
+    for spec in args.all("supply") {
+        factory.supplies.push(loaded.item_rate(spec)?);
+    }
+    if factory.outputs.is_empty() {
+        return Err(usage("nothing to make; give a plan file or --make ITEM=RATE".to_string()));
+    }
+    let breakdown = loaded.breakdown(&factory, &recipes);
+    loaded.warn_unproducible(&breakdown);
+
+    let report = match args.option("view").unwrap_or("tree") {
+        "tree" => {
+            let mut report = Report::new(&["depth", "item", "name", "rate", "recipe", "machine", "machines", "supplied"]);
+            for root in &breakdown {
+                let mut rows = Vec::new();
+                flatten_tree(root, 0, &mut rows);
+                for (depth, node) in rows {
+                    // Tables show the tree by indenting names; other formats have the depth column
+                    let indent = if format == ReportFormat::Table { "  ".repeat(depth) } else { String::new() };
+                    report.push(vec![
+                        (depth as u64).into(),
+                        node.product.as_str().into(),
+                        format!("{}{}", indent, loaded.label(&node.product)).into(),
+                        node.rate.into(),
+                        node.recipe_name.into(),
+                        node.machine.into(),
+                        node.machines_needed.into(),
+                        node.supplied.into(),
+                    ]);
+                }
+            }
+            report
+        }
+        "machines" => {
+            let mut report = Report::new(&["machine", "count"]);
+            for (machine, count) in machine_totals(&breakdown) {
+                report.push(vec![machine.into(), count.into()]);
+            }
+            report
+        }
+        "resources" => {
+            let mut report = Report::new(&["kind", "name", "item", "rate"]);
+            let (raw, supplied) = leaf_totals(&breakdown, &loaded.raw_resources);
+            for (kind, totals) in [("raw", raw), ("supplied", supplied)] {
+                for (item, rate) in totals {
+                    report.push(vec![kind.into(), loaded.label(&item).into(), item.into(), rate.into()]);
+                }
+            }
+            report
+        }
+        view => return Err(usage(format!("unknown view {}", view))),
+    };
+    Ok(report.render(format))
+}
+
+fn analyze(args: &Args) -> Result<String, CliError> {
+    let format = args.report_format()?;
+    let loaded = Loaded::read(args.positional(1, "dataset")?)?;
+    let (_, recipes) = loaded.plan(args.positional.get(2).map(String::as_str))?;
+    let enabled: Vec<Recipe> = recipes.into_iter().filter(|r| r.enabled).collect();
+    for unreachable in compute_unreachable_items(&enabled, &enabled_ids(&enabled), &loaded.raw_resources) {
+        eprintln!("Unreachable item {}: {:?} (root causes: {:?})", unreachable.item, unreachable.missing, unreachable.root_causes);
+    }
+    let analysis = compute_item_analysis(&enabled, &machine_power_map_from_recipes(&enabled), &loaded.raw_resources);
+
+    let report = match args.option("item") {
+        Some(item) => {
+            let item = loaded.item(item)?;
+            let analysis = analysis.get(&item).ok_or_else(|| format!("the enabled recipes cannot make {}", loaded.label(&item)))?;
+            let mut report = Report::new(&["recipe", "rate", "wp", "power", "wp_flow", "inputs"]);
+            for recipe in &analysis.recipes_analysis {
+                let inputs = recipe.inputs.iter().map(|i| format!("{:.2} × {}", i.quantity, loaded.label(&i.item)));
+                report.push(vec![
+                    recipe.recipe_name.as_str().into(),
+                    recipe.rate.into(),
+                    recipe.wp.into(),
+                    recipe.power.into(),
+                    recipe.wp_flow.into(),
+                    inputs.collect::<Vec<_>>().join(", ").into(),
+                ]);
+            }
+            report
+        }
+        None => {
+            let mut report = Report::new(&["item", "name", "wp", "power", "recipes"]);
+            let items: BTreeMap<_, _> = analysis.iter().map(|(item, a)| ((loaded.label(item), item), a)).collect();
+            for ((name, item), a) in items {
+                report.push(vec![
+                    item.as_str().into(),
+                    name.into(),
+                    a.wp.into(),
+                    a.power.into(),
+                    (a.recipes_analysis.len() as u64).into(),
+                ]);
+            }
+            report
+        }
+    };
+    Ok(report.render(format))
+}
+
+fn recipes(args: &Args) -> Result<String, CliError> {
+    let format = args.report_format()?;
+    let loaded = Loaded::read(args.positional(1, "dataset")?)?;
+    let (_, recipes) = loaded.plan(args.positional.get(2).map(String::as_str))?;
+    let makes = args.option("makes").map(|item| loaded.item(item)).transpose()?;
+    let uses = args.option("uses").map(|item| loaded.item(item)).transpose()?;
+    let enabled = match args.option("enabled") {
+        Some(flag) => Some(flag.parse::<bool>().map_err(|_| usage(format!("--enabled takes true or false, got {}", flag)))?),
+        None => None,
+    };
+    let machine = args.option("machine");
+
+    let mut report = Report::new(&["id", "name", "machine", "time", "inputs", "outputs", "enabled", "source"]);
+    for recipe in recipes.iter().filter(|r| {
+        matches_search(args.option("search"), &r.id, &r.name)
+            && makes.as_ref().is_none_or(|item| r.outputs.iter().any(|o| &o.item == item))
+            && uses.as_ref().is_none_or(|item| r.inputs.iter().any(|i| &i.item == item))
+            && machine.is_none_or(|m| r.machine.name.eq_ignore_ascii_case(m) || r.machine.id == m)
+            && enabled.is_none_or(|flag| r.enabled == flag)
+    }) {
+        report.push(vec![
+            recipe.id.as_str().into(),
+            recipe.name.as_str().into(),
+            recipe.machine.name.as_str().into(),
+            (f64::from(recipe.time) / 1000.0).into(),
+            stacks(&loaded, &recipe.inputs).into(),
+            stacks(&loaded, &recipe.outputs).into(),
+            recipe.enabled.into(),
+            recipe.source.clone().into(),
+        ]);
+    }
+    Ok(report.render(format))
+}
+
+fn items(args: &Args) -> Result<String, CliError> {
+    let format = args.report_format()?;
+    let loaded = Loaded::read(args.positional(1, "dataset")?)?;
+    let recipes = &loaded.dataset.recipes;
+    let mut report = Report::new(&["id", "name", "raw", "stack_size", "made_by", "used_by"]);
+    for item in loaded.dataset.items.iter().filter(|i| matches_search(args.option("search"), &i.id, &i.name)) {
+        let count = |of: fn(&Recipe) -> &[ItemStack]| recipes.iter().filter(|r| of(r).iter().any(|s| s.item == item.id)).count() as u64;
+        report.push(vec![
+            item.id.as_str().into(),
+            item.name.as_str().into(),
+            loaded.raw_resources.contains(&item.id).into(),
+            loaded.dataset.stack_sizes.get(&item.id).map(|s| u64::from(*s)).into(),
+            count(|r| &r.outputs).into(),
+            count(|r| &r.inputs).into(),
+        ]);
+    }
+    Ok(report.render(format))
+}
+
+fn diff(args: &Args) -> Result<String, CliError> {
+    let format = args.report_format()?;
+    let loaded = Loaded::read(args.positional(1, "dataset")?)?;
+    // Per side: outputs, machines, raw resources and supplies, as totals by kind and key
+    let mut sides = Vec::new();
+    for (index, name) in [(2, "first plan file"), (3, "second plan file")] {
+        let (plan, recipes) = loaded.plan(Some(args.positional(index, name)?))?;
+        let factory = factory(&plan, args.option("factory"))?;
+        let breakdown = loaded.breakdown(factory, &recipes);
+        let (raw, supplied) = leaf_totals(&breakdown, &loaded.raw_resources);
+        let mut totals: BTreeMap<(&str, String), f64> = BTreeMap::new();
+        for (item, rate) in &factory.outputs {
+            *totals.entry(("output", loaded.label(item))).or_insert(0.0) += rate;
+        }
+        totals.extend(machine_totals(&breakdown).into_iter().map(|(machine, count)| (("machine", machine), count)));
+        totals.extend(raw.into_iter().map(|(item, rate)| (("raw", loaded.label(&item)), rate)));
+        totals.extend(supplied.into_iter().map(|(item, rate)| (("supplied", loaded.label(&item)), rate)));
+        sides.push(totals);
+    }
+
+    let keys: BTreeSet<_> = sides.iter().flat_map(|totals| totals.keys().cloned()).collect();
+    let mut report = Report::new(&["kind", "name", "before", "after", "change"]);
+    for key in keys {
+        let before = sides[0].get(&key).copied().unwrap_or(0.0);
+        let after = sides[1].get(&key).copied().unwrap_or(0.0);
+        if (after - before).abs() > 1e-6 {
+            report.push(vec![key.0.into(), key.1.into(), before.into(), after.into(), (after - before).into()]);
+        }
+    }
+    Ok(report.render(format))
+}
+
+fn graph(args: &Args) -> Result<String, CliError> {
+    let loaded = Loaded::read(args.positional(1, "dataset")?)?;
+    let item = args.option("item");
+    let path = match item {
+        Some(_) => args.positional.get(2).map(String::as_str),
+        None => Some(args.positional(2, "plan file")?),
+    };
+    let (plan, recipes) = loaded.plan(path)?;
+    let format = match args.option("format") {
+        Some(id) => GraphFormat::from_id(id).ok_or_else(|| usage(format!("unknown format {}", id)))?,
+        None => GraphFormat::Dot,
+    };
+    let factory = factory(&plan, args.option("factory"))?;
+    let mut breakdown = loaded.breakdown(factory, &recipes);
+    if let Some(item) = item {
+        // Every use of the item merged into one tree, made with the plan's supplies, choices and clocks
+        let item = loaded.item(item)?;
+        let rate = match (product_rate(&breakdown, &item), args.option("rate")) {
+            (Some(rate), _) => rate,
+            (None, Some(rate)) => parse_rate(rate)?,
+            (None, None) => 60.0,
+        };
+        let alone = FactoryPlan { outputs: vec![(item, rate)], ..factory.clone() };
+        breakdown = loaded.breakdown(&alone, &recipes);
+    }
+    let graph = build_flow_graph(&breakdown, &loaded.raw_resources);
+    Ok(export_flow_graph(&graph, format, &loaded.item_names, &loaded.dataset.units.rate))
+}
+
+fn run(args: &Args) -> Result<String, CliError> {
+    match args.positional.first().map(String::as_str) {
+        Some("plan") => plan(args),
+        Some("analyze") => analyze(args),
+        Some("recipes") => recipes(args),
+        Some("items") => items(args),
+        Some("diff") => diff(args),
+        Some("graph") => graph(args),
+        Some(command) => Err(usage(format!("unknown command {}", command))),
+        None => Err(usage("missing command".to_string())),
+    }
+}
+
+// Plans from the command line, e.g. `planner plan assets/satisfactory_en-US.dataset --make "Iron Plate=60" --format csv`
+fn main() {
+    let output = Args::parse(std::env::args().skip(1)).and_then(|args| {
+        let output = run(&args)?;
+        match args.option("output") {
+            Some(path) => std::fs::write(path, &output)
+                .map(|_| String::new())
+                .map_err(|e| CliError::Failed(format!("failed to write {}: {}", path, e))),
+            None => Ok(output),
+        }
+    });
+    match output {
+        Ok(output) => print!("{}", output),
+        Err(CliError::Help) => println!("{}", USAGE),
+        Err(CliError::Usage(e)) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+        Err(CliError::Failed(e)) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,12 +1,28 @@
-use factory_planner::{adapters::{satisfactory_adapter::{build_machine_power_map_from_assets, load_satisfactory_recipes_from_json}, satisfactory_asset::SatisfactoryAsset}, model::compute_item_analysis};
+use factory_planner::{adapters::satisfactory_adapter::compile_satisfactory_dataset, model::{compute_item_analysis, dataset::{compiled_dataset_file, CompiledDataset}, diagnostics::compute_unreachable_items, localization::{satisfactory_docs_path, DEFAULT_LOCALE}, machine_power_map_from_recipes}};
 
 fn main() {
-        let path = "assets/satisfactory_en-US.json";
-        let json_str = std::fs::read_to_string(path).expect("Failed to read JSON file");
-        let recipes = load_satisfactory_recipes_from_json(&json_str).expect("Failed to load recipes");
-        let assets: Vec<SatisfactoryAsset> = serde_json::from_str(&json_str).expect("Failed to parse JSON");
-        let machine_power_map = build_machine_power_map_from_assets(&assets);
-        let item_analysis = compute_item_analysis(&recipes, &machine_power_map);
+        // Optional first argument selects the Docs language, e.g. `satisfactory_analysis de-DE`
+        let locale = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+        // Prefer the compiled dataset from `build_dataset`, falling back to parsing the Docs file
+        let dataset = match std::fs::read(format!("assets/{}", compiled_dataset_file(&locale))) {
+            Ok(bytes) => CompiledDataset::from_bytes(&bytes).expect("Failed to load compiled dataset; rerun build_dataset"),
+            Err(_) => {
+                let bytes = std::fs::read(satisfactory_docs_path(&locale)).expect("Failed to read JSON file");
+                compile_satisfactory_dataset(&bytes, &locale).expect("Failed to load recipes")
+            }
+        };
+        for warning in &dataset.warnings {
+            eprintln!("Skipped recipe: {}", warning);
+        }
+        let recipes = dataset.recipes;
+        let raw_resources = dataset.raw_resources.into_iter().collect();
+        let machine_power_map = machine_power_map_from_recipes(&recipes);
+        let item_analysis = compute_item_analysis(&recipes, &machine_power_map, &raw_resources);
+        let all_recipes = recipes.iter().map(|r| r.id.clone()).collect();
+        for unreachable in compute_unreachable_items(&recipes, &all_recipes, &raw_resources) {
+            eprintln!("Unreachable item {}: {:?} (root causes: {:?})", unreachable.item, unreachable.missing, unreachable.root_causes);
+        }
         let json = json5::to_string(&item_analysis).expect("Failed to serialize item analysis to JSON5");
-        std::fs::write("assets/satisfactory_item_analysis.json5", json).expect("Failed to write item_analysis.json");
+        // The app computes this itself from the loaded recipes; the file is for inspecting a dataset
+        println!("{}", json);
     }
@@ -0,0 +1,29 @@
+use factory_planner::adapters::{encoding::decode_text, generic_dataset::{parse_generic_dataset, validate_generic_dataset}};
+
+// Checks a dataset written in the generic TOML/JSON format: `validate_dataset path/to/dataset.toml`
+fn main() {
+        let path = std::env::args().nth(1).expect("Usage: validate_dataset <dataset.toml|dataset.json>");
+        let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e));
+        let text = decode_text(&bytes).expect("Failed to decode dataset");
+        let dataset = match parse_generic_dataset(&text) {
+            Ok(dataset) => dataset,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
+            }
+        };
+        let errors = validate_generic_dataset(&dataset);
+        for error in &errors {
+            eprintln!("{}: {}", path, error);
+        }
+        if !errors.is_empty() {
+            std::process::exit(1);
+        }
+        println!(
+            "{}: valid, {} items, {} machines, {} recipes",
+            path,
+            dataset.items.len(),
+            dataset.machines.len(),
+            dataset.recipes.len(),
+        );
+    }
@@ -1,17 +1,17 @@
 use leptos::prelude::*;
-use once_cell::sync::Lazy;
 use reactive_stores::Store;
-use std::collections::HashMap;
-use std::sync::Arc;
-use crate::model::{recipe::Recipe, AppStore, AppStoreStoreFields, ItemAnalysis};
-use leptos::prelude::RwSignal;
-use json5;
+use std::collections::{HashMap, HashSet};
+use crate::components::pareto_view::ParetoView;
+use crate::model::{
+    compute_item_analysis, item_label, machine_power_map_from_recipes, recipe::Recipe, AppStore, AppStoreStoreFields,
+    ItemAnalysis,
+};
 
-static FALLBACK_ITEM_ANALYSIS: Lazy<ItemAnalysis> = Lazy::new(|| ItemAnalysis {
-    wp: f64::INFINITY,
-    power: f64::INFINITY,
-    recipes_analysis: vec![],
-});
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AnalysisMode {
+    Minima,
+    Pareto,
+}
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum SortColumn {
@@ -23,38 +23,31 @@ enum SortColumn {
 #[component]
 pub fn AnalysisTab() -> impl IntoView {
     let store = use_context::<Store<AppStore>>().expect("AppStore context");
-    let recipes = store.recipes().get_untracked();
-    // Map from output item to Vec<Recipe>
-    let mut recipes_by_output: HashMap<String, Vec<Recipe>> = HashMap::new();
-    for recipe in recipes.iter() {
-        for output in &recipe.outputs {
-            recipes_by_output.entry(output.item.clone()).or_default().push(recipe.clone());
-        }
-    }
     // Sorting state
     let sort_column = RwSignal::new(SortColumn::Item);
     let sort_desc = RwSignal::new(false);
 
-    // Load item analysis from JSON file at compile time using include_str!
-    let item_analysis: Arc<HashMap<String, ItemAnalysis>> = Arc::new({
-        let json_str = include_str!("../../assets/satisfactory_item_analysis.json5");
-        json5::from_str(json_str).expect("Failed to parse satisfactory_item_analysis.json5")
+    // Recomputed from the enabled recipes, custom ones included, whenever the dataset or selection changes
+    let enabled_recipes = use_context::<Memo<HashSet<String>>>().expect("enabled_recipes context");
+    let item_analysis: Memo<HashMap<String, ItemAnalysis>> = Memo::new(move |_| {
+        let enabled = enabled_recipes.get();
+        let recipes: Vec<Recipe> = store.recipes().with(|r| r.iter().filter(|r| enabled.contains(&r.id)).cloned().collect());
+        store.raw_resources().with(|raw| compute_item_analysis(&recipes, &machine_power_map_from_recipes(&recipes), raw))
     });
-    let sort_column_memo = sort_column.clone();
-    let sort_desc_memo = sort_desc.clone();
-    let item_analysis_memo = item_analysis.clone();
     let sorted_items = Memo::new(move |_| {
-        let sort_column = sort_column_memo.clone();
-        let sort_desc = sort_desc_memo.clone();
-        let item_analysis = item_analysis_memo.clone();
+        // Labels arrive with the dataset, after the tab is created
+        let item_names_memo = store.item_names().get();
+        let item_analysis = item_analysis.get();
         let mut items: Vec<_> = item_analysis.keys().cloned().collect();
         match sort_column.get() {
             SortColumn::Item => {
                 items.sort_by(|a, b| {
+                    let la = item_label(&item_names_memo, a);
+                    let lb = item_label(&item_names_memo, b);
                     if sort_desc.get() {
-                        b.cmp(a)
+                        lb.cmp(&la)
                     } else {
-                        a.cmp(b)
+                        la.cmp(&lb)
                     }
                 });
             }
@@ -85,7 +78,10 @@ pub fn AnalysisTab() -> impl IntoView {
                 });
             }
         }
-        items
+        items.into_iter().map(|item| {
+            let analysis = item_analysis[&item].clone();
+            (item, analysis)
+        }).collect::<Vec<_>>()
     });
 
     let on_sort = move |col: SortColumn| {
@@ -100,10 +96,29 @@ pub fn AnalysisTab() -> impl IntoView {
     // Dialog state for details
     let dialog_open = RwSignal::new(false);
     let dialog_inputs = RwSignal::new(Vec::new());
+    let mode = RwSignal::new(AnalysisMode::Minima);
 
     view! {
         <div class="p-4">
             <h2 class="text-xl font-bold mb-4">Recipe Analysis</h2>
+            <div class="join mb-4">
+                <button
+                    class=move || format!("btn btn-sm join-item{}", if mode.get() == AnalysisMode::Minima { " btn-active" } else { "" })
+                    on:click=move |_| mode.set(AnalysisMode::Minima)
+                >
+                    Per-metric minima
+                </button>
+                <button
+                    class=move || format!("btn btn-sm join-item{}", if mode.get() == AnalysisMode::Pareto { " btn-active" } else { "" })
+                    on:click=move |_| mode.set(AnalysisMode::Pareto)
+                >
+                    Pareto frontier
+                </button>
+            </div>
+            <Show when=move || mode.get() == AnalysisMode::Pareto>
+                <ParetoView />
+            </Show>
+            <div style=move || if mode.get() == AnalysisMode::Minima { "" } else { "display:none;" }>
             <table class="table table-zebra w-full">
                 <thead>
                     <tr>
@@ -122,15 +137,12 @@ pub fn AnalysisTab() -> impl IntoView {
                 <tbody>
                     <For
                         each=move || sorted_items.get()
-                        key=|item| item.clone()
+                        // Rows are rebuilt when an item's figures change with the enabled recipes
+                        key=|(item, analysis)| (item.clone(), analysis.wp.to_bits(), analysis.power.to_bits(), analysis.recipes_analysis.len())
                         children={
-                            let item_analysis = item_analysis.clone();
-                            let dialog_open = dialog_open.clone();
-                            let dialog_inputs = dialog_inputs.clone();
-                            move |item| {
-                                let analysis = item_analysis.get(&item).unwrap_or(&FALLBACK_ITEM_ANALYSIS);
+                            move |(item, analysis)| {
                                 let recipes_analysis = analysis.recipes_analysis.clone();
-                                let item = item.clone();
+                                let item = move || store.item_names().with(|names| item_label(names, &item));
                                 Some(view! {
                                     <tr>
                                         <td>{item}</td>
@@ -143,15 +155,13 @@ pub fn AnalysisTab() -> impl IntoView {
                                                         <th>Recipe</th>
                                                         <th>WP</th>
                                                         <th>Power (MJ)</th>
-                                                        <th>Rate (/min)</th>
+                                                        <th>{move || format!("Rate ({})", store.units().get().rate)}</th>
                                                         <th>WP Rate</th>
                                                         <th>Details</th>
                                                     </tr>
                                                 </thead>
                                                 <tbody>
                                                     {recipes_analysis.into_iter().map({
-                                                        let dialog_open = dialog_open.clone();
-                                                        let dialog_inputs = dialog_inputs.clone();
                                                         move |recipe| {
                                                             let recipe_name = recipe.recipe_name.clone();
                                                             let wp = recipe.wp;
@@ -188,6 +198,7 @@ pub fn AnalysisTab() -> impl IntoView {
                     />
                 </tbody>
             </table>
+            </div>
             // DaisyUI dialog
             {move || if dialog_open.get() {
                 view! {
@@ -207,7 +218,7 @@ pub fn AnalysisTab() -> impl IntoView {
                                     {dialog_inputs.get().into_iter().map(|input| view! {
                                         <tr>
                                             <td>{format!("{:.2}", input.quantity)}</td>
-                                            <td>{input.item.clone()}</td>
+                                            <td>{store.item_names().with(|names| item_label(names, &input.item))}</td>
                                             <td>{format!("{:.2}", input.wp_per_item)}</td>
                                             <td>{format!("{:.2}", input.power_per_item)}</td>
                                         </tr>
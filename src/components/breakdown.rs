@@ -1,25 +1,11 @@
-use crate::{components::raw_resource::is_raw_resource, model::AppStore};
+use crate::{components::{flow_graph_view::{breakdown_row_id, FlowGraphView}, logistics::LogisticsCalculator, plan_persistence::PlanSignals, raw_resource::is_raw_resource}, model::{item_label, AppStore, AppStoreStoreFields}};
 use leptos::prelude::*;
 use reactive_stores::Store;
 use std::collections::HashSet;
-use crate::model::recipe::Recipe;
-
-#[derive(Clone, Debug, PartialEq)]
-pub struct BreakdownNode {
-    pub product: String,
-    pub rate: f64,
-    pub recipe_name: Option<String>,
-    pub machine: Option<String>,
-    pub machines_needed: Option<f64>,
-    pub children: Vec<BreakdownNode>,
-}
-
-pub fn flatten_tree(node: &BreakdownNode, depth: usize, out: &mut Vec<(usize, BreakdownNode)>) {
-    out.push((depth, node.clone()));
-    for child in &node.children {
-        flatten_tree(child, depth + 1, out);
-    }
-}
+use crate::model::{
+    breakdown::{build_breakdown, collect_unproducible, flatten_tree, leaf_totals, product_rate, supply_rates, BreakdownInputs, BreakdownNode},
+    diagnostics::{compute_unreachable_items, MissingLink},
+};
 
 #[component]
 pub fn BreakdownView(
@@ -27,89 +13,96 @@ pub fn BreakdownView(
 ) -> impl IntoView {
     let store = use_context::<Store<AppStore>>().expect("Store<AppStore> context");
     let enabled_recipes = use_context::<Memo<HashSet<String>>>().expect("enabled_recipes context");
-    fn build_tree(
-        product: &str,
-        rate: f64,
-        recipes: &[Recipe],
-        enabled: &HashSet<String>,
-        path: &mut Vec<String>,
-    ) -> BreakdownNode {
-        if path.contains(&product.to_string()) {
-            // True cycle detected (recursion in the current path)
-            return BreakdownNode {
-                product: product.to_string(),
-                rate,
-                recipe_name: Some("Cycle".to_string()),
-                machine: None,
-                machines_needed: None,
-                children: vec![],
-            };
-        }
-        // Stop recursion at raw resources
-        if is_raw_resource(product) {
-            return BreakdownNode {
-                product: product.to_string(),
-                rate,
-                recipe_name: None,
-                machine: None,
-                machines_needed: None,
-                children: vec![],
-            };
-        }
-        path.push(product.to_string());
-        let recipes_for_product: Vec<&Recipe> = recipes.iter()
-            .filter(|r| enabled.contains(&r.name) && r.outputs.iter().any(|o| o.item == product))
-            .collect();
-        let node = if !recipes_for_product.is_empty() {
-            let recipe = recipes_for_product[0];
-            let output = recipe.outputs.iter().find(|o| o.item == product).unwrap();
-            let items_per_min = output.quantity as f64 * (60_000.0 / recipe.time as f64);
-            let machines_needed = rate / items_per_min;
-            let children = recipe.inputs.iter().map(|input| {
-                let input_rate = rate * (input.quantity as f64) / (output.quantity as f64);
-                build_tree(&input.item, input_rate, recipes, enabled, path)
-            }).collect();
-            BreakdownNode {
-                product: product.to_string(),
-                rate,
-                recipe_name: Some(recipe.name.clone()),
-                machine: Some(recipe.machine.name.clone()),
-                machines_needed: Some(machines_needed),
-                children,
-            }
-        } else {
-            // No enabled recipe produces this product: it's a raw resource
-            BreakdownNode {
-                product: product.to_string(),
-                rate,
-                recipe_name: None,
-                machine: None,
-                machines_needed: None,
-                children: vec![],
-            }
-        };
-        path.pop();
-        node
-    }
+    let plan = use_context::<PlanSignals>().expect("PlanSignals context");
+    let recipe_choices = plan.recipe_choices;
+    let clocks = plan.clocks;
+    // Imported item and rate the logistics calculator is open for
+    let logistics = RwSignal::new(None::<(String, f64)>);
+    // Product picked in the graph, highlighted in the table
+    let selected = RwSignal::new(None::<String>);
 
-    let breakdown = Memo::new(move |_| {
+    // Trees for some outputs with the plan's recipes, supplies, choices and clocks
+    let build = move |outputs: &[(String, f64)]| {
         let recipes = store.with(|s| s.recipes.clone());
+        let raw_resources = store.raw_resources().get();
         let enabled = enabled_recipes.get();
-        outputs.get().iter().map(|(product, rate)| {
-            let mut path = Vec::new();
-            build_tree(product, *rate, &recipes, &enabled, &mut path)
-        }).collect::<Vec<_>>()
+        let supplied = plan.supplies.with(|supplies| supply_rates(supplies));
+        let choices = recipe_choices.get();
+        let clocks = clocks.get();
+        let inputs = BreakdownInputs {
+            recipes: &recipes,
+            enabled: &enabled,
+            raw_resources: &raw_resources,
+            supplied: &supplied,
+            choices: &choices,
+            clocks: &clocks,
+        };
+        build_breakdown(outputs, &inputs)
+    };
+    let breakdown = Memo::new(move |_| build(&outputs.get()));
+    // One product's graph, at the rate the whole plan makes it at, or 60/min when the plan does not use it
+    let product_graph = Callback::new(move |product: String| {
+        untrack(|| {
+            let rate = breakdown.with(|nodes| product_rate(nodes, &product)).unwrap_or(60.0);
+            build(&[(product, rate)])
+        })
+    });
+
+    let warnings = Memo::new(move |_| {
+        let recipes = store.with(|s| s.recipes.clone());
+        let item_names = store.item_names().get();
+        let raw_resources = store.raw_resources().get();
+        let unreachable = compute_unreachable_items(&recipes, &enabled_recipes.get(), &raw_resources);
+        let mut warnings: Vec<String> = breakdown.get().iter().filter_map(|root| {
+            let mut blocked = Vec::new();
+            collect_unproducible(root, &raw_resources, &mut blocked);
+            if blocked.is_empty() {
+                return None;
+            }
+            let details = blocked.iter().map(|item| {
+                let label = item_label(&item_names, item);
+                match unreachable.iter().find(|u| &u.item == item).map(|u| &u.missing) {
+                    Some(MissingLink::NoEnabledRecipe { disabled_recipes }) if !disabled_recipes.is_empty() => {
+                        format!("{} (enable one of: {})", label, disabled_recipes.join(", "))
+                    }
+                    _ => format!("{} (no recipe in dataset)", label),
+                }
+            }).collect::<Vec<_>>().join("; ");
+            Some(format!(
+                "{} depends on items that cannot be produced: {}",
+                item_label(&item_names, &root.product),
+                details,
+            ))
+        }).collect();
+        // Supplies that fall short of what the breakdown draws from them
+        let (_, drawn) = leaf_totals(&breakdown.get(), &raw_resources);
+        for (item, supplied) in plan.supplies.get() {
+            let needed = drawn.get(&item).copied().unwrap_or(0.0);
+            if needed > supplied + 1e-6 {
+                warnings.push(format!(
+                    "{} is supplied at {:.2} but {:.2} is needed",
+                    item_label(&item_names, &item),
+                    supplied,
+                    needed,
+                ));
+            }
+        }
+        warnings
     });
 
     view! {
         <div class="overflow-x-auto">
+            {move || warnings.get().into_iter().map(|w| view! {
+                <div role="alert" class="alert alert-warning mt-2">{w}</div>
+            }).collect::<Vec<_>>()}
             <table class="table table-zebra w-full mt-4">
                 <thead>
                     <tr>
                         <th>Product</th>
-                        <th>Total Rate (items/min)</th>
+                        <th>{move || format!("Total Rate ({})", store.units().get().rate)}</th>
                         <th>Recipe</th>
                         <th>Machine</th>
+                        <th>Clock (%)</th>
                         <th>Machines Needed</th>
                     </tr>
                 </thead>
@@ -119,18 +112,85 @@ pub fn BreakdownView(
                         for node in breakdown.get().iter() {
                             flatten_tree(node, 0, &mut flat);
                         }
-                        flat.into_iter().map(|(depth, node)| view! {
-                            <tr>
-                                <td style={format!("padding-left:{}em;", depth * 2)}>{node.product.clone()}</td>
-                                <td>{format!("{:.2}", node.rate)}</td>
-                                <td>{node.recipe_name.clone().unwrap_or("(Raw Resource)".to_string())}</td>
-                                <td>{node.machine.clone().unwrap_or("-".to_string())}</td>
-                                <td>{node.machines_needed.map(|m| format!("{:.2}", m)).unwrap_or("-".to_string())}</td>
-                            </tr>
+                        let item_names = store.item_names().get();
+                        // The graph jumps to the first row of each product
+                        let mut anchored = HashSet::new();
+                        flat.into_iter().map(|(depth, node)| {
+                            let id = anchored.insert(node.product.clone()).then(|| breakdown_row_id(&node.product));
+                            let row_product = node.product.clone();
+                            let highlighted = move || selected.with(|s| s.as_ref() == Some(&row_product));
+                            view! {
+                                <tr id=id class=move || if highlighted() { "bg-warning/30" } else { "" }>
+                                    <td style={format!("padding-left:{}em;", depth * 2)}>{item_label(&item_names, &node.product)}</td>
+                                    <td>{format!("{:.2}", node.rate)}</td>
+                                    <td>{if node.alternatives.len() > 1 {
+                                        let product = node.product.clone();
+                                        let selected = recipe_choices.with(|c| c.get(&product).cloned());
+                                        view! {
+                                            <select
+                                                class="select select-bordered select-sm"
+                                                on:change=move |ev| {
+                                                    let id = event_target_value(&ev);
+                                                    recipe_choices.update(|c| {
+                                                        c.insert(product.clone(), id);
+                                                    });
+                                                }
+                                            >
+                                                {node.alternatives.iter().enumerate().map(|(i, (id, name))| view! {
+                                                    <option
+                                                        value=id.clone()
+                                                        selected=selected.as_ref().map_or(i == 0, |s| s == id)
+                                                    >
+                                                        {name.clone()}
+                                                    </option>
+                                                }).collect::<Vec<_>>()}
+                                            </select>
+                                        }.into_any()
+                                    } else if node.supplied {
+                                        let flow = (node.product.clone(), node.rate);
+                                        view! {
+                                            "(Supplied) "
+                                            <button class="btn btn-xs btn-outline" on:click=move |_| logistics.set(Some(flow.clone()))>
+                                                "Logistics"
+                                            </button>
+                                        }.into_any()
+                                    } else {
+                                        node.recipe_name.clone().unwrap_or_else(|| if store.raw_resources().with(|raw| is_raw_resource(raw, &node.product)) { "(Raw Resource)".to_string() } else { "(No enabled recipe)".to_string() }).into_any()
+                                    }}</td>
+                                    <td>{node.machine.clone().unwrap_or("-".to_string())}</td>
+                                    <td>{node.machine.is_some().then(|| {
+                                        let product = node.product.clone();
+                                        let clock = clocks.with(|c| c.get(&product).copied().unwrap_or(100.0));
+                                        view! {
+                                            <input
+                                                class="input input-bordered input-sm w-20"
+                                                type="number"
+                                                min="1"
+                                                step="1"
+                                                aria-label="Clock speed (%)"
+                                                value=clock.to_string()
+                                                on:change=move |ev| {
+                                                    let clock = event_target_value(&ev).parse().unwrap_or(100.0);
+                                                    clocks.update(|c| {
+                                                        if clock > 0.0 && clock != 100.0 {
+                                                            c.insert(product.clone(), clock);
+                                                        } else {
+                                                            c.remove(&product);
+                                                        }
+                                                    });
+                                                }
+                                            />
+                                        }
+                                    })}</td>
+                                    <td>{node.machines_needed.map(|m| format!("{:.2}", m)).unwrap_or("-".to_string())}</td>
+                                </tr>
+                            }
                         }).collect::<Vec<_>>()
                     }}
                 </tbody>
             </table>
+            <LogisticsCalculator flow=logistics />
+            <FlowGraphView breakdown=breakdown product_graph=product_graph selected=selected />
             <crate::components::summaries::RawResourceSummary breakdown=breakdown />
             <crate::components::summaries::BuildingSummary breakdown=breakdown />
         </div>
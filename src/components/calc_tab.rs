@@ -1,11 +1,12 @@
 use leptos::prelude::*;
-use crate::{components::breakdown::BreakdownView, model::{AppStore, AppStoreStoreFields}};
+use crate::{components::{breakdown::BreakdownView, factory_bar::FactoryBar, marginal_costs::MarginalCostView, plan_file_io::PlanFileButtons, plan_persistence::PlanSignals}, model::{item_label, AppStore, AppStoreStoreFields}};
 use reactive_stores::Store;
 
 #[component]
 pub fn CalcTab() -> impl IntoView {
     let (search, set_search) = signal(String::new());
-    let (outputs, set_outputs) = signal(Vec::<(String, f64)>::new());
+    let plan = use_context::<PlanSignals>().expect("PlanSignals context");
+    let (outputs, set_outputs) = (plan.outputs.read_only(), plan.outputs.write_only());
     let (show_autocomplete, set_show_autocomplete) = signal(false);
     let (highlighted, set_highlighted) = signal(None::<usize>);
     let store = use_context::<Store<AppStore>>().expect("AppStore context");
@@ -17,6 +18,7 @@ pub fn CalcTab() -> impl IntoView {
             Vec::new()
         } else {
             let mut seen = std::collections::HashSet::new();
+            let item_names = store.item_names().get();
             store
                 .recipes()
                 .into_iter()
@@ -27,22 +29,42 @@ pub fn CalcTab() -> impl IntoView {
                         .map(|o| o.item.clone())
                         .collect::<Vec<_>>()
                 })
-                .filter(|name| name.to_lowercase().contains(&search_val.to_lowercase()))
-                .filter(|name| !current_outputs.contains(name))
-                .filter(|name| seen.insert(name.clone()))
-                .collect()
+                .filter(|id| item_label(&item_names, id).to_lowercase().contains(&search_val.to_lowercase()))
+                .filter(|id| !current_outputs.contains(id))
+                .filter(|id| seen.insert(id.clone()))
+                .map(|id| {
+                    let name = item_label(&item_names, &id);
+                    (id, name)
+                })
+                .collect::<Vec<_>>()
         }
     });
-    let add_output = move |name: String| {
+    let supply_label = RwSignal::new(String::new());
+    let supply_rate = RwSignal::new(60.0);
+    let add_supply = move |_| {
+        let label = supply_label.get_untracked();
+        let Some(id) = store.item_names().with_untracked(|names| {
+            names.iter().find(|(_, name)| name.as_str() == label.trim()).map(|(id, _)| id.clone())
+        }) else {
+            return;
+        };
+        plan.supplies.update(|supplies| match supplies.iter_mut().find(|(item, _)| item == &id) {
+            Some(supply) => supply.1 = supply_rate.get_untracked(),
+            None => supplies.push((id, supply_rate.get_untracked())),
+        });
+        supply_label.set(String::new());
+    };
+    let add_output = move |id: String| {
         set_outputs.update(|outs| {
-            if !outs.iter().any(|(n, _)| n == &name) {
-                outs.push((name, 60.0));
+            if !outs.iter().any(|(n, _)| n == &id) {
+                outs.push((id, 60.0));
             }
         });
         set_search.set(String::new());
         set_show_autocomplete.set(false);
     };
     view! {
+        <FactoryBar />
         <div class="mb-4 relative">
             <input
                 class="input input-bordered w-full"
@@ -75,10 +97,8 @@ pub fn CalcTab() -> impl IntoView {
                                 });
                         }
                         "Enter" => {
-                            if let Some(i) = highlighted.get() {
-                                if let Some(name) = results.get(i) {
-                                    add_output(name.clone());
-                                }
+                            if let Some((id, _)) = highlighted.get().and_then(|i| results.get(i)) {
+                                add_output(id.clone());
                             }
                         }
                         _ => {}
@@ -91,9 +111,7 @@ pub fn CalcTab() -> impl IntoView {
                         let results = search_results_memo.get();
                         (0..results.len())
                             .map(|i| {
-                                let name = results[i].clone();
-                                let add_output = add_output.clone();
-                                let set_highlighted = set_highlighted.clone();
+                                let (id, name) = results[i].clone();
                                 let highlighted = highlighted.get();
                                 view! {
                                     <li
@@ -105,7 +123,7 @@ pub fn CalcTab() -> impl IntoView {
                                                 ""
                                             },
                                         )
-                                        on:mousedown=move |_| add_output(name.clone())
+                                        on:mousedown=move |_| add_output(id.clone())
                                         on:mouseover=move |_| set_highlighted.set(Some(i))
                                     >
                                         {name.clone()}
@@ -120,12 +138,13 @@ pub fn CalcTab() -> impl IntoView {
             </ul>
         </div>
         <h2 class="text-xl font-semibold mt-8 mb-2">Outputs</h2>
+        <PlanFileButtons />
         <div class="overflow-x-auto">
             <table class="table table-zebra w-full">
                 <thead>
                     <tr>
                         <th>Product</th>
-                        <th>Rate (items/min)</th>
+                        <th>{move || format!("Rate ({})", store.units().get().rate)}</th>
                         <th>Actions</th>
                     </tr>
                 </thead>
@@ -135,12 +154,11 @@ pub fn CalcTab() -> impl IntoView {
                             .get()
                             .iter()
                             .enumerate()
-                            .map(|(i, (name, rate))| {
-                                let name = name.clone();
-                                let set_outputs = set_outputs.clone();
+                            .map(|(i, (id, rate))| {
+                                let name = store.item_names().with(|names| item_label(names, id));
                                 view! {
                                     <tr>
-                                        <td>{name.clone()}</td>
+                                        <td>{name}</td>
                                         <td>
                                             <input
                                                 class="input input-bordered w-24"
@@ -175,9 +193,141 @@ pub fn CalcTab() -> impl IntoView {
                 </tbody>
             </table>
         </div>
+        <h2 class="text-xl font-semibold mt-8 mb-2">Supplies</h2>
+        <p class="text-sm opacity-70 mb-2">"Items brought in from outside or from another factory of the project; the breakdown uses them instead of producing them."</p>
+        <datalist id="plan-items">
+            {move || {
+                let mut labels: Vec<String> = store.item_names().get().into_values().collect();
+                labels.sort();
+                labels.dedup();
+                labels.into_iter().map(|label| view! { <option value=label></option> }).collect::<Vec<_>>()
+            }}
+        </datalist>
+        <div class="flex items-center gap-2 mb-2">
+            <input
+                class="input input-bordered input-sm w-64"
+                type="text"
+                list="plan-items"
+                placeholder="Item..."
+                prop:value=move || supply_label.get()
+                on:input=move |ev| supply_label.set(event_target_value(&ev))
+            />
+            <input
+                class="input input-bordered input-sm w-24"
+                type="number"
+                min="0"
+                step="0.1"
+                aria-label="Supplied rate"
+                prop:value=move || supply_rate.get().to_string()
+                on:input=move |ev| supply_rate.set(event_target_value(&ev).parse().unwrap_or(0.0))
+            />
+            <button class="btn btn-sm" on:click=add_supply>"Add supply"</button>
+        </div>
+        <div class="overflow-x-auto">
+            <table class="table table-zebra w-full">
+                <thead>
+                    <tr>
+                        <th>Item</th>
+                        <th>{move || format!("Rate ({})", store.units().get().rate)}</th>
+                        <th>From</th>
+                        <th>Actions</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        plan.supplies
+                            .get()
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, (id, rate))| {
+                                let name = store.item_names().with(|names| item_label(names, &id));
+                                let source = plan.supply_sources.with(|sources| sources.get(&id).cloned().unwrap_or_default());
+                                // The factories of the project this one can take the item from
+                                let others: Vec<String> = plan.factories.with(|factories| {
+                                    let active = plan.active.get();
+                                    factories.iter().enumerate().filter(|(j, _)| *j != active).map(|(_, f)| f.name.clone()).collect()
+                                });
+                                let source_id = id.clone();
+                                let remove_id = id.clone();
+                                view! {
+                                    <tr>
+                                        <td>{name}</td>
+                                        <td>
+                                            <input
+                                                class="input input-bordered w-24"
+                                                type="number"
+                                                min="0"
+                                                step="0.1"
+                                                value=rate.to_string()
+                                                on:input=move |ev| {
+                                                    let val = event_target_value(&ev).parse().unwrap_or(0.0);
+                                                    plan.supplies.update(|supplies| supplies[i].1 = val);
+                                                }
+                                            />
+                                        </td>
+                                        <td>
+                                            <select
+                                                class="select select-bordered select-sm"
+                                                aria-label="Supplied from"
+                                                on:change=move |ev| {
+                                                    let from = event_target_value(&ev);
+                                                    plan.supply_sources.update(|sources| {
+                                                        if from.is_empty() {
+                                                            sources.remove(&source_id);
+                                                        } else {
+                                                            sources.insert(source_id.clone(), from);
+                                                        }
+                                                    });
+                                                }
+                                            >
+                                                <option value="" selected=source.is_empty()>"Outside the project"</option>
+                                                {others
+                                                    .into_iter()
+                                                    .map(|name| {
+                                                        let selected = name == source;
+                                                        view! { <option value=name.clone() selected=selected>{name.clone()}</option> }
+                                                    })
+                                                    .collect::<Vec<_>>()}
+                                            </select>
+                                        </td>
+                                        <td>
+                                            <button
+                                                class="btn btn-error btn-sm"
+                                                on:click=move |_| {
+                                                    plan.supplies.update(|supplies| {
+                                                        supplies.remove(i);
+                                                    });
+                                                    plan.supply_sources.update(|sources| {
+                                                        sources.remove(&remove_id);
+                                                    });
+                                                }
+                                            >
+                                                Remove
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </tbody>
+            </table>
+        </div>
+        <h2 class="text-xl font-semibold mt-8 mb-2">Notes</h2>
+        <textarea
+            class="textarea textarea-bordered w-full"
+            rows="3"
+            placeholder="Notes saved with the plan..."
+            prop:value=move || plan.notes.get()
+            on:change=move |ev| plan.notes.set(event_target_value(&ev))
+        ></textarea>
         <h2 class="text-xl font-semibold mt-8 mb-2">Breakdown</h2>
         <div class="overflow-x-auto">
             <BreakdownView outputs=outputs />
         </div>
+        <h2 class="text-xl font-semibold mt-8 mb-2">Marginal Costs</h2>
+        <div class="overflow-x-auto">
+            <MarginalCostView outputs=outputs />
+        </div>
     }
 }
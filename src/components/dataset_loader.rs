@@ -0,0 +1,366 @@
+use leptos::{prelude::*, task::spawn_local};
+use reactive_stores::Store;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{File, HtmlInputElement};
+use crate::{
+    adapters::{
+        encoding::decode_text,
+        error::LoadedRecipes,
+        game_adapter::{detect_game_adapter, game_adapter, DatasetVersion, GameAdapter, GAME_ADAPTERS},
+    },
+    model::{
+        dataset::{CompiledDataset, DatasetSource, StoredDataset, DATASET_STORAGE_KEY},
+        localization::DEFAULT_LOCALE,
+        AppStore, AppStoreStoreFields,
+    },
+};
+
+pub(crate) fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+fn load_stored_dataset() -> Option<StoredDataset> {
+    let json = local_storage()?.get_item(DATASET_STORAGE_KEY).ok().flatten()?;
+    StoredDataset::from_json(&json)
+}
+
+fn save_stored_dataset(dataset: &StoredDataset) -> Result<(), String> {
+    let storage = local_storage().ok_or("local storage is unavailable")?;
+    storage
+        .set_item(DATASET_STORAGE_KEY, &dataset.to_json())
+        .map_err(|_| "local storage is full or disabled".to_string())
+}
+
+fn clear_stored_dataset() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(DATASET_STORAGE_KEY);
+    }
+}
+
+/// Fetch a compiled dataset served next to the app
+async fn fetch_bundled_dataset(file: &str) -> Result<CompiledDataset, String> {
+    let url = format!("/{}", file);
+    let response = gloo_net::http::Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.ok() {
+        return Err(format!("{} returned HTTP {}", url, response.status()));
+    }
+    let bytes = response.binary().await.map_err(|e| e.to_string())?;
+    CompiledDataset::from_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+/// A dataset with no recipes that only records the selected game and version
+fn empty_dataset(adapter: &dyn GameAdapter, version: &DatasetVersion, warnings: Vec<String>) -> CompiledDataset {
+    CompiledDataset {
+        warnings,
+        ..LoadedRecipes::default().into_dataset(adapter, version.id, DEFAULT_LOCALE)
+    }
+}
+
+/// Switch to a game version's bundled dataset, or to an empty one until the user loads a file
+async fn use_bundled_dataset(store: Store<AppStore>, adapter: &'static dyn GameAdapter, version: &'static DatasetVersion) {
+    let Some(file) = version.bundled_file else {
+        store.update(|s| s.replace_dataset(empty_dataset(adapter, version, vec![]), DatasetSource::Missing));
+        return;
+    };
+    match fetch_bundled_dataset(file).await {
+        Ok(dataset) => store.update(|s| s.replace_dataset(dataset, DatasetSource::Bundled)),
+        Err(e) => store.update(|s| {
+            let warnings = vec![format!("No recipes loaded: {}", e)];
+            s.replace_dataset(empty_dataset(adapter, version, warnings), DatasetSource::Bundled)
+        }),
+    }
+}
+
+/// Load the dataset the user supplied last time, falling back to the first game's bundled one.
+/// When a game and version are requested, e.g. by a shared plan, the stored dataset is only used if it matches them.
+pub fn load_initial_dataset(store: Store<AppStore>, requested: Option<(String, String)>) {
+    let stored = load_stored_dataset().filter(|stored| {
+        requested
+            .as_ref()
+            .is_none_or(|(game, version)| &stored.dataset.game == game && &stored.dataset.version == version)
+    });
+    if let Some(stored) = stored {
+        store.update(|s| s.replace_dataset(stored.dataset, DatasetSource::Uploaded { file_name: stored.file_name }));
+        return;
+    }
+    let (adapter, version) = requested
+        .and_then(|(game, version)| {
+            let adapter = game_adapter(&game)?;
+            Some((adapter, adapter.version(&version).unwrap_or(adapter.default_version())))
+        })
+        .unwrap_or((GAME_ADAPTERS[0], GAME_ADAPTERS[0].default_version()));
+    spawn_local(use_bundled_dataset(store, adapter, version));
+}
+
+pub(crate) async fn read_file(file: &File) -> Result<Vec<u8>, String> {
+    let buffer = JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|e| format!("could not read file: {:?}", e))?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// The data file loaded this session and the mod files layered on it, kept so they can be re-read
+#[derive(Debug, Clone)]
+struct SessionFiles {
+    file_name: String,
+    text: String,
+    /// Mod name and text of each mod file, in load order
+    mods: Vec<(String, String)>,
+}
+
+impl SessionFiles {
+    /// Name shown as the dataset's source
+    fn label(&self) -> String {
+        if self.mods.is_empty() {
+            return self.file_name.clone();
+        }
+        let mods = self.mods.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        format!("{} + {}", self.file_name, mods.join(", "))
+    }
+}
+
+/// Parse data files with the selected game's adapter, or with the adapter whose format they match
+fn parse_data_files(files: &SessionFiles, game: &str, version: &str) -> Result<CompiledDataset, String> {
+    let selected = game_adapter(game);
+    let adapter = selected
+        .filter(|a| a.detect(&files.text))
+        .or_else(|| detect_game_adapter(&files.text))
+        .or(selected)
+        .ok_or_else(|| format!("unknown game {}", game))?;
+    let version = Some(adapter)
+        .filter(|a| a.id() == game)
+        .and_then(|a| a.version(version))
+        .unwrap_or(adapter.default_version());
+    let dataset = if files.mods.is_empty() {
+        adapter.dataset_from_text(&files.text, version.id, DEFAULT_LOCALE)
+    } else {
+        adapter
+            .load_with_mods(&files.text, &files.mods, version.id)
+            .map(|loaded| loaded.into_dataset(adapter, version.id, DEFAULT_LOCALE))
+    };
+    dataset.map_err(|e| e.to_string())
+}
+
+/// Make a parsed user dataset the current, persisted one
+fn use_user_dataset(store: Store<AppStore>, file_name: String, dataset: CompiledDataset) -> Result<(), String> {
+    if dataset.recipes.is_empty() {
+        return Err(format!("{} contains no recipes", file_name));
+    }
+    let stored = StoredDataset::new(file_name.clone(), dataset);
+    // The dataset is still used for this session if it cannot be persisted
+    let saved = save_stored_dataset(&stored);
+    store.update(|s| s.replace_dataset(stored.dataset, DatasetSource::Uploaded { file_name }));
+    saved.map_err(|e| format!("Loaded, but not saved for next visit: {}", e))
+}
+
+/// Parse the session's files as the given game version and make them the current dataset
+fn use_session_files(store: Store<AppStore>, files: &SessionFiles, game: &str, version: &str) -> Result<(), String> {
+    use_user_dataset(store, files.label(), parse_data_files(files, game, version)?)
+}
+
+fn selected_game(store: Store<AppStore>) -> (String, String) {
+    store.with_untracked(|s| (s.game.clone(), s.dataset_version.clone()))
+}
+
+/// Read a user-supplied file; returns it when it was parsed, so other versions can be read from it later
+async fn load_user_file(store: Store<AppStore>, file: File) -> Result<Option<SessionFiles>, String> {
+    let file_name = file.name();
+    let bytes = read_file(&file).await?;
+    // A dataset compiled by `build_dataset`, or a data file one of the game adapters reads
+    if CompiledDataset::is_compiled(&bytes) {
+        let dataset = CompiledDataset::from_bytes(&bytes).map_err(|e| e.to_string())?;
+        return use_user_dataset(store, file_name, dataset).map(|_| None);
+    }
+    let text = decode_text(&bytes).map_err(|e| e.to_string())?;
+    let files = SessionFiles { file_name, text, mods: vec![] };
+    let (game, version) = selected_game(store);
+    use_session_files(store, &files, &game, &version)?;
+    Ok(Some(files))
+}
+
+/// Layer a mod file, named after the file, on top of the session's files
+async fn add_mod_file(store: Store<AppStore>, mut files: SessionFiles, file: File) -> Result<SessionFiles, String> {
+    let file_name = file.name();
+    let mod_name = file_name.rsplit_once('.').map_or(file_name.as_str(), |(stem, _)| stem).to_string();
+    let text = decode_text(&read_file(&file).await?).map_err(|e| e.to_string())?;
+    files.mods.push((mod_name, text));
+    let (game, version) = selected_game(store);
+    use_session_files(store, &files, &game, &version)?;
+    Ok(files)
+}
+
+#[component]
+pub fn DatasetLoader() -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    let loading = RwSignal::new(false);
+    let dragging = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+    // Files loaded this session, re-read when another version is selected or a mod is added
+    let session_files = RwSignal::new(None::<SessionFiles>);
+    let load_file = move |file: File| {
+        error.set(None);
+        loading.set(true);
+        spawn_local(async move {
+            match load_user_file(store, file).await {
+                Ok(files) => session_files.set(files),
+                Err(e) => error.set(Some(e)),
+            }
+            loading.set(false);
+        });
+    };
+    let load_mod = move |file: File| {
+        let Some(files) = session_files.get_untracked() else {
+            return;
+        };
+        error.set(None);
+        loading.set(true);
+        spawn_local(async move {
+            match add_mod_file(store, files, file).await {
+                Ok(files) => session_files.set(Some(files)),
+                Err(e) => error.set(Some(e)),
+            }
+            loading.set(false);
+        });
+    };
+    let remove_mods = move |_| {
+        let Some(mut files) = session_files.get_untracked() else {
+            return;
+        };
+        files.mods.clear();
+        let (game, version) = selected_game(store);
+        match use_session_files(store, &files, &game, &version) {
+            Ok(()) => error.set(None),
+            Err(e) => error.set(Some(e)),
+        }
+        session_files.set(Some(files));
+    };
+    let select = move |game: String, version: Option<String>| {
+        let Some(adapter) = game_adapter(&game) else {
+            return;
+        };
+        let version = version.and_then(|v| adapter.version(&v)).unwrap_or(adapter.default_version());
+        error.set(None);
+        if let Some(files) = session_files.get_untracked().filter(|f| adapter.detect(&f.text)) {
+            if let Err(e) = use_session_files(store, &files, adapter.id(), version.id) {
+                error.set(Some(e));
+            }
+            return;
+        }
+        clear_stored_dataset();
+        loading.set(true);
+        spawn_local(async move {
+            use_bundled_dataset(store, adapter, version).await;
+            loading.set(false);
+        });
+    };
+    let reset = move |_| {
+        session_files.set(None);
+        select(store.game().get_untracked(), Some(store.dataset_version().get_untracked()));
+    };
+    let current_adapter = move || game_adapter(&store.game().get());
+    let can_add_mods = move || {
+        current_adapter().is_some_and(|a| a.supports_mods())
+            && session_files.with(|f| f.as_ref().is_some_and(|f| current_adapter().is_some_and(|a| a.detect(&f.text))))
+    };
+    let has_mods = move || session_files.with(|f| f.as_ref().is_some_and(|f| !f.mods.is_empty()));
+    let has_bundled = move || {
+        current_adapter()
+            .and_then(|a| a.version(&store.dataset_version().get()))
+            .is_some_and(|v| v.bundled_file.is_some())
+    };
+
+    view! {
+        <div
+            class=move || format!(
+                "flex flex-wrap items-center gap-2 p-2 mb-6 border-2 border-dashed rounded {}",
+                if dragging.get() { "border-primary bg-base-100" } else { "border-base-300" },
+            )
+            on:dragover=move |ev| {
+                ev.prevent_default();
+                dragging.set(true);
+            }
+            on:dragleave=move |_| dragging.set(false)
+            on:drop=move |ev| {
+                ev.prevent_default();
+                dragging.set(false);
+                if let Some(file) = ev.data_transfer().and_then(|dt| dt.files()).and_then(|files| files.get(0)) {
+                    load_file(file);
+                }
+            }
+        >
+            <select
+                class="select select-bordered select-sm"
+                aria-label="Game"
+                disabled=move || loading.get()
+                on:change=move |ev| select(event_target_value(&ev), None)
+            >
+                {GAME_ADAPTERS.iter().map(|adapter| {
+                    let id = adapter.id();
+                    view! { <option value=id selected=move || store.game().get() == id>{adapter.name()}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+            <select
+                class="select select-bordered select-sm"
+                aria-label="Dataset version"
+                disabled=move || loading.get()
+                on:change=move |ev| select(store.game().get_untracked(), Some(event_target_value(&ev)))
+            >
+                {move || current_adapter().map(|adapter| adapter.versions().iter().map(|version| {
+                    let id = version.id;
+                    view! { <option value=id selected=move || store.dataset_version().get() == id>{version.name}</option> }
+                }).collect::<Vec<_>>())}
+            </select>
+            <span class="text-sm">
+                <span class="font-semibold">Dataset: </span>
+                {move || store.dataset().get().to_string()}
+            </span>
+            <label class="btn btn-sm">
+                "Load data file"
+                <input
+                    type="file"
+                    accept=".json,.dataset,application/json"
+                    class="hidden"
+                    on:change=move |ev| {
+                        let input: HtmlInputElement = event_target(&ev);
+                        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                            load_file(file);
+                        }
+                        input.set_value("");
+                    }
+                />
+            </label>
+            <Show when=can_add_mods>
+                <label class="btn btn-sm btn-outline">
+                    "Add mod file"
+                    <input
+                        type="file"
+                        accept=".json,application/json"
+                        class="hidden"
+                        on:change=move |ev| {
+                            let input: HtmlInputElement = event_target(&ev);
+                            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                                load_mod(file);
+                            }
+                            input.set_value("");
+                        }
+                    />
+                </label>
+            </Show>
+            <Show when=has_mods>
+                <button class="btn btn-sm btn-ghost" on:click=remove_mods>"Remove mods"</button>
+            </Show>
+            <Show when=move || matches!(store.dataset().get(), DatasetSource::Uploaded { .. }) && has_bundled()>
+                <button class="btn btn-sm btn-ghost" on:click=reset>"Use bundled dataset"</button>
+            </Show>
+            <span class="text-xs opacity-70">
+                {move || current_adapter().map(|a| format!("or drop {} here", a.data_file()))}
+            </span>
+            {move || (loading.get() || store.dataset().get() == DatasetSource::Loading)
+                .then(|| view! { <span class="loading loading-spinner loading-sm"></span> })}
+            {move || error.get().map(|e| view! { <span class="text-error text-sm">{e}</span> })}
+        </div>
+    }
+}
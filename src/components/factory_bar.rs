@@ -0,0 +1,57 @@
+use leptos::prelude::*;
+use crate::components::plan_persistence::PlanSignals;
+
+/// Switch between the factories of the project, add, rename and remove them
+#[component]
+pub fn FactoryBar() -> impl IntoView {
+    let plan = use_context::<PlanSignals>().expect("PlanSignals context");
+    let rename_error = RwSignal::new(false);
+    view! {
+        <div class="flex flex-wrap items-center gap-2 mb-4">
+            <div role="tablist" class="tabs tabs-bordered">
+                {move || {
+                    let active = plan.active.get();
+                    plan.factories
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, factory)| view! {
+                            <button
+                                role="tab"
+                                class=format!("tab{}", if i == active { " tab-active" } else { "" })
+                                aria-selected=i == active
+                                on:click=move |_| {
+                                    rename_error.set(false);
+                                    plan.select_factory(i);
+                                }
+                            >
+                                {factory.name}
+                            </button>
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </div>
+            <button class="btn btn-sm" on:click=move |_| plan.update_factories(|p| p.add_factory())>"Add factory"</button>
+            <input
+                class=move || format!("input input-bordered input-sm w-48{}", if rename_error.get() { " input-error" } else { "" })
+                type="text"
+                aria-label="Factory name"
+                prop:value=move || plan.factories.with(|factories| factories[plan.active.get()].name.clone())
+                on:change=move |ev| {
+                    let name = event_target_value(&ev);
+                    let mut renamed = false;
+                    plan.update_factories(|p| renamed = p.rename_factory(p.active, &name));
+                    rename_error.set(!renamed);
+                }
+            />
+            <Show when=move || plan.factories.with(|factories| factories.len() > 1)>
+                <button
+                    class="btn btn-sm btn-error btn-outline"
+                    on:click=move |_| plan.update_factories(|p| p.remove_factory(p.active))
+                >
+                    "Remove factory"
+                </button>
+            </Show>
+        </div>
+    }
+}
@@ -2,15 +2,49 @@
 use crate::components::calc_tab::CalcTab;
 use crate::components::recipes_tab::RecipesTab;
 use crate::components::analysis_tab::AnalysisTab;
+use crate::components::language_selector::LanguageSelector;
+use crate::components::dataset_loader::DatasetLoader;
+use crate::components::recipe_editor::RecipeEditorTab;
+use crate::components::plan_persistence::PlanSignals;
+use crate::components::share_link::ShareLink;
+use crate::components::project_view::ProjectView;
+use crate::adapters::{game_adapter::GameAdapter, satisfactory_adapter::SatisfactoryAdapter};
+use crate::model::{AppStore, AppStoreStoreFields};
 
 use leptos::prelude::*;
+use reactive_stores::Store;
 
 #[component]
 pub fn FactoryPlannerApp() -> impl IntoView {
-    let (tab, set_tab) = signal("calc".to_string());
+    let plan = use_context::<PlanSignals>().expect("PlanSignals context");
+    let (tab, set_tab) = (plan.tab.read_only(), plan.tab.write_only());
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
     view! {
         <div class="container mx-auto p-4">
-            <h1 class="text-3xl font-bold mb-6">Factory Planner</h1>
+            <div class="flex items-center justify-between mb-6">
+                <h1 class="text-3xl font-bold">Factory Planner</h1>
+                <div class="flex items-center gap-4">
+                    <ShareLink />
+                    // Translated labels are only available from Satisfactory's localized Docs files
+                    <Show when=move || store.game().get() == SatisfactoryAdapter.id()>
+                        <LanguageSelector />
+                    </Show>
+                </div>
+            </div>
+            <DatasetLoader />
+            {move || {
+                let warnings = store.load_warnings().get();
+                (!warnings.is_empty()).then(|| view! {
+                    <div role="alert" class="alert alert-warning mb-6 flex-col items-start">
+                        <span class="font-semibold">
+                            {format!("{} problem(s) while loading the dataset; affected recipes were skipped", warnings.len())}
+                        </span>
+                        <ul class="list-disc ml-6 text-sm max-h-40 overflow-auto">
+                            {warnings.into_iter().map(|w| view! { <li>{w}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    </div>
+                })
+            }}
             <div role="tablist" class="tabs tabs-boxed mb-6">
                 <button
                     role="tab"
@@ -20,6 +54,14 @@ pub fn FactoryPlannerApp() -> impl IntoView {
                 >
                     Calculator
                 </button>
+                <button
+                    role="tab"
+                    class=move || format!("tab{}", if tab.get() == "project" { " tab-active" } else { "" })
+                    aria-selected=move || tab.get() == "project"
+                    on:click=move |_| set_tab.set("project".to_string())
+                >
+                    Project
+                </button>
                 <button
                     role="tab"
                     class=move || format!("tab{}", if tab.get() == "recipes" { " tab-active" } else { "" })
@@ -36,16 +78,30 @@ pub fn FactoryPlannerApp() -> impl IntoView {
                 >
                     Recipe Analysis
                 </button>
+                <button
+                    role="tab"
+                    class=move || format!("tab{}", if tab.get() == "custom" { " tab-active" } else { "" })
+                    aria-selected=move || tab.get() == "custom"
+                    on:click=move |_| set_tab.set("custom".to_string())
+                >
+                    Custom Recipes
+                </button>
             </div>
             <div id="tab-calc-content" style=move || if tab.get() == "calc" { "" } else { "display:none;" }>
                 <CalcTab />
             </div>
+            <div id="tab-project-content" style=move || if tab.get() == "project" { "" } else { "display:none;" }>
+                <ProjectView />
+            </div>
             <div id="tab-recipes-content" style=move || if tab.get() == "recipes" { "" } else { "display:none;" }>
                 <RecipesTab />
             </div>
             <div id="tab-analysis-content" style=move || if tab.get() == "analysis" { "" } else { "display:none;" }>
                 <AnalysisTab />
             </div>
+            <div id="tab-custom-content" style=move || if tab.get() == "custom" { "" } else { "display:none;" }>
+                <RecipeEditorTab />
+            </div>
         </div>
     }
 }
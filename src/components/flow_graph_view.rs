@@ -0,0 +1,188 @@
+use leptos::prelude::*;
+use reactive_stores::Store;
+use crate::{
+    components::plan_file_io::download,
+    model::{
+        breakdown::BreakdownNode,
+        flow_graph::{build_flow_graph, layout_flow_graph, FlowEdge, FlowGraphLayout, FlowNode, FlowNodeKind, GraphLayout},
+        graph_export::{export_flow_graph, GraphFormat},
+        item_label, AppStore, AppStoreStoreFields,
+    },
+};
+
+/// Id of the breakdown table row a graph node jumps to
+pub fn breakdown_row_id(product: &str) -> String {
+    format!("breakdown-row-{}", product)
+}
+
+fn node_class(kind: FlowNodeKind) -> &'static str {
+    match kind {
+        FlowNodeKind::Recipe => "fill-base-200 stroke-base-content",
+        FlowNodeKind::RawResource => "fill-success/30 stroke-success",
+        FlowNodeKind::Supplied => "fill-info/30 stroke-info",
+        FlowNodeKind::Unproducible => "fill-error/30 stroke-error",
+    }
+}
+
+/// Node and edge weights of the graph next to where the layout put them, by index; kept instead of
+/// the petgraph graph so it can be memoized
+#[derive(Clone, PartialEq)]
+struct PlacedGraph {
+    nodes: Vec<FlowNode>,
+    edges: Vec<FlowEdge>,
+    layout: FlowGraphLayout,
+    kind: GraphLayout,
+}
+
+/// The breakdown drawn as an SVG graph; clicking a node selects its product and scrolls to its table row.
+/// `product_graph` builds the trees of one product for exporting its graph alone.
+#[component]
+pub fn FlowGraphView(
+    breakdown: Memo<Vec<BreakdownNode>>,
+    product_graph: Callback<String, Vec<BreakdownNode>>,
+    selected: RwSignal<Option<String>>,
+) -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    let layout = RwSignal::new(GraphLayout::Layered);
+    // Laid out again only when the breakdown or the layout changes, not on every render
+    let placed = Memo::new(move |_| {
+        let graph = store.raw_resources().with(|raw| build_flow_graph(&breakdown.get(), raw));
+        let kind = layout.get();
+        let layout = layout_flow_graph(&graph, kind);
+        let (nodes, edges) = graph.into_nodes_edges();
+        PlacedGraph {
+            nodes: nodes.into_iter().map(|n| n.weight).collect(),
+            edges: edges.into_iter().map(|e| e.weight).collect(),
+            layout,
+            kind,
+        }
+    });
+    let select = move |product: String| {
+        if let Some(row) = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.get_element_by_id(&breakdown_row_id(&product)))
+        {
+            row.scroll_into_view();
+        }
+        selected.set(Some(product));
+    };
+    // Empty exports the whole plan, otherwise the graph making one product
+    let export_item = RwSignal::new(String::new());
+    let export_error = RwSignal::new(None::<String>);
+    let export = move |format: GraphFormat| {
+        let item = export_item.get_untracked();
+        let nodes = if item.is_empty() { breakdown.get_untracked() } else { product_graph.run(item.clone()) };
+        let text = store.with_untracked(|s| {
+            export_flow_graph(&build_flow_graph(&nodes, &s.raw_resources), format, &s.item_names, &s.units.rate)
+        });
+        let name = if item.is_empty() { "plan".to_string() } else { item };
+        let file_name = format!("{}-{}.{}", store.game().get_untracked(), name, format.extension());
+        export_error.set(download(&file_name, "text/plain", &text).err().map(|e| format!("Could not export: {}", e)));
+    };
+
+    view! {
+        <div class="mt-6">
+            <div class="flex items-center gap-2 mb-2">
+                <h3 class="text-lg font-semibold">Production Graph</h3>
+                <select
+                    class="select select-bordered select-sm"
+                    aria-label="Graph layout"
+                    on:change=move |ev| {
+                        if let Some(l) = GraphLayout::from_id(&event_target_value(&ev)) {
+                            layout.set(l);
+                        }
+                    }
+                >
+                    {GraphLayout::ALL.into_iter().map(|l| view! {
+                        <option value=l.id() selected=move || layout.get() == l>{l.name()}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+                <select
+                    class="select select-bordered select-sm"
+                    aria-label="Graph to export"
+                    on:change=move |ev| export_item.set(event_target_value(&ev))
+                >
+                    <option value="" selected=move || export_item.with(String::is_empty)>"Whole plan"</option>
+                    {move || {
+                        // Any item of the dataset, made with the enabled recipes
+                        let item_names = store.item_names().get();
+                        let mut products: Vec<(String, String)> = item_names
+                            .keys()
+                            .map(|id| (item_label(&item_names, id), id.clone()))
+                            .collect();
+                        products.sort();
+                        products.into_iter().map(|(label, product)| {
+                            let value = product.clone();
+                            view! {
+                                <option value=value selected=move || export_item.with(|i| *i == product)>{label}</option>
+                            }
+                        }).collect::<Vec<_>>()
+                    }}
+                </select>
+                {GraphFormat::ALL.into_iter().map(|format| view! {
+                    <button class="btn btn-sm" on:click=move |_| export(format)>{format!("Export {}", format.name())}</button>
+                }).collect::<Vec<_>>()}
+                {move || export_error.get().map(|e| view! { <span class="text-warning text-sm">{e}</span> })}
+            </div>
+            {move || {
+                let PlacedGraph { nodes, edges, layout: placed, kind } = placed.get();
+                if nodes.is_empty() {
+                    return view! { <div></div> }.into_any();
+                }
+                let sankey = kind == GraphLayout::Sankey;
+                let item_names = store.item_names().get();
+                let edges = edges.iter().zip(&placed.edges).map(|(flow, path)| {
+                    let ((x1, y1), (x2, y2)) = (path.from, path.to);
+                    let middle = (x1 + x2) / 2.0;
+                    view! {
+                        <path
+                            d=format!("M{:.1},{:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}", x1, y1, middle, y1, middle, y2, x2, y2)
+                            class="fill-none stroke-base-content"
+                            stroke-width=format!("{:.1}", path.width)
+                            stroke-opacity=if sankey { "0.3" } else { "0.5" }
+                        >
+                            <title>{format!("{}: {:.2}", item_label(&item_names, &flow.item), flow.rate)}</title>
+                        </path>
+                    }
+                }).collect::<Vec<_>>();
+                let nodes = nodes.into_iter().zip(placed.nodes.iter().copied()).map(|(node, b)| {
+                    let label = item_label(&item_names, &node.item);
+                    let detail = match &node.machine {
+                        Some(machine) => format!("{:.2} × {}", node.machines_needed, machine),
+                        None => format!("{:.2}/min", node.rate),
+                    };
+                    let (text_x, anchor_y) = if sankey { (b.x + b.width + 4.0, b.y + b.height / 2.0) } else { (b.x + 8.0, b.y + 18.0) };
+                    let product = node.item.clone();
+                    let is_selected = move || selected.with(|s| s.as_deref() == Some(node.item.as_str()));
+                    view! {
+                        <g class="cursor-pointer" on:click=move |_| select(product.clone())>
+                            <rect
+                                x=b.x
+                                y=b.y
+                                width=b.width
+                                height=b.height
+                                rx="4"
+                                class=node_class(node.kind)
+                                stroke-width=move || if is_selected() { "3" } else { "1" }
+                            />
+                            <text x=text_x y=anchor_y class="fill-base-content text-xs">{label}</text>
+                            <text x=text_x y=anchor_y + 16.0 class="fill-base-content text-xs opacity-70">{detail}</text>
+                        </g>
+                    }
+                }).collect::<Vec<_>>();
+                view! {
+                    <div class="overflow-auto border border-base-300 rounded max-h-[36rem]">
+                        <svg
+                            width=placed.width + 20.0
+                            height=placed.height + 20.0
+                            viewBox=format!("-10 -10 {:.0} {:.0}", placed.width + 20.0, placed.height + 20.0)
+                        >
+                            {edges}
+                            {nodes}
+                        </svg>
+                    </div>
+                }.into_any()
+            }}
+        </div>
+    }
+}
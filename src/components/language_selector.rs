@@ -0,0 +1,86 @@
+use leptos::{prelude::*, task::spawn_local};
+use reactive_stores::Store;
+use std::collections::HashMap;
+use crate::{
+    adapters::satisfactory_adapter::load_satisfactory_labels_from_bytes,
+    model::{
+        localization::{satisfactory_docs_file, DatasetLabels, SATISFACTORY_LOCALES},
+        AppStore, AppStoreStoreFields,
+    },
+};
+
+/// Fetch a localized Docs file from `/locales` and keep only its labels
+async fn fetch_labels(locale: &str) -> Result<DatasetLabels, String> {
+    let url = format!("/locales/{}", satisfactory_docs_file(locale));
+    let response = gloo_net::http::Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.ok() {
+        return Err(format!("{} returned HTTP {}", url, response.status()));
+    }
+    let bytes = response.binary().await.map_err(|e| e.to_string())?;
+    load_satisfactory_labels_from_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+#[component]
+pub fn LanguageSelector() -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    // Labels already loaded per locale, reseeded with the file's own labels whenever a dataset is loaded
+    let cache = StoredValue::new(HashMap::<String, DatasetLabels>::new());
+    Effect::new(move |_| {
+        store.dataset().track();
+        let seed = store.with_untracked(|s| (s.locale.clone(), DatasetLabels::from_recipes(&s.recipes, &s.item_names)));
+        cache.set_value(HashMap::from([seed]));
+    });
+    let loading = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+    let apply = move |locale: String, labels: &DatasetLabels| {
+        store.update(|s| {
+            labels.apply(&mut s.recipes, &mut s.item_names);
+            s.locale = locale;
+        });
+    };
+    let select_locale = move |locale: String| {
+        error.set(None);
+        if let Some(labels) = cache.with_value(|c| c.get(&locale).cloned()) {
+            apply(locale, &labels);
+            return;
+        }
+        loading.set(true);
+        spawn_local(async move {
+            match fetch_labels(&locale).await {
+                Ok(labels) => {
+                    cache.update_value(|c| {
+                        c.insert(locale.clone(), labels.clone());
+                    });
+                    apply(locale, &labels);
+                }
+                Err(e) => error.set(Some(format!("Could not load {}: {}", locale, e))),
+            }
+            loading.set(false);
+        });
+    };
+
+    view! {
+        <div class="flex items-center gap-2">
+            <select
+                class="select select-bordered select-sm"
+                disabled=move || loading.get()
+                on:change=move |ev| select_locale(event_target_value(&ev))
+            >
+                {SATISFACTORY_LOCALES.iter().map(|(code, name)| {
+                    let code = code.to_string();
+                    let selected_code = code.clone();
+                    view! {
+                        <option value=code selected=move || store.locale().get() == selected_code>
+                            {name.to_string()}
+                        </option>
+                    }
+                }).collect::<Vec<_>>()}
+            </select>
+            {move || loading.get().then(|| view! { <span class="loading loading-spinner loading-sm"></span> })}
+            {move || error.get().map(|e| view! { <span class="text-error text-sm">{e}</span> })}
+        </div>
+    }
+}
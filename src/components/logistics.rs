@@ -0,0 +1,175 @@
+use leptos::prelude::*;
+use reactive_stores::Store;
+use crate::model::{
+    item_label,
+    logistics::{size_fleet, size_trains, Cargo, ItemFlow, Vehicle, DRONE, FREIGHT_CAR, LOCOMOTIVE, TRUCK},
+    AppStore, AppStoreStoreFields,
+};
+
+/// One editable figure of a vehicle
+fn vehicle_input(vehicle: RwSignal<Vehicle>, get: fn(&Vehicle) -> f64, set: fn(&mut Vehicle, f64)) -> impl IntoView {
+    view! {
+        <td>
+            <input
+                class="input input-bordered input-xs w-20"
+                type="number"
+                min="0"
+                prop:value=move || vehicle.with(get).to_string()
+                on:input=move |ev| {
+                    let value = event_target_value(&ev).parse().unwrap_or(0.0);
+                    vehicle.update(|v| set(v, value));
+                }
+            />
+        </td>
+    }
+}
+
+/// Trains, trucks and drones for the selected imported item; closes by clearing `flow`
+#[component]
+pub fn LogisticsCalculator(flow: RwSignal<Option<(String, f64)>>) -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    let round_trip = RwSignal::new(300.0);
+    let max_cars = RwSignal::new(4_u32);
+    // Empty for fluids and items the dataset has no stack size for
+    let stack_size = RwSignal::new(String::new());
+    Effect::new(move |_| {
+        let size = flow.with(|f| f.as_ref().and_then(|(item, _)| store.stack_sizes().with(|s| s.get(item).copied())));
+        stack_size.set(size.map(|s| s.to_string()).unwrap_or_default());
+    });
+    let number = |ev: leptos::ev::Event| event_target_value(&ev).parse().unwrap_or(0.0);
+    // Satisfactory's vehicles to start with; other games enter their own
+    let car = RwSignal::new(FREIGHT_CAR);
+    let locomotive = RwSignal::new(LOCOMOTIVE);
+    let truck = RwSignal::new(TRUCK);
+    let drone = RwSignal::new(DRONE);
+
+    move || flow.get().map(|(item, rate)| {
+        let sized = move || {
+            let cargo = match stack_size.get().trim().parse::<u32>() {
+                Ok(size) => Cargo::Stacks(size),
+                Err(_) => Cargo::Fluid,
+            };
+            let flow = ItemFlow { rate, cargo, round_trip: round_trip.get() };
+            let cell = |text: Result<String, String>| match text {
+                Ok(text) => view! { <td>{text}</td> }.into_any(),
+                Err(e) => view! { <td class="text-warning">{e}</td> }.into_any(),
+            };
+            let train = size_trains(&flow, &car.get(), &locomotive.get(), max_cars.get()).map_err(|e| e.to_string());
+            let (truck, drone) = (truck.get(), drone.get());
+            let trucks = size_fleet(&flow, &truck).map_err(|e| e.to_string());
+            let drones = size_fleet(&flow, &drone).map_err(|e| e.to_string());
+            view! {
+                <table class="table table-xs w-full mt-2">
+                    <thead>
+                        <tr>
+                            <th>Vehicle</th>
+                            <th>Needed</th>
+                            <th>Trips per minute</th>
+                            <th>Use of capacity</th>
+                            <th>Fuel or power</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td>"Trains"</td>
+                            {cell(train.clone().map(|t| format!(
+                                "{} train(s) of {} car(s); {} platform(s) per station at {:.2}/min each",
+                                t.trains, t.cars_per_train, t.platforms, t.platform_throughput,
+                            )))}
+                            {cell(train.clone().map(|t| format!("{:.2}", f64::from(t.trains) * 60.0 / flow.round_trip)))}
+                            {cell(train.clone().map(|t| format!("{:.0}%", t.utilization * 100.0)))}
+                            {cell(train.map(|t| format!("{:.0} MW", t.power)))}
+                        </tr>
+                        {[(truck, trucks), (drone, drones)].into_iter().map(|(vehicle, fleet)| view! {
+                            <tr>
+                                <td>{format!("{}s", vehicle.name)}</td>
+                                {cell(fleet.clone().map(|f| if vehicle.name == DRONE.name {
+                                    format!("{} (one port each)", f.vehicles)
+                                } else {
+                                    f.vehicles.to_string()
+                                }))}
+                                {cell(fleet.clone().map(|f| format!("{:.2}", f.trips_per_minute)))}
+                                {cell(fleet.clone().map(|f| format!("{:.0}%", f.utilization * 100.0)))}
+                                {cell(fleet.map(|f| format!("{:.2} {}/min", f.fuel_per_minute, vehicle.fuel.unwrap_or("fuel"))))}
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            }
+        };
+        view! {
+            <div class="card bg-base-200 p-4 mt-4">
+                <div class="flex items-center justify-between">
+                    <h3 class="text-lg font-semibold">
+                        {store.item_names().with(|names| format!(
+                            "Logistics for {} at {:.2} {}",
+                            item_label(names, &item),
+                            rate,
+                            store.units().get().rate,
+                        ))}
+                    </h3>
+                    <button class="btn btn-sm btn-ghost" on:click=move |_| flow.set(None)>"Close"</button>
+                </div>
+                <div class="flex flex-wrap items-center gap-4 mt-2">
+                    <label class="flex items-center gap-2 text-sm">
+                        "Round trip (s)"
+                        <input
+                            class="input input-bordered input-sm w-24"
+                            type="number"
+                            min="1"
+                            prop:value=move || round_trip.get().to_string()
+                            on:input=move |ev| round_trip.set(number(ev))
+                        />
+                    </label>
+                    <label class="flex items-center gap-2 text-sm">
+                        "Stack size"
+                        <input
+                            class="input input-bordered input-sm w-24"
+                            type="number"
+                            min="1"
+                            placeholder="fluid"
+                            prop:value=move || stack_size.get()
+                            on:input=move |ev| stack_size.set(event_target_value(&ev))
+                        />
+                    </label>
+                    <label class="flex items-center gap-2 text-sm">
+                        "Max cars per train"
+                        <input
+                            class="input input-bordered input-sm w-20"
+                            type="number"
+                            min="1"
+                            prop:value=move || max_cars.get().to_string()
+                            on:input=move |ev| max_cars.set(number(ev) as u32)
+                        />
+                    </label>
+                </div>
+                {sized}
+                <details class="mt-2">
+                    <summary class="cursor-pointer text-sm">"Vehicle figures"</summary>
+                    <table class="table table-xs w-full mt-2">
+                        <thead>
+                            <tr>
+                                <th>Vehicle</th>
+                                <th>Slots</th>
+                                <th>Fluid capacity</th>
+                                <th>Power (MW)</th>
+                                <th>Fuel energy (MJ)</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {[car, locomotive, truck, drone].into_iter().map(|vehicle| view! {
+                                <tr>
+                                    <td>{vehicle.with_untracked(|v| v.name)}</td>
+                                    {vehicle_input(vehicle, |v| f64::from(v.slots), |v, x| v.slots = x as u32)}
+                                    {vehicle_input(vehicle, |v| v.fluid_capacity, |v, x| v.fluid_capacity = x)}
+                                    {vehicle_input(vehicle, |v| v.power, |v, x| v.power = x)}
+                                    {vehicle_input(vehicle, |v| v.fuel_energy, |v, x| v.fuel_energy = x)}
+                                </tr>
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                </details>
+            </div>
+        }
+    })
+}
@@ -0,0 +1,90 @@
+use leptos::prelude::*;
+use reactive_stores::Store;
+use std::collections::HashSet;
+use crate::model::{item_label, marginal_cost::compute_plan_optimum, AppStore, AppStoreStoreFields};
+
+#[component]
+pub fn MarginalCostView(
+    outputs: ReadSignal<Vec<(String, f64)>>,
+) -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("Store<AppStore> context");
+    let enabled_recipes = use_context::<Memo<HashSet<String>>>().expect("enabled_recipes context");
+    let optimum = Memo::new(move |_| {
+        let recipes = store.with(|s| s.recipes.clone());
+        let enabled = enabled_recipes.get();
+        let outputs = outputs.get();
+        if outputs.is_empty() {
+            return Ok(None);
+        }
+        compute_plan_optimum(&recipes, &enabled, &outputs, &store.raw_resources().get())
+            .map(Some)
+            .map_err(|e| e.to_string())
+    });
+
+    view! {
+        <div class="mt-6">
+            {move || match optimum.get() {
+                Ok(None) => view! { <div></div> }.into_any(),
+                Err(e) => view! {
+                    <div class="alert alert-warning">{format!("Optimal plan unavailable: {}", e)}</div>
+                }.into_any(),
+                Ok(Some(opt)) => {
+                    let item_names = store.item_names().get();
+                    let mut items: Vec<(String, f64)> = opt
+                        .item_costs
+                        .iter()
+                        .filter(|c| c.marginal_wp.abs() > 1e-9)
+                        .map(|c| (item_label(&item_names, &c.item), c.marginal_wp))
+                        .collect();
+                    items.sort_by(|a, b| a.0.cmp(&b.0));
+                    view! {
+                        <div>
+                            <div class="mb-2">
+                                <span class="font-semibold">Optimal plan WP: </span>
+                                {format!("{:.2}", opt.total_wp)}
+                            </div>
+                            <h3 class="text-lg font-semibold mb-2">Raw Resource Shadow Prices</h3>
+                            <table class="table table-xs w-full">
+                                <thead>
+                                    <tr>
+                                        <th>Resource</th>
+                                        <th>{move || format!("Used ({})", store.units().get().rate)}</th>
+                                        <th>Available</th>
+                                        <th>WP saved per extra unit/min</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {opt.resource_prices.iter().map(|p| view! {
+                                        <tr>
+                                            <td>{item_label(&item_names, &p.resource)}</td>
+                                            <td>{format!("{:.2}", p.used)}</td>
+                                            <td>{if p.available.is_finite() { format!("{:.0}", p.available) } else { "∞".to_string() }}</td>
+                                            <td>{format!("{:.4}", p.shadow_price)}</td>
+                                        </tr>
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                            <h3 class="text-lg font-semibold mt-6 mb-2">Item Marginal Costs</h3>
+                            <table class="table table-xs w-full">
+                                <thead>
+                                    <tr>
+                                        <th>Item</th>
+                                        <th>WP per extra item/min</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {items.into_iter().map(|(item, marginal_wp)| view! {
+                                        <tr>
+                                            <td>{item}</td>
+                                            <td>{format!("{:.4}", marginal_wp)}</td>
+                                        </tr>
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        </div>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
@@ -5,3 +5,15 @@ pub mod calc_tab;
 pub mod recipes_tab;
 pub mod analysis_tab;
 pub mod raw_resource;
+pub mod marginal_costs;
+pub mod pareto_view;
+pub mod language_selector;
+pub mod dataset_loader;
+pub mod recipe_editor;
+pub mod plan_persistence;
+pub mod share_link;
+pub mod plan_file_io;
+pub mod factory_bar;
+pub mod project_view;
+pub mod logistics;
+pub mod flow_graph_view;
@@ -0,0 +1,174 @@
+use leptos::prelude::*;
+use reactive_stores::Store;
+use std::collections::{HashMap, HashSet};
+use crate::model::{item_label, machine_power_map_from_recipes, recipe::Recipe, pareto::{compute_pareto_frontiers, ParetoPoint}, AppStore, AppStoreStoreFields};
+
+const CHART_WIDTH: f64 = 480.0;
+const CHART_HEIGHT: f64 = 240.0;
+const CHART_PADDING: f64 = 40.0;
+
+/// Scatter chart of an item's frontier: WP on x, power on y, circle size by building count
+fn frontier_chart(points: &[ParetoPoint]) -> impl IntoView {
+    let max_wp = points.iter().map(|p| p.wp).fold(0.0, f64::max).max(1e-9);
+    let max_power = points.iter().map(|p| p.power).fold(0.0, f64::max).max(1e-9);
+    let max_buildings = points.iter().map(|p| p.buildings).fold(0.0, f64::max).max(1e-9);
+    let plot_w = CHART_WIDTH - 2.0 * CHART_PADDING;
+    let plot_h = CHART_HEIGHT - 2.0 * CHART_PADDING;
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.wp.partial_cmp(&b.wp).unwrap_or(std::cmp::Ordering::Equal));
+    let coords: Vec<(f64, f64)> = sorted
+        .iter()
+        .map(|p| (
+            CHART_PADDING + p.wp / max_wp * plot_w,
+            CHART_HEIGHT - CHART_PADDING - p.power / max_power * plot_h,
+        ))
+        .collect();
+    let polyline = coords.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect::<Vec<_>>().join(" ");
+    view! {
+        <svg
+            class="border rounded bg-base-100"
+            width=CHART_WIDTH.to_string()
+            height=CHART_HEIGHT.to_string()
+            viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+        >
+            <line
+                x1=CHART_PADDING.to_string()
+                y1=(CHART_HEIGHT - CHART_PADDING).to_string()
+                x2=(CHART_WIDTH - CHART_PADDING).to_string()
+                y2=(CHART_HEIGHT - CHART_PADDING).to_string()
+                stroke="currentColor"
+            />
+            <line
+                x1=CHART_PADDING.to_string()
+                y1=CHART_PADDING.to_string()
+                x2=CHART_PADDING.to_string()
+                y2=(CHART_HEIGHT - CHART_PADDING).to_string()
+                stroke="currentColor"
+            />
+            <text x=(CHART_WIDTH / 2.0).to_string() y=(CHART_HEIGHT - 10.0).to_string() font-size="12" text-anchor="middle" fill="currentColor">
+                WP per item
+            </text>
+            <text x="12" y=(CHART_HEIGHT / 2.0).to_string() font-size="12" text-anchor="middle" fill="currentColor" transform=format!("rotate(-90 12 {})", CHART_HEIGHT / 2.0)>
+                Power (MJ)
+            </text>
+            <polyline points=polyline fill="none" stroke="currentColor" stroke-dasharray="4 2" opacity="0.5" />
+            {sorted.iter().zip(coords.iter()).map(|(p, (x, y))| {
+                let r = 4.0 + 8.0 * p.buildings / max_buildings;
+                view! {
+                    <circle cx=format!("{:.1}", x) cy=format!("{:.1}", y) r=format!("{:.1}", r) fill="oklch(var(--p))" opacity="0.7">
+                        <title>
+                            {format!(
+                                "{}: WP {:.2}, {:.2} MJ, {:.3} buildings per item/min",
+                                p.recipe_name().unwrap_or("-"),
+                                p.wp,
+                                p.power,
+                                p.buildings,
+                            )}
+                        </title>
+                    </circle>
+                }
+            }).collect::<Vec<_>>()}
+        </svg>
+    }
+}
+
+#[component]
+pub fn ParetoView() -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    // Over the enabled recipes only, like the per-metric minima of the analysis tab
+    let enabled_recipes = use_context::<Memo<HashSet<String>>>().expect("enabled_recipes context");
+    let frontiers = Memo::new(move |_| {
+        let enabled = enabled_recipes.get();
+        let recipes: Vec<Recipe> = store.recipes().with(|r| r.iter().filter(|r| enabled.contains(&r.id)).cloned().collect());
+        let machine_power_map = machine_power_map_from_recipes(&recipes);
+        compute_pareto_frontiers(&recipes, &machine_power_map, &store.raw_resources().get())
+    });
+    let item_filter = RwSignal::new(String::new());
+    let selected = RwSignal::new(None::<String>);
+    let items = Memo::new(move |_| {
+        let filter = item_filter.get().to_lowercase();
+        let item_names = store.item_names().get();
+        let mut items: Vec<(String, String, usize)> = frontiers.with(|f: &HashMap<String, Vec<ParetoPoint>>| {
+            f.iter()
+                .map(|(item, points)| (item_label(&item_names, item), item.clone(), points.len()))
+                .filter(|(label, _, _)| label.to_lowercase().contains(&filter))
+                .collect()
+        });
+        items.sort();
+        items
+    });
+
+    view! {
+        <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
+            <div>
+                <input
+                    class="input input-bordered w-full mb-2"
+                    type="text"
+                    placeholder="Filter items..."
+                    value=move || item_filter.get()
+                    on:input=move |ev| item_filter.set(event_target_value(&ev))
+                />
+                <ul class="menu bg-base-100 border border-base-300 rounded max-h-96 overflow-auto flex-nowrap">
+                    <For
+                        each=move || items.get()
+                        key=|(_, item, count)| (item.clone(), *count)
+                        children=move |(label, item, count)| {
+                            let item_click = item.clone();
+                            let item_active = item.clone();
+                            view! {
+                                <li>
+                                    <a
+                                        class=move || if selected.get().as_deref() == Some(item_active.as_str()) { "active" } else { "" }
+                                        on:click=move |_| selected.set(Some(item_click.clone()))
+                                    >
+                                        {label}
+                                        <span class="badge badge-sm">{count}</span>
+                                    </a>
+                                </li>
+                            }
+                        }
+                    />
+                </ul>
+            </div>
+            <div class="md:col-span-2">
+                {move || {
+                    let Some(item) = selected.get() else {
+                        return view! { <div class="opacity-70">Select an item to see its trade-off frontier.</div> }.into_any();
+                    };
+                    let points = frontiers.with(|f| f.get(&item).cloned().unwrap_or_default());
+                    let item_names = store.item_names().get();
+                    view! {
+                        <div>
+                            <h3 class="text-lg font-semibold mb-2">{item_label(&item_names, &item)}</h3>
+                            {frontier_chart(&points)}
+                            <table class="table table-compact w-full border mt-4">
+                                <thead>
+                                    <tr>
+                                        <th>Recipe</th>
+                                        <th>WP</th>
+                                        <th>Power (MJ)</th>
+                                        <th>Buildings per item/min</th>
+                                        <th>Chain</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {points.iter().map(|p| view! {
+                                        <tr>
+                                            <td>{p.recipe_name().unwrap_or("-").to_string()}</td>
+                                            <td>{format!("{:.2}", p.wp)}</td>
+                                            <td>{format!("{:.2}", p.power)}</td>
+                                            <td>{format!("{:.4}", p.buildings)}</td>
+                                            <td class="text-xs">
+                                                {p.chain.iter().skip(1).map(|(i, r)| format!("{}: {}", item_label(&item_names, i), r)).collect::<Vec<_>>().join(", ")}
+                                            </td>
+                                        </tr>
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        </div>
+                    }.into_any()
+                }}
+            </div>
+        </div>
+    }
+}
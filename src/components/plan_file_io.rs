@@ -0,0 +1,101 @@
+use leptos::{prelude::*, task::spawn_local};
+use reactive_stores::Store;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{File, HtmlAnchorElement, HtmlInputElement};
+use crate::{
+    adapters::encoding::decode_text,
+    components::{dataset_loader::read_file, plan_persistence::PlanSignals},
+    model::{plan::PlanState, plan_file::PlanFile, AppStore},
+};
+
+/// Hand a text file to the browser's download
+pub(crate) fn download(file_name: &str, mime: &str, text: &str) -> Result<(), String> {
+    let error = |e: JsValue| format!("{:?}", e);
+    let document = web_sys::window().and_then(|w| w.document()).ok_or("no document")?;
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&JsValue::from_str(text)), &options)
+        .map_err(error)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(error)?;
+    let anchor: HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(error)?
+        .dyn_into()
+        .map_err(|_| "not an anchor".to_string())?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url).map_err(error)
+}
+
+fn current_plan_file(store: Store<AppStore>, plan: PlanSignals) -> PlanFile {
+    store.with_untracked(|s| PlanFile::new(&s.game, &s.dataset_version, &plan.get_untracked(), &s.recipes, &s.item_names))
+}
+
+/// Read a plan file and make it the current plan; returns warnings about what the dataset lacks
+async fn import_plan_file(store: Store<AppStore>, plan: PlanSignals, file: File) -> Result<Vec<String>, String> {
+    let text = decode_text(&read_file(&file).await?).map_err(|e| e.to_string())?;
+    let plan_file = PlanFile::parse(&text).map_err(|e| e.to_string())?;
+    let (state, warnings) = store
+        .with_untracked(|s| plan_file.to_plan(&s.game, &s.recipes, &s.item_names))
+        .map_err(|e| e.to_string())?;
+    // UI settings are not part of the file
+    plan.replace(store, PlanState {
+        settings: plan.get_untracked().settings,
+        ..state
+    });
+    Ok(warnings)
+}
+
+#[component]
+pub fn PlanFileButtons() -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    let plan = use_context::<PlanSignals>().expect("PlanSignals context");
+    let messages = RwSignal::new(Vec::<String>::new());
+    let export = move |toml: bool| {
+        let file = current_plan_file(store, plan);
+        let result = if toml {
+            download(&format!("{}-plan.toml", file.game), "application/toml", &file.to_toml())
+        } else {
+            download(&format!("{}-plan.json", file.game), "application/json", &file.to_json())
+        };
+        messages.set(result.err().map(|e| format!("Could not export: {}", e)).into_iter().collect());
+    };
+    let import = move |file: File| {
+        spawn_local(async move {
+            messages.set(match import_plan_file(store, plan, file).await {
+                Ok(warnings) => warnings,
+                Err(e) => vec![format!("Could not import: {}", e)],
+            });
+        });
+    };
+    view! {
+        <div class="flex flex-wrap items-center gap-2 mb-4">
+            <button class="btn btn-sm" on:click=move |_| export(true)>"Export TOML"</button>
+            <button class="btn btn-sm" on:click=move |_| export(false)>"Export JSON"</button>
+            <label class="btn btn-sm btn-outline">
+                "Import plan"
+                <input
+                    type="file"
+                    accept=".toml,.json,application/toml,application/json"
+                    class="hidden"
+                    on:change=move |ev| {
+                        let input: HtmlInputElement = event_target(&ev);
+                        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                            import(file);
+                        }
+                        input.set_value("");
+                    }
+                />
+            </label>
+            {move || {
+                let messages = messages.get();
+                (!messages.is_empty()).then(|| view! {
+                    <ul class="text-warning text-sm">
+                        {messages.into_iter().map(|m| view! { <li>{m}</li> }).collect::<Vec<_>>()}
+                    </ul>
+                })
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,262 @@
+//! Keeps the plan in local storage, per game: restored when a game's dataset loads and saved on every change.
+//! A plan opened from a shared link or a plan file replaces the saved one of its game, which is kept as a backup.
+use leptos::prelude::*;
+use reactive_stores::Store;
+use std::collections::{BTreeMap, HashSet};
+use crate::{
+    components::dataset_loader::local_storage,
+    model::{
+        plan::{plan_storage_key, FactoryPlan, PlanSettings, PlanState, StoredPlan},
+        share::{ShareLinkError, SharedPlan},
+        AppStore, AppStoreStoreFields,
+    },
+};
+
+/// Plan state the tabs share, provided as context. The per-factory signals hold the active factory;
+/// its entry in `factories` is only brought up to date by [`PlanSignals::get`].
+#[derive(Clone, Copy)]
+pub struct PlanSignals {
+    pub factories: RwSignal<Vec<FactoryPlan>>,
+    pub active: RwSignal<usize>,
+    pub outputs: RwSignal<Vec<(String, f64)>>,
+    pub supplies: RwSignal<Vec<(String, f64)>>,
+    pub supply_sources: RwSignal<BTreeMap<String, String>>,
+    pub recipe_choices: RwSignal<BTreeMap<String, String>>,
+    pub clocks: RwSignal<BTreeMap<String, f64>>,
+    pub notes: RwSignal<String>,
+    pub tab: RwSignal<String>,
+    pub recipe_filter: RwSignal<String>,
+    sync: StoredValue<PlanSync>,
+}
+
+impl PlanSignals {
+    fn set(&self, plan: &PlanState) {
+        let factory = plan.active_factory();
+        self.factories.set(plan.factories.clone());
+        self.active.set(plan.active);
+        self.outputs.set(factory.outputs.clone());
+        self.supplies.set(factory.supplies.clone());
+        self.supply_sources.set(factory.supply_sources.clone());
+        self.recipe_choices.set(factory.recipe_choices.clone());
+        self.clocks.set(factory.clocks.clone());
+        self.notes.set(factory.notes.clone());
+        self.tab.set(plan.settings.tab.clone());
+        self.recipe_filter.set(plan.settings.recipe_filter.clone());
+    }
+
+    /// The current plan; enabled flags are not kept in the signals and are left empty
+    pub fn get(&self) -> PlanState {
+        let mut factories = self.factories.get();
+        let active = self.active.get();
+        let name = factories[active].name.clone();
+        factories[active] = FactoryPlan {
+            name,
+            outputs: self.outputs.get(),
+            supplies: self.supplies.get(),
+            supply_sources: self.supply_sources.get(),
+            recipe_choices: self.recipe_choices.get(),
+            clocks: self.clocks.get(),
+            notes: self.notes.get(),
+        };
+        PlanState {
+            factories,
+            active,
+            enabled: BTreeMap::new(),
+            settings: PlanSettings {
+                tab: self.tab.get(),
+                recipe_filter: self.recipe_filter.get(),
+            },
+        }
+    }
+
+    pub fn get_untracked(&self) -> PlanState {
+        untrack(|| self.get())
+    }
+
+    /// Change the factories of the plan, e.g. to add one, and show the factory that is active afterwards
+    pub fn update_factories(&self, change: impl FnOnce(&mut PlanState)) {
+        let mut plan = self.get_untracked();
+        change(&mut plan);
+        plan.normalize();
+        self.set(&plan);
+    }
+
+    pub fn select_factory(&self, index: usize) {
+        self.update_factories(|plan| plan.active = index);
+    }
+
+    /// Make a plan the current one of a game, including its enabled flags
+    fn load(&self, store: Store<AppStore>, game: String, plan: PlanState) {
+        self.set(&plan);
+        self.sync.set_value(PlanSync {
+            game: Some(game),
+            enabled: plan.enabled,
+            seen: HashSet::new(),
+        });
+        apply_saved_flags(store, self.sync);
+    }
+
+    /// Replace the current game's plan, e.g. with an imported one; the saved plan is kept as a backup
+    pub fn replace(&self, store: Store<AppStore>, plan: PlanState) {
+        let game = store.game().get_untracked();
+        back_up_plan(&game);
+        self.load(store, game, plan);
+    }
+}
+
+/// What the saved plan of the current game looks like, as far as the app has synced it
+#[derive(Default)]
+struct PlanSync {
+    /// Game whose plan is restored; nothing is saved before a restore
+    game: Option<String>,
+    /// Enabled flags as last saved, including recipes that are not loaded right now
+    enabled: BTreeMap<String, bool>,
+    /// Recipes the saved flags were applied to; later recipes, e.g. merged custom ones, still get theirs
+    seen: HashSet<String>,
+}
+
+/// Copy a game's saved plan aside before it is replaced, returning the key of the copy
+fn back_up_plan(game: &str) -> Option<String> {
+    let storage = local_storage()?;
+    let key = plan_storage_key(game);
+    let json = storage.get_item(&key).ok().flatten()?;
+    let backup = format!("{}.backup", key);
+    storage.set_item(&backup, &json).ok()?;
+    Some(backup)
+}
+
+fn load_plan(game: &str) -> Result<Option<PlanState>, String> {
+    let Some(json) = local_storage().and_then(|storage| storage.get_item(&plan_storage_key(game)).ok().flatten()) else {
+        return Ok(None);
+    };
+    StoredPlan::from_json(&json).map(Some).map_err(|e| {
+        // Kept aside so the next save does not destroy it
+        match back_up_plan(game) {
+            Some(backup) => format!("Started a new plan because {}; the old one was kept under {}", e, backup),
+            None => format!("Started a new plan because {}", e),
+        }
+    })
+}
+
+fn save_plan(game: &str, plan: PlanState) -> Result<(), String> {
+    let storage = local_storage().ok_or("local storage is unavailable")?;
+    storage
+        .set_item(&plan_storage_key(game), &StoredPlan::new(plan).to_json())
+        .map_err(|_| "local storage is full or disabled".to_string())
+}
+
+/// Apply saved enabled flags to the recipes not seen since the last restore
+fn apply_saved_flags(store: Store<AppStore>, sync: StoredValue<PlanSync>) {
+    let changes: Vec<(String, bool)> = sync.with_value(|sync| {
+        store.with_untracked(|s| {
+            s.recipes
+                .iter()
+                .filter(|r| !sync.seen.contains(&r.id))
+                .filter_map(|r| sync.enabled.get(&r.id).filter(|flag| **flag != r.enabled).map(|flag| (r.id.clone(), *flag)))
+                .collect()
+        })
+    });
+    let ids: Vec<String> = store.with_untracked(|s| s.recipes.iter().map(|r| r.id.clone()).collect());
+    sync.update_value(|sync| sync.seen.extend(ids));
+    if !changes.is_empty() {
+        let plan = PlanState {
+            enabled: changes.into_iter().collect(),
+            ..Default::default()
+        };
+        store.update(|s| plan.apply_enabled(&mut s.recipes));
+    }
+}
+
+/// Create the plan signals, provide them as context and keep them in local storage.
+/// A plan from a shared link is applied once its game's recipes are loaded.
+pub fn provide_plan_persistence(store: Store<AppStore>, shared: Option<Result<SharedPlan, ShareLinkError>>) -> PlanSignals {
+    let plan = PlanSignals {
+        factories: RwSignal::new(PlanState::default().factories),
+        active: RwSignal::new(0),
+        outputs: RwSignal::new(Vec::new()),
+        supplies: RwSignal::new(Vec::new()),
+        supply_sources: RwSignal::new(BTreeMap::new()),
+        recipe_choices: RwSignal::new(BTreeMap::new()),
+        clocks: RwSignal::new(BTreeMap::new()),
+        notes: RwSignal::new(String::new()),
+        tab: RwSignal::new(PlanSettings::default().tab),
+        recipe_filter: RwSignal::new(String::new()),
+        sync: StoredValue::new(PlanSync::default()),
+    };
+    provide_context(plan);
+    let sync = plan.sync;
+    let (shared, link_error) = match shared {
+        Some(Ok(shared)) => (Some(shared), None),
+        Some(Err(e)) => (None, Some(format!("Could not open the link: {}", e))),
+        None => (None, None),
+    };
+    let shared = StoredValue::new(shared);
+    let link_error = StoredValue::new(link_error);
+
+    // Restore when a dataset of another game is loaded, or apply the shared plan once its recipes are there
+    Effect::new(move |_| {
+        store.dataset().track();
+        let game = store.game().get_untracked();
+        if game.is_empty() {
+            return;
+        }
+        if let Some(error) = link_error.try_update_value(Option::take).flatten() {
+            store.update(|s| s.load_warnings.push(error));
+        }
+        let use_shared = shared.with_value(|p| p.as_ref().is_some_and(|p| p.game == game))
+            && store.with_untracked(|s| !s.recipes.is_empty());
+        if !use_shared && sync.with_value(|s| s.game.as_deref() == Some(game.as_str())) {
+            return;
+        }
+        if use_shared {
+            let link = shared.try_update_value(Option::take).flatten().expect("shared plan is pending");
+            plan.replace(store, store.with_untracked(|s| link.plan(&s.recipes)));
+            return;
+        }
+        let saved = match load_plan(&game) {
+            Ok(saved) => saved.unwrap_or_default(),
+            Err(warning) => {
+                store.update(|s| s.load_warnings.push(warning));
+                PlanState::default()
+            }
+        };
+        plan.load(store, game, saved);
+    });
+
+    // Recipes merged in later, e.g. custom recipes, get their saved flags too
+    let recipe_ids = Memo::new(move |_| store.with(|s| s.recipes.iter().map(|r| r.id.clone()).collect::<Vec<_>>()));
+    Effect::new(move |_| {
+        recipe_ids.track();
+        if sync.with_value(|s| s.game.is_some()) {
+            apply_saved_flags(store, sync);
+        }
+    });
+
+    let enabled_recipes = use_context::<Memo<HashSet<String>>>().expect("enabled_recipes context");
+    Effect::new(move |_| {
+        enabled_recipes.track();
+        let state = plan.get();
+        let Some(game) = sync.with_value(|s| s.game.clone()) else {
+            return;
+        };
+        if store.game().get_untracked() != game {
+            return;
+        }
+        // Flags of recipes the saved flags were not applied to yet would overwrite the saved ones
+        sync.update_value(|sync| {
+            store.with_untracked(|s| {
+                for recipe in s.recipes.iter().filter(|r| sync.seen.contains(&r.id)) {
+                    sync.enabled.insert(recipe.id.clone(), recipe.enabled);
+                }
+            })
+        });
+        let state = PlanState {
+            enabled: sync.with_value(|s| s.enabled.clone()),
+            ..state
+        };
+        if let Err(e) = save_plan(&game, state) {
+            leptos::logging::warn!("Could not save the plan: {}", e);
+        }
+    });
+    plan
+}
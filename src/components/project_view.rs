@@ -0,0 +1,116 @@
+use leptos::prelude::*;
+use reactive_stores::Store;
+use std::collections::HashSet;
+use crate::{
+    components::plan_persistence::PlanSignals,
+    model::{
+        item_label,
+        project::{summarize_project, ProjectWarning},
+        AppStore, AppStoreStoreFields,
+    },
+};
+
+/// All factories of the plan: what they pass to each other and the raw resources they use together
+#[component]
+pub fn ProjectView() -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    let plan = use_context::<PlanSignals>().expect("PlanSignals context");
+    let enabled_recipes = use_context::<Memo<HashSet<String>>>().expect("enabled_recipes context");
+    let summary = Memo::new(move |_| {
+        let factories = plan.get().factories;
+        let enabled = enabled_recipes.get();
+        store.with(|s| summarize_project(&factories, &s.recipes, &enabled, &s.raw_resources))
+    });
+
+    view! {
+        <div>
+            {move || {
+                let item_names = store.item_names().get();
+                let warnings: Vec<String> = summary
+                    .with(|s| s.warnings.clone())
+                    .into_iter()
+                    .map(|w| match w {
+                        ProjectWarning::UnknownSource { factory, item, source } => format!(
+                            "{} takes {} from {}, which is not a factory of the project",
+                            factory, item_label(&item_names, &item), source
+                        ),
+                        ProjectWarning::ShortSource { source, item, made, taken } => format!(
+                            "{} makes {:.2} of {} but other factories take {:.2}",
+                            source, made, item_label(&item_names, &item), taken
+                        ),
+                    })
+                    .collect();
+                (!warnings.is_empty()).then(|| view! {
+                    <div role="alert" class="alert alert-warning mb-4 flex-col items-start">
+                        <ul class="list-disc ml-6 text-sm">
+                            {warnings.into_iter().map(|w| view! { <li>{w}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    </div>
+                })
+            }}
+            <h2 class="text-xl font-semibold mb-2">Flows between factories</h2>
+            <div class="overflow-x-auto">
+                <table class="table table-zebra w-full">
+                    <thead>
+                        <tr>
+                            <th>From</th>
+                            <th>To</th>
+                            <th>Item</th>
+                            <th>{move || format!("Rate ({})", store.units().get().rate)}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            let item_names = store.item_names().get();
+                            summary
+                                .with(|s| s.flows.clone())
+                                .into_iter()
+                                .map(|flow| view! {
+                                    <tr>
+                                        <td>{flow.from}</td>
+                                        <td>{flow.to}</td>
+                                        <td>{item_label(&item_names, &flow.item)}</td>
+                                        <td>{format!("{:.2}", flow.rate)}</td>
+                                    </tr>
+                                })
+                                .collect::<Vec<_>>()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+            <h2 class="text-xl font-semibold mt-8 mb-2">Raw resources of the project</h2>
+            <div class="overflow-x-auto">
+                <table class="table table-zebra w-full">
+                    <thead>
+                        <tr>
+                            <th>Resource</th>
+                            <th>{move || format!("Used ({})", store.units().get().rate)}</th>
+                            <th>Available</th>
+                            <th>Share of the map</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            let item_names = store.item_names().get();
+                            summary
+                                .with(|s| s.resources.clone())
+                                .into_iter()
+                                .map(|r| {
+                                    let over = r.used > r.available;
+                                    view! {
+                                        <tr class=if over { "text-error" } else { "" }>
+                                            <td>{item_label(&item_names, &r.resource)}</td>
+                                            <td>{format!("{:.2}", r.used)}</td>
+                                            <td>{if r.available.is_finite() { format!("{:.0}", r.available) } else { "∞".to_string() }}</td>
+                                            <td>{if r.available.is_finite() { format!("{:.1}%", 100.0 * r.used / r.available) } else { "-".to_string() }}</td>
+                                        </tr>
+                                    }
+                                })
+                                .collect::<Vec<_>>()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -1,6 +1,6 @@
-use crate::model::RESOURCE_AVAIL;
+use std::collections::HashSet;
 
-/// Returns true if the given product is a raw resource (should stop recursion)
-pub fn is_raw_resource(product: &str) -> bool {
-    RESOURCE_AVAIL.iter().any(|(name, _)| name == &product)
+/// Returns true if the given product is one of the dataset's raw resources (should stop recursion)
+pub fn is_raw_resource(raw_resources: &HashSet<String>, product: &str) -> bool {
+    raw_resources.contains(product)
 }
@@ -0,0 +1,359 @@
+use leptos::prelude::*;
+use reactive_stores::Store;
+use std::collections::HashMap;
+use crate::{
+    components::dataset_loader::local_storage,
+    model::{
+        custom::{custom_recipes_storage_key, new_custom_id, CustomRecipes, StoredCustomRecipes},
+        item_label,
+        recipe::{CraftingMachine, ItemStack, Recipe},
+        AppStore, AppStoreStoreFields,
+    },
+};
+
+fn load_custom_recipes(game: &str) -> CustomRecipes {
+    local_storage()
+        .and_then(|storage| storage.get_item(&custom_recipes_storage_key(game)).ok().flatten())
+        .and_then(|json| StoredCustomRecipes::from_json(&json))
+        .map(|stored| stored.custom)
+        .unwrap_or_default()
+}
+
+fn save_custom_recipes(game: &str, custom: &CustomRecipes) -> Result<(), String> {
+    let storage = local_storage().ok_or("local storage is unavailable")?;
+    storage
+        .set_item(&custom_recipes_storage_key(game), &StoredCustomRecipes::new(custom.clone()).to_json())
+        .map_err(|_| "local storage is full or disabled".to_string())
+}
+
+/// Persist a changed set of custom recipes for the current game and merge it into the recipe list
+fn update_custom_recipes(store: Store<AppStore>, change: impl FnOnce(&mut CustomRecipes)) -> Result<(), String> {
+    let (game, mut custom) = store.with_untracked(|s| (s.game.clone(), s.custom.clone()));
+    change(&mut custom);
+    // Kept for this session even if it cannot be persisted
+    let saved = save_custom_recipes(&game, &custom);
+    store.update(|s| s.set_custom_recipes(custom));
+    saved
+}
+
+fn recipe_id_taken(store: Store<AppStore>, id: &str) -> bool {
+    store.with_untracked(|s| s.recipes.iter().any(|r| r.id == id))
+}
+
+/// Copy a recipe into the custom recipes, where it can be edited
+pub fn clone_as_custom_recipe(store: Store<AppStore>, recipe: &Recipe) -> Result<(), String> {
+    let name = format!("{} (custom)", recipe.name);
+    let copy = Recipe {
+        id: new_custom_id(&recipe.name, |id| recipe_id_taken(store, id)),
+        name,
+        enabled: true,
+        ..recipe.clone()
+    };
+    update_custom_recipes(store, |custom| custom.upsert(copy))
+}
+
+/// Editable form of a recipe; items are entered by label and times in seconds
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RecipeDraft {
+    /// Id of the custom recipe being edited, `None` for a new one
+    id: Option<String>,
+    name: String,
+    machine_id: String,
+    time_secs: f64,
+    inputs: Vec<(String, u32)>,
+    outputs: Vec<(String, u32)>,
+}
+
+impl RecipeDraft {
+    fn from_recipe(recipe: &Recipe, item_names: &HashMap<String, String>) -> Self {
+        let stacks = |stacks: &[ItemStack]| stacks.iter().map(|s| (item_label(item_names, &s.item), s.quantity)).collect();
+        RecipeDraft {
+            id: Some(recipe.id.clone()),
+            name: recipe.name.clone(),
+            machine_id: recipe.machine.id.clone(),
+            time_secs: recipe.time as f64 / 1000.0,
+            inputs: stacks(&recipe.inputs),
+            outputs: stacks(&recipe.outputs),
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Give the recipe a name".to_string());
+        }
+        if self.machine_id.is_empty() {
+            return Err("Choose a machine".to_string());
+        }
+        if self.time_secs <= 0.0 || !self.time_secs.is_finite() {
+            return Err("Craft time must be positive".to_string());
+        }
+        if self.outputs.is_empty() {
+            return Err("Add at least one output".to_string());
+        }
+        if self.inputs.iter().chain(self.outputs.iter()).any(|(label, quantity)| label.trim().is_empty() || *quantity == 0) {
+            return Err("Every input and output needs an item and a quantity".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Save a draft as a new or updated custom recipe
+fn save_draft(store: Store<AppStore>, draft: &RecipeDraft, machines: &[CraftingMachine]) -> Result<(), String> {
+    draft.check()?;
+    let machine = machines
+        .iter()
+        .find(|m| m.id == draft.machine_id)
+        .cloned()
+        .ok_or("Choose a machine")?;
+    let id = draft
+        .id
+        .clone()
+        .unwrap_or_else(|| new_custom_id(&draft.name, |id| recipe_id_taken(store, id)));
+    let item_names = store.item_names().get_untracked();
+    let draft = draft.clone();
+    update_custom_recipes(store, move |custom| {
+        let mut stacks = |stacks: &[(String, u32)]| {
+            stacks
+                .iter()
+                .map(|(label, quantity)| ItemStack { item: custom.item_for_label(label, &item_names), quantity: *quantity })
+                .collect::<Vec<_>>()
+        };
+        let inputs = stacks(&draft.inputs);
+        let outputs = stacks(&draft.outputs);
+        custom.upsert(Recipe {
+            id,
+            name: draft.name.trim().to_string(),
+            inputs,
+            outputs,
+            machine,
+            time: (draft.time_secs * 1000.0).round() as u32,
+            enabled: true,
+            source: None,
+        });
+    })
+}
+
+fn draft_inputs(draft: &mut RecipeDraft) -> &mut Vec<(String, u32)> {
+    &mut draft.inputs
+}
+
+fn draft_outputs(draft: &mut RecipeDraft) -> &mut Vec<(String, u32)> {
+    &mut draft.outputs
+}
+
+/// A change to the input or output rows of the draft
+type StackEdit = Box<dyn FnOnce(&mut Vec<(String, u32)>)>;
+
+/// Input or output rows of the draft, edited in place
+#[component]
+fn StackRows(
+    draft: RwSignal<Option<RecipeDraft>>,
+    #[prop(into)] title: String,
+    field: fn(&mut RecipeDraft) -> &mut Vec<(String, u32)>,
+) -> impl IntoView {
+    let rows = move || draft.with(|d| d.clone().map(|mut d| field(&mut d).clone()).unwrap_or_default());
+    let edit = move |f: StackEdit| {
+        draft.update(|d| {
+            if let Some(d) = d.as_mut() {
+                f(field(d));
+            }
+        })
+    };
+    view! {
+        <div>
+            <div class="font-semibold mb-1">{title}</div>
+            {move || rows().into_iter().enumerate().map(|(i, (label, quantity))| view! {
+                <div class="flex gap-2 mb-1">
+                    <input
+                        class="input input-bordered input-sm flex-1"
+                        type="text"
+                        list="custom-recipe-items"
+                        placeholder="Item"
+                        value=label
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            edit(Box::new(move |rows| rows[i].0 = value));
+                        }
+                    />
+                    <input
+                        class="input input-bordered input-sm w-24"
+                        type="number"
+                        min="1"
+                        value=quantity.to_string()
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev).parse().unwrap_or(0);
+                            edit(Box::new(move |rows| rows[i].1 = value));
+                        }
+                    />
+                    <button class="btn btn-sm btn-ghost" on:click=move |_| edit(Box::new(move |rows| { rows.remove(i); }))>"✕"</button>
+                </div>
+            }).collect::<Vec<_>>()}
+            <button class="btn btn-xs" on:click=move |_| edit(Box::new(|rows| rows.push((String::new(), 1))))>"Add item"</button>
+        </div>
+    }
+}
+
+#[component]
+pub fn RecipeEditorTab() -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    // A newly loaded dataset has no custom recipes merged in; bring in the ones saved for its game
+    Effect::new(move |_| {
+        store.dataset().track();
+        let (game, current) = store.with_untracked(|s| (s.game.clone(), s.custom.clone()));
+        let saved = load_custom_recipes(&game);
+        if saved != current {
+            store.update(|s| s.set_custom_recipes(saved));
+        }
+    });
+    let draft = RwSignal::new(None::<RecipeDraft>);
+    let error = RwSignal::new(None::<String>);
+    let machines = Memo::new(move |_| {
+        let mut machines: Vec<CraftingMachine> = Vec::new();
+        store.with(|s| {
+            for recipe in &s.recipes {
+                if !machines.iter().any(|m| m.id == recipe.machine.id) {
+                    machines.push(recipe.machine.clone());
+                }
+            }
+        });
+        machines.sort_by(|a, b| a.name.cmp(&b.name));
+        machines
+    });
+    let report = move |result: Result<(), String>| error.set(result.err());
+    let new_recipe = move |_| {
+        error.set(None);
+        draft.set(Some(RecipeDraft {
+            time_secs: 1.0,
+            outputs: vec![(String::new(), 1)],
+            ..Default::default()
+        }));
+    };
+    let save = move |_| {
+        let Some(d) = draft.get_untracked() else {
+            return;
+        };
+        match save_draft(store, &d, &machines.get_untracked()) {
+            Ok(()) => {
+                error.set(None);
+                draft.set(None);
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    view! {
+        <datalist id="custom-recipe-items">
+            {move || {
+                let mut labels: Vec<String> = store.item_names().get().into_values().collect();
+                labels.sort();
+                labels.dedup();
+                labels.into_iter().map(|label| view! { <option value=label></option> }).collect::<Vec<_>>()
+            }}
+        </datalist>
+        <div class="flex items-center gap-2 mb-4">
+            <p class="text-sm opacity-70 flex-1">
+                "Custom recipes are saved with the dataset of the current game and take part in the calculator and analysis like the game's own. Clone an existing recipe from Recipe Options, or start from scratch."
+            </p>
+            <button class="btn btn-sm btn-primary" on:click=new_recipe>"New recipe"</button>
+        </div>
+        {move || error.get().map(|e| view! { <div role="alert" class="alert alert-error mb-4">{e}</div> })}
+        {move || draft.get().is_some().then(|| view! {
+            <div class="card bg-base-200 mb-4">
+                <div class="card-body gap-4">
+                    <div class="flex flex-wrap gap-4">
+                        <label class="form-control">
+                            <span class="label-text">Name</span>
+                            <input
+                                class="input input-bordered input-sm"
+                                type="text"
+                                value=move || draft.with_untracked(|d| d.as_ref().map(|d| d.name.clone()).unwrap_or_default())
+                                on:change=move |ev| draft.update(|d| if let Some(d) = d.as_mut() { d.name = event_target_value(&ev) })
+                            />
+                        </label>
+                        <label class="form-control">
+                            <span class="label-text">Machine</span>
+                            <select
+                                class="select select-bordered select-sm"
+                                on:change=move |ev| draft.update(|d| if let Some(d) = d.as_mut() { d.machine_id = event_target_value(&ev) })
+                            >
+                                <option value="">"Choose..."</option>
+                                {move || machines.get().into_iter().map(|m| {
+                                    let id = m.id.clone();
+                                    let selected = draft.with_untracked(|d| d.as_ref().is_some_and(|d| d.machine_id == id));
+                                    view! { <option value=m.id selected=selected>{m.name}</option> }
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </label>
+                        <label class="form-control">
+                            <span class="label-text">Craft time (s)</span>
+                            <input
+                                class="input input-bordered input-sm w-28"
+                                type="number"
+                                min="0"
+                                step="any"
+                                value=move || draft.with_untracked(|d| d.as_ref().map(|d| d.time_secs.to_string()).unwrap_or_default())
+                                on:change=move |ev| {
+                                    let secs = event_target_value(&ev).parse().unwrap_or(0.0);
+                                    draft.update(|d| if let Some(d) = d.as_mut() { d.time_secs = secs })
+                                }
+                            />
+                        </label>
+                    </div>
+                    <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                        <StackRows draft=draft title="Inputs" field=draft_inputs />
+                        <StackRows draft=draft title="Outputs" field=draft_outputs />
+                    </div>
+                    <div class="card-actions justify-end">
+                        <button class="btn btn-sm btn-ghost" on:click=move |_| draft.set(None)>"Cancel"</button>
+                        <button class="btn btn-sm btn-primary" on:click=save>"Save recipe"</button>
+                    </div>
+                </div>
+            </div>
+        })}
+        <table class="table table-zebra w-full">
+            <thead>
+                <tr>
+                    <th>Recipe</th>
+                    <th>Outputs</th>
+                    <th>Inputs</th>
+                    <th>Machine</th>
+                    <th>Craft Time</th>
+                    <th></th>
+                </tr>
+            </thead>
+            <tbody>
+                {move || {
+                    let item_names = store.item_names().get();
+                    let stacks = |stacks: &[ItemStack]| stacks
+                        .iter()
+                        .map(|s| format!("{} × {}", s.quantity, item_label(&item_names, &s.item)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    store.custom().get().recipes.into_iter().map(|recipe| {
+                        let edit_draft = RecipeDraft::from_recipe(&recipe, &item_names);
+                        let id = recipe.id.clone();
+                        view! {
+                            <tr>
+                                <td>{recipe.name.clone()}</td>
+                                <td>{stacks(&recipe.outputs)}</td>
+                                <td>{stacks(&recipe.inputs)}</td>
+                                <td>{recipe.machine.name.clone()}</td>
+                                <td>{format!("{:.2} s", recipe.time as f64 / 1000.0)}</td>
+                                <td class="flex gap-1">
+                                    <button class="btn btn-xs" on:click=move |_| {
+                                        error.set(None);
+                                        draft.set(Some(edit_draft.clone()));
+                                    }>"Edit"</button>
+                                    <button class="btn btn-xs btn-ghost" on:click=move |_| {
+                                        let id = id.clone();
+                                        report(update_custom_recipes(store, move |custom| custom.remove(&id)));
+                                    }>"Delete"</button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Vec<_>>()
+                }}
+            </tbody>
+        </table>
+    }
+}
@@ -1,13 +1,65 @@
 use leptos::prelude::*;
 use reactive_stores::Store;
-use crate::model::{AppStore, AppStoreStoreFields};
+use std::collections::HashSet;
+use crate::components::{plan_persistence::PlanSignals, recipe_editor::clone_as_custom_recipe};
+use crate::model::{diagnostics::{compute_unreachable_items, MissingLink}, item_label, AppStore, AppStoreStoreFields};
 
 #[component]
 pub fn RecipesTab() -> impl IntoView {
     let store = use_context::<Store<AppStore>>().expect("AppStore context");
-    let recipe_output_filter = RwSignal::new(String::new());
+    let recipe_output_filter = use_context::<PlanSignals>().expect("PlanSignals context").recipe_filter;
     let set_recipe_output_filter = recipe_output_filter.write_only();
+    let enabled_recipes = use_context::<Memo<HashSet<String>>>().expect("enabled_recipes context");
+    let unreachable = Memo::new(move |_| {
+        let recipes = store.with(|s| s.recipes.clone());
+        compute_unreachable_items(&recipes, &enabled_recipes.get(), &store.raw_resources().get())
+    });
     view! {
+        <div class="collapse collapse-arrow border border-base-300 mb-4">
+            <input type="checkbox" />
+            <div class="collapse-title font-medium">
+                {move || format!("Diagnostics: {} unreachable items", unreachable.get().len())}
+            </div>
+            <div class="collapse-content">
+                <table class="table table-xs w-full">
+                    <thead>
+                        <tr>
+                            <th>Item</th>
+                            <th>Missing Link</th>
+                            <th>Root Causes</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || unreachable.get().into_iter().map(|u| {
+                            let label = |id: &str| store.item_names().with(|names| item_label(names, id));
+                            let missing = match &u.missing {
+                                MissingLink::NoEnabledRecipe { disabled_recipes } if disabled_recipes.is_empty() => {
+                                    "No recipe produces this item".to_string()
+                                }
+                                MissingLink::NoEnabledRecipe { disabled_recipes } => {
+                                    format!("No enabled recipe; disabled: {}", disabled_recipes.join(", "))
+                                }
+                                MissingLink::UnreachableInputs { recipes } => recipes
+                                    .iter()
+                                    .map(|(recipe, inputs)| {
+                                        let inputs = inputs.iter().map(|i| label(i)).collect::<Vec<_>>();
+                                        format!("{} needs {}", recipe, inputs.join(", "))
+                                    })
+                                    .collect::<Vec<_>>()
+                                    .join("; "),
+                            };
+                            view! {
+                                <tr>
+                                    <td>{label(&u.item)}</td>
+                                    <td>{missing}</td>
+                                    <td>{u.root_causes.iter().map(|i| label(i)).collect::<Vec<_>>().join(", ")}</td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </div>
+        </div>
         <div class="mb-4">
             <input
                 class="input input-bordered w-full max-w-xs"
@@ -36,18 +88,33 @@ pub fn RecipesTab() -> impl IntoView {
                             if filter.is_empty() {
                                 store.recipes().into_iter().collect::<Vec<_>>()
                             } else {
+                                let item_names = store.item_names().get();
                                 store.recipes().into_iter()
-                                    .filter(|r| r.get().outputs.iter().any(|o| o.item.to_lowercase().contains(&filter)))
+                                    .filter(|r| r.get().outputs.iter().any(|o| item_label(&item_names, &o.item).to_lowercase().contains(&filter)))
                                     .collect::<Vec<_>>()
                             }
                         }
-                        key=|r| r.get().name.clone()
+                        key=|r| r.get().id.clone()
                         children=move |r| {
-                            let name = r.get().name;
-                            let outputs = r.get().outputs.iter().map(|o| o.item.clone()).collect::<Vec<_>>().join(", ");
-                            let inputs = r.get().inputs.iter().map(|i| i.item.clone()).collect::<Vec<_>>().join(", ");
+                            // Labels are read reactively so a language switch relabels existing rows
+                            let (r_name, r_outputs, r_inputs, r_out_table, r_in_table, r_machine) =
+                                (r.clone(), r.clone(), r.clone(), r.clone(), r.clone(), r.clone());
+                            let name = move || r_name.get().name;
+                            let r_source = r.clone();
+                            let source = move || r_source.get().source.map(|source| view! {
+                                <span class="badge badge-outline badge-sm ml-2" title="Added or changed by this mod">{source}</span>
+                            });
+                            let outputs = move || {
+                                let item_names = store.item_names().get();
+                                r_outputs.get().outputs.iter().map(|o| item_label(&item_names, &o.item)).collect::<Vec<_>>().join(", ")
+                            };
+                            let inputs = move || {
+                                let item_names = store.item_names().get();
+                                r_inputs.get().inputs.iter().map(|i| item_label(&item_names, &i.item)).collect::<Vec<_>>().join(", ")
+                            };
                             let r1 = r.clone();
                             let r2 = r.clone();
+                            let r_clone = r.clone();
                             view! {
                                 <tr>
                                     <td>
@@ -61,7 +128,7 @@ pub fn RecipesTab() -> impl IntoView {
                                             }
                                         />
                                     </td>
-                                    <td>{name}</td>
+                                    <td>{name}{source}</td>
                                     <td>{outputs}</td>
                                     <td>{inputs}</td>
                                 </tr>
@@ -81,20 +148,24 @@ pub fn RecipesTab() -> impl IntoView {
                                                                 <tr>
                                                                     <th>Product</th>
                                                                     <th>Per Craft</th>
-                                                                    <th>Rate/min</th>
+                                                                    <th>{move || format!("Rate ({})", store.units().get().rate)}</th>
                                                                 </tr>
                                                             </thead>
                                                             <tbody>
-                                                                {r.get().outputs.iter().map(|o| {
-                                                                    let rate = (o.quantity as f64) * (60_000.0 / r.get().time as f64);
+                                                                {move || {
+                                                                let item_names = store.item_names().get();
+                                                                let recipe = r_out_table.get();
+                                                                recipe.outputs.iter().map(|o| {
+                                                                    let rate = (o.quantity as f64) * (60_000.0 / recipe.time as f64);
                                                                     view! {
                                                                         <tr>
-                                                                            <td>{o.item.clone()}</td>
+                                                                            <td>{item_label(&item_names, &o.item)}</td>
                                                                             <td>{o.quantity}</td>
                                                                             <td>{format!("{:.2}", rate)}</td>
                                                                         </tr>
                                                                     }
-                                                                }).collect::<Vec<_>>()}
+                                                                }).collect::<Vec<_>>()
+                                                                }}
                                                             </tbody>
                                                         </table>
                                                     </div>
@@ -105,20 +176,24 @@ pub fn RecipesTab() -> impl IntoView {
                                                                 <tr>
                                                                     <th>Product</th>
                                                                     <th>Per Craft</th>
-                                                                    <th>Rate/min</th>
+                                                                    <th>{move || format!("Rate ({})", store.units().get().rate)}</th>
                                                                 </tr>
                                                             </thead>
                                                             <tbody>
-                                                                {r.get().inputs.iter().map(|i| {
-                                                                    let rate = (i.quantity as f64) * (60_000.0 / r.get().time as f64);
+                                                                {move || {
+                                                                let item_names = store.item_names().get();
+                                                                let recipe = r_in_table.get();
+                                                                recipe.inputs.iter().map(|i| {
+                                                                    let rate = (i.quantity as f64) * (60_000.0 / recipe.time as f64);
                                                                     view! {
                                                                         <tr>
-                                                                            <td>{i.item.clone()}</td>
+                                                                            <td>{item_label(&item_names, &i.item)}</td>
                                                                             <td>{i.quantity}</td>
                                                                             <td>{format!("{:.2}", rate)}</td>
                                                                         </tr>
                                                                     }
-                                                                }).collect::<Vec<_>>()}
+                                                                }).collect::<Vec<_>>()
+                                                                }}
                                                             </tbody>
                                                         </table>
                                                     </div>
@@ -126,12 +201,23 @@ pub fn RecipesTab() -> impl IntoView {
                                                 <div class="mt-4 flex flex-wrap gap-8">
                                                     <div>
                                                         <span class="font-semibold">Machine:</span>
-                                                        {r.get().machine.name.clone()}
+                                                        {move || r_machine.get().machine.name}
                                                     </div>
                                                     <div>
                                                         <span class="font-semibold">Craft Time:</span>
                                                         {format!("{:.2} ms", r.get().time)}
                                                     </div>
+                                                    <button
+                                                        class="btn btn-xs"
+                                                        title="Copy into Custom Recipes, where it can be edited"
+                                                        on:click=move |_| {
+                                                            if let Err(e) = clone_as_custom_recipe(store, &r_clone.get_untracked()) {
+                                                                leptos::logging::warn!("Custom recipe not saved: {}", e);
+                                                            }
+                                                        }
+                                                    >
+                                                        "Clone as custom recipe"
+                                                    </button>
                                                 </div>
                                             </div>
                                         </div>
@@ -0,0 +1,78 @@
+use leptos::{prelude::*, task::spawn_local};
+use reactive_stores::Store;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use crate::{
+    components::plan_persistence::PlanSignals,
+    model::{share::{ShareLinkError, SharedPlan}, AppStore},
+};
+
+/// Read the shared plan the page was opened with and drop it from the address, so a reload keeps later changes
+pub fn take_shared_plan() -> Option<Result<SharedPlan, ShareLinkError>> {
+    let window = web_sys::window()?;
+    let location = window.location();
+    let hash = location.hash().ok()?;
+    if !SharedPlan::is_fragment(&hash) {
+        return None;
+    }
+    let shared = SharedPlan::from_fragment(&hash);
+    if let (Ok(history), Ok(path), Ok(search)) = (window.history(), location.pathname(), location.search()) {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&format!("{}{}", path, search)));
+    }
+    Some(shared)
+}
+
+/// Link to the app that opens the current plan
+fn share_url(store: Store<AppStore>, plan: PlanSignals) -> Result<String, String> {
+    let location = web_sys::window().ok_or("no window")?.location();
+    let origin = location.origin().map_err(|e| format!("{:?}", e))?;
+    let path = location.pathname().map_err(|e| format!("{:?}", e))?;
+    let shared = store.with_untracked(|s| SharedPlan::new(&s.game, &s.dataset_version, &plan.get_untracked(), &s.recipes));
+    Ok(format!("{}{}#{}", origin, path, shared.to_fragment()))
+}
+
+async fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let clipboard = web_sys::window().ok_or("no window")?.navigator().clipboard();
+    JsFuture::from(clipboard.write_text(text))
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("{:?}", e))
+}
+
+#[component]
+pub fn ShareLink() -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("AppStore context");
+    let plan = use_context::<PlanSignals>().expect("PlanSignals context");
+    let link = RwSignal::new(None::<String>);
+    let status = RwSignal::new(None::<String>);
+    let share = move |_| match share_url(store, plan) {
+        Ok(url) => {
+            link.set(Some(url.clone()));
+            status.set(None);
+            spawn_local(async move {
+                status.set(Some(match copy_to_clipboard(&url).await {
+                    Ok(()) => "Link copied".to_string(),
+                    // The link is still shown for copying by hand
+                    Err(_) => "Copy the link above".to_string(),
+                }));
+            });
+        }
+        Err(e) => status.set(Some(format!("Could not create a link: {}", e))),
+    };
+    view! {
+        <div class="flex items-center gap-2">
+            {move || link.get().map(|url| view! {
+                <input
+                    class="input input-bordered input-sm w-64"
+                    type="text"
+                    readonly=true
+                    aria-label="Plan link"
+                    value=url
+                    on:focus=move |ev| { event_target::<web_sys::HtmlInputElement>(&ev).select() }
+                />
+            })}
+            {move || status.get().map(|s| view! { <span class="text-xs opacity-70">{s}</span> })}
+            <button class="btn btn-sm" on:click=share>"Share plan"</button>
+        </div>
+    }
+}
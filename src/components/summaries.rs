@@ -1,16 +1,19 @@
 use leptos::prelude::*;
-use super::breakdown::BreakdownNode;
+use reactive_stores::Store;
+use crate::model::breakdown::BreakdownNode;
+use crate::model::{item_label, AppStore, AppStoreStoreFields};
 use std::collections::HashMap;
 
 #[component]
 pub fn RawResourceSummary(
     breakdown: Memo<Vec<BreakdownNode>>,
 ) -> impl IntoView {
+    let store = use_context::<Store<AppStore>>().expect("Store<AppStore> context");
     view! {
         <div class="mt-6">
             <h3 class="text-lg font-semibold mb-2">Total Raw Resource Rate</h3>
             <table class="table table-xs w-full">
-                <thead><tr><th>Resource</th><th>Total Rate (items/min)</th></tr></thead>
+                <thead><tr><th>Resource</th><th>{move || format!("Total Rate ({})", store.units().get().rate)}</th></tr></thead>
                 <tbody>
                     {move || {
                         let mut raw_map = HashMap::new();
@@ -25,8 +28,9 @@ pub fn RawResourceSummary(
                         for node in breakdown.get().iter() {
                             collect_raws(node, &mut raw_map);
                         }
+                        let item_names = store.item_names().get();
                         raw_map.iter().map(|(res, rate)| view! {
-                            <tr><td>{res.clone()}</td><td>{format!("{:.2}", rate)}</td></tr>
+                            <tr><td>{item_label(&item_names, res)}</td><td>{format!("{:.2}", rate)}</td></tr>
                         }).collect::<Vec<_>>()
                     }}
                 </tbody>
@@ -1,14 +1,17 @@
-use factory_planner::{adapters::satisfactory_adapter::load_satisfactory_recipes_from_json, components::factory_planner_app::FactoryPlannerApp, model::{AppStore, AppStoreStoreFields}};
+use factory_planner::{components::{dataset_loader::load_initial_dataset, factory_planner_app::FactoryPlannerApp, plan_persistence::provide_plan_persistence, share_link::take_shared_plan}, model::{localization::DEFAULT_LOCALE, AppStore, AppStoreStoreFields}};
 use leptos::{logging::log, mount::mount_to_body, prelude::*};
 
 use reactive_stores::Store;
 
 #[component]
 fn App() -> impl IntoView {
-    let recipes = load_satisfactory_recipes_from_json(include_str!("../assets/satisfactory_en-US.json")).unwrap_or_default();
     let store = Store::new(AppStore {
-        recipes,
+        locale: DEFAULT_LOCALE.to_string(),
+        ..Default::default()
     });
+    let shared = take_shared_plan();
+    let requested = shared.as_ref().and_then(|s| s.as_ref().ok()).map(|s| (s.game.clone(), s.dataset_version.clone()));
+    load_initial_dataset(store, requested);
     provide_context(store);
     let enabled_recipes = Memo::new(move |_| {
         log!("Calculating enabled recipes");
@@ -16,10 +19,11 @@ fn App() -> impl IntoView {
             .recipes()
             .into_iter()
             .filter(|r| r.get().enabled)
-            .map(|r| r.get().name)
+            .map(|r| r.get().id)
             .collect::<std::collections::HashSet<_>>()
     });
     provide_context(enabled_recipes);
+    provide_plan_persistence(store, shared);
     view! { <FactoryPlannerApp /> }
 }
 
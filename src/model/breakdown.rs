@@ -0,0 +1,346 @@
+//! Production trees: how each requested output is made, down to raw resources and supplies
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::model::{plan::FactoryPlan, recipe::Recipe};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakdownNode {
+    pub product: String,
+    pub rate: f64,
+    pub recipe_name: Option<String>,
+    /// Enabled recipes that make the product, as id and name; the user picks between them when there are several
+    pub alternatives: Vec<(String, String)>,
+    pub machine: Option<String>,
+    pub machines_needed: Option<f64>,
+    /// Brought in from outside rather than produced or mined. When a supply covers only part of a
+    /// product's demand, the node producing or mining the rest has a supplied child of the same product.
+    pub supplied: bool,
+    pub children: Vec<BreakdownNode>,
+}
+
+/// Everything a tree is built from besides the requested outputs
+pub struct BreakdownInputs<'a> {
+    pub recipes: &'a [Recipe],
+    pub enabled: &'a HashSet<String>,
+    pub raw_resources: &'a HashSet<String>,
+    /// Rate of each item brought in from outside, used up before the item is produced
+    pub supplied: &'a HashMap<String, f64>,
+    /// Recipe id per product, used while it is enabled
+    pub choices: &'a BTreeMap<String, String>,
+    /// Clock speed in percent per product
+    pub clocks: &'a BTreeMap<String, f64>,
+}
+
+fn leaf(product: &str, rate: f64, recipe_name: Option<String>, supplied: bool) -> BreakdownNode {
+    BreakdownNode {
+        product: product.to_string(),
+        rate,
+        recipe_name,
+        alternatives: vec![],
+        machine: None,
+        machines_needed: None,
+        supplied,
+        children: vec![],
+    }
+}
+
+/// Supply left of each item while the trees are built, so items used in several places share it
+type Remaining = HashMap<String, f64>;
+
+fn build_tree(product: &str, rate: f64, inputs: &BreakdownInputs, remaining: &mut Remaining, path: &mut Vec<String>) -> BreakdownNode {
+    if path.contains(&product.to_string()) {
+        // True cycle detected (recursion in the current path)
+        return leaf(product, rate, Some("Cycle".to_string()), false);
+    }
+    // Whatever the supply does not cover is produced or mined, with the supplied part as a child
+    let taken = remaining.get_mut(product).map_or(0.0, |left| {
+        let taken = left.min(rate);
+        *left -= taken;
+        taken
+    });
+    if taken >= rate - 1e-9 {
+        return leaf(product, rate, None, true);
+    }
+    let rate = rate - taken;
+    let node = if inputs.raw_resources.contains(product) {
+        leaf(product, rate, None, false)
+    } else {
+        produce(product, rate, inputs, remaining, path)
+    };
+    if taken > 0.0 {
+        let mut node = node;
+        node.rate += taken;
+        node.children.insert(0, leaf(product, taken, None, true));
+        return node;
+    }
+    node
+}
+
+/// The node making a product with its first or chosen enabled recipe, unproducible without one
+fn produce(product: &str, rate: f64, inputs: &BreakdownInputs, remaining: &mut Remaining, path: &mut Vec<String>) -> BreakdownNode {
+    path.push(product.to_string());
+    let recipes_for_product: Vec<&Recipe> = inputs.recipes.iter()
+        .filter(|r| inputs.enabled.contains(&r.id) && r.outputs.iter().any(|o| o.item == product))
+        .collect();
+    let node = if !recipes_for_product.is_empty() {
+        // The chosen recipe while it stays enabled, the first one otherwise
+        let recipe = recipes_for_product
+            .iter()
+            .find(|r| inputs.choices.get(product) == Some(&r.id))
+            .unwrap_or(&recipes_for_product[0]);
+        let output = recipe.outputs.iter().find(|o| o.item == product).unwrap();
+        let clock = inputs.clocks.get(product).copied().unwrap_or(100.0) / 100.0;
+        let items_per_min = output.quantity as f64 * (60_000.0 / recipe.time as f64) * clock;
+        let machines_needed = rate / items_per_min;
+        let children = recipe.inputs.iter().map(|input| {
+            let input_rate = rate * (input.quantity as f64) / (output.quantity as f64);
+            build_tree(&input.item, input_rate, inputs, remaining, path)
+        }).collect();
+        BreakdownNode {
+            product: product.to_string(),
+            rate,
+            recipe_name: Some(recipe.name.clone()),
+            alternatives: recipes_for_product.iter().map(|r| (r.id.clone(), r.name.clone())).collect(),
+            machine: Some(recipe.machine.name.clone()),
+            machines_needed: Some(machines_needed),
+            supplied: false,
+            children,
+        }
+    } else {
+        // No enabled recipe produces this product: reported as unproducible
+        leaf(product, rate, None, false)
+    };
+    path.pop();
+    node
+}
+
+/// One tree per requested output, always using the first or chosen enabled recipe of each product
+pub fn build_breakdown(outputs: &[(String, f64)], inputs: &BreakdownInputs) -> Vec<BreakdownNode> {
+    let mut remaining = inputs.supplied.clone();
+    outputs
+        .iter()
+        .map(|(product, rate)| build_tree(product, *rate, inputs, &mut remaining, &mut Vec::new()))
+        .collect()
+}
+
+/// Supplied rate per item, summing an item listed more than once
+pub fn supply_rates(supplies: &[(String, f64)]) -> HashMap<String, f64> {
+    let mut rates = HashMap::new();
+    for (item, rate) in supplies {
+        *rates.entry(item.clone()).or_insert(0.0) += rate;
+    }
+    rates
+}
+
+/// The breakdown of one factory of a plan, with its supplies, recipe choices and clock speeds
+pub fn factory_breakdown(
+    factory: &FactoryPlan,
+    recipes: &[Recipe],
+    enabled: &HashSet<String>,
+    raw_resources: &HashSet<String>,
+) -> Vec<BreakdownNode> {
+    let supplied = supply_rates(&factory.supplies);
+    let inputs = BreakdownInputs {
+        recipes,
+        enabled,
+        raw_resources,
+        supplied: &supplied,
+        choices: &factory.recipe_choices,
+        clocks: &factory.clocks,
+    };
+    build_breakdown(&factory.outputs, &inputs)
+}
+
+/// First node of the trees, depth first, that is about a product
+pub fn find_node<'a>(nodes: &'a [BreakdownNode], product: &str) -> Option<&'a BreakdownNode> {
+    nodes
+        .iter()
+        .find_map(|node| if node.product == product { Some(node) } else { find_node(&node.children, product) })
+}
+
+/// Rate the trees make a product at, summed over every place it is used; `None` when it is not used.
+/// A partly supplied product counts once, since its produced node already includes the supplied part.
+pub fn product_rate(nodes: &[BreakdownNode], product: &str) -> Option<f64> {
+    nodes
+        .iter()
+        .filter_map(|node| if node.product == product { Some(node.rate) } else { product_rate(&node.children, product) })
+        .reduce(|a, b| a + b)
+}
+
+pub fn flatten_tree(node: &BreakdownNode, depth: usize, out: &mut Vec<(usize, BreakdownNode)>) {
+    out.push((depth, node.clone()));
+    for child in &node.children {
+        flatten_tree(child, depth + 1, out);
+    }
+}
+
+/// Leaves of the tree that are neither raw resources, supplied nor produced by an enabled recipe
+pub fn collect_unproducible(node: &BreakdownNode, raw_resources: &HashSet<String>, out: &mut Vec<String>) {
+    if node.recipe_name.is_none() && !node.supplied && !raw_resources.contains(&node.product) && !out.contains(&node.product) {
+        out.push(node.product.clone());
+    }
+    for child in &node.children {
+        collect_unproducible(child, raw_resources, out);
+    }
+}
+
+/// Total rate of the raw resource and supplied leaves of the trees, by item
+pub fn leaf_totals(nodes: &[BreakdownNode], raw_resources: &HashSet<String>) -> (BTreeMap<String, f64>, BTreeMap<String, f64>) {
+    fn collect(node: &BreakdownNode, raw_resources: &HashSet<String>, raw: &mut BTreeMap<String, f64>, supplied: &mut BTreeMap<String, f64>) {
+        if node.supplied {
+            *supplied.entry(node.product.clone()).or_insert(0.0) += node.rate;
+        } else if raw_resources.contains(&node.product) {
+            // Less the part supplied from outside
+            let supplied: f64 = node.children.iter().filter(|c| c.supplied).map(|c| c.rate).sum();
+            *raw.entry(node.product.clone()).or_insert(0.0) += node.rate - supplied;
+        }
+        for child in &node.children {
+            collect(child, raw_resources, raw, supplied);
+        }
+    }
+    let (mut raw, mut supplied) = (BTreeMap::new(), BTreeMap::new());
+    for node in nodes {
+        collect(node, raw_resources, &mut raw, &mut supplied);
+    }
+    (raw, supplied)
+}
+
+/// Machines needed across the trees, by machine name
+pub fn machine_totals(nodes: &[BreakdownNode]) -> BTreeMap<String, f64> {
+    fn collect(node: &BreakdownNode, totals: &mut BTreeMap<String, f64>) {
+        if let (Some(machine), Some(count)) = (&node.machine, node.machines_needed) {
+            *totals.entry(machine.clone()).or_insert(0.0) += count;
+        }
+        for child in &node.children {
+            collect(child, totals);
+        }
+    }
+    let mut totals = BTreeMap::new();
+    for node in nodes {
+        collect(node, &mut totals);
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::test_recipe;
+
+    const ORE: &str = "Desc_OreIron_C";
+    const INGOT: &str = "Desc_IronIngot_C";
+    const PLATE: &str = "Desc_IronPlate_C";
+
+    fn recipes() -> Vec<Recipe> {
+        vec![
+            test_recipe("Recipe_IngotIron_C", ORE, INGOT),
+            test_recipe("Recipe_Alternate_PureIronIngot_C", ORE, INGOT),
+            test_recipe("Recipe_IronPlate_C", INGOT, PLATE),
+        ]
+    }
+
+    #[test]
+    fn test_factory_breakdown_supplies_clocks_and_choices() {
+        let recipes = recipes();
+        let enabled = recipes.iter().map(|r| r.id.clone()).collect();
+        let raw_resources = HashSet::from([ORE.to_string()]);
+        let factory = FactoryPlan {
+            outputs: vec![(PLATE.to_string(), 60.0)],
+            supplies: vec![(INGOT.to_string(), 20.0)],
+            recipe_choices: BTreeMap::from([(INGOT.to_string(), "Recipe_Alternate_PureIronIngot_C".to_string())]),
+            clocks: BTreeMap::from([(PLATE.to_string(), 200.0)]),
+            ..FactoryPlan::named("Plates")
+        };
+        let breakdown = factory_breakdown(&factory, &recipes, &enabled, &raw_resources);
+
+        // 30/min per constructor, doubled by the clock
+        let plate = &breakdown[0];
+        assert_eq!((plate.rate, plate.machines_needed), (60.0, Some(1.0)));
+        // The 20/min supply covers part of the ingots; the chosen recipe makes the other 40
+        let ingot = &plate.children[0];
+        assert_eq!(ingot.rate, 60.0);
+        assert_eq!(ingot.recipe_name.as_deref(), Some("Recipe_Alternate_PureIronIngot_C"));
+        assert!((ingot.machines_needed.unwrap() - 40.0 / 30.0).abs() < 1e-9);
+        assert!(ingot.children[0].supplied && ingot.children[0].rate == 20.0);
+        assert_eq!((ingot.children[1].product.as_str(), ingot.children[1].rate), (ORE, 40.0));
+
+        let (raw, supplied) = leaf_totals(&breakdown, &raw_resources);
+        assert_eq!(raw, BTreeMap::from([(ORE.to_string(), 40.0)]));
+        assert_eq!(supplied, BTreeMap::from([(INGOT.to_string(), 20.0)]));
+        assert!((machine_totals(&breakdown)["Constructor"] - (1.0 + 40.0 / 30.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_supply_is_shared_between_trees() {
+        let recipes = recipes();
+        let enabled = recipes.iter().map(|r| r.id.clone()).collect();
+        let raw_resources = HashSet::from([ORE.to_string()]);
+        let supplied = supply_rates(&[(INGOT.to_string(), 10.0), (INGOT.to_string(), 20.0)]);
+        let inputs = BreakdownInputs {
+            recipes: &recipes,
+            enabled: &enabled,
+            raw_resources: &raw_resources,
+            supplied: &supplied,
+            choices: &BTreeMap::new(),
+            clocks: &BTreeMap::new(),
+        };
+        let breakdown = build_breakdown(&[(INGOT.to_string(), 20.0), (PLATE.to_string(), 20.0)], &inputs);
+
+        // The first tree takes 20 of the 30 supplied; the plates get the last 10 and make 10 more
+        assert!(breakdown[0].supplied && breakdown[0].children.is_empty());
+        let ingot = &breakdown[1].children[0];
+        assert!(!ingot.supplied && ingot.rate == 20.0);
+        assert_eq!(ingot.recipe_name.as_deref(), Some("Recipe_IngotIron_C"));
+        assert!(ingot.children[0].supplied && ingot.children[0].rate == 10.0);
+        assert_eq!(leaf_totals(&breakdown, &raw_resources).0[ORE], 10.0);
+    }
+
+    #[test]
+    fn test_supplied_raw_resource_is_mined_only_for_the_rest() {
+        let recipes = recipes();
+        let enabled = recipes.iter().map(|r| r.id.clone()).collect();
+        let raw_resources = HashSet::from([ORE.to_string()]);
+        let supplied = supply_rates(&[(ORE.to_string(), 15.0)]);
+        let inputs = BreakdownInputs {
+            recipes: &recipes,
+            enabled: &enabled,
+            raw_resources: &raw_resources,
+            supplied: &supplied,
+            choices: &BTreeMap::new(),
+            clocks: &BTreeMap::new(),
+        };
+        let breakdown = build_breakdown(&[(INGOT.to_string(), 10.0), (INGOT.to_string(), 30.0)], &inputs);
+
+        // The first tree's ore is all supplied; the second mines what is left after the other 5
+        assert!(breakdown[0].children[0].supplied && breakdown[0].children[0].rate == 10.0);
+        let ore = &breakdown[1].children[0];
+        assert!(!ore.supplied && ore.rate == 30.0);
+        assert!(ore.children[0].supplied && ore.children[0].rate == 5.0);
+        let (raw, supplied) = leaf_totals(&breakdown, &raw_resources);
+        assert_eq!(raw[ORE], 25.0);
+        assert_eq!(supplied[ORE], 15.0);
+    }
+
+    #[test]
+    fn test_product_rate_merges_every_use() {
+        let recipes = recipes();
+        let enabled = recipes.iter().map(|r| r.id.clone()).collect();
+        let raw_resources = HashSet::from([ORE.to_string()]);
+        let supplied = supply_rates(&[(INGOT.to_string(), 5.0)]);
+        let inputs = BreakdownInputs {
+            recipes: &recipes,
+            enabled: &enabled,
+            raw_resources: &raw_resources,
+            supplied: &supplied,
+            choices: &BTreeMap::new(),
+            clocks: &BTreeMap::new(),
+        };
+        let breakdown = build_breakdown(&[(INGOT.to_string(), 20.0), (PLATE.to_string(), 30.0)], &inputs);
+
+        // Ingots are made for both outputs; the supplied 5 is part of the first node, not added again
+        assert_eq!(product_rate(&breakdown, INGOT), Some(50.0));
+        assert_eq!(product_rate(&breakdown, ORE), Some(45.0));
+        assert_eq!(product_rate(&breakdown, "Desc_Wire_C"), None);
+    }
+}
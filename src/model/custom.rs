@@ -0,0 +1,149 @@
+//! User-made recipes and items, kept per game next to the dataset and merged into the recipe list
+//! so the breakdown and analysis treat them like the game's own.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::recipe::{Item, Recipe};
+
+/// Local storage key prefix of the custom recipes; the game id is appended
+pub const CUSTOM_RECIPES_STORAGE_KEY: &str = "factory-planner.custom-recipes";
+
+/// Bumped whenever the stored layout or the recipe model changes; older entries are ignored
+pub const CUSTOM_RECIPES_STORAGE_VERSION: u32 = 1;
+
+/// `Recipe::source` of custom recipes
+pub const CUSTOM_SOURCE: &str = "Custom";
+
+/// Prefix of the ids of custom recipes and items, so they never collide with the game's
+const CUSTOM_ID_PREFIX: &str = "Custom_";
+
+pub fn custom_recipes_storage_key(game: &str) -> String {
+    format!("{}.{}", CUSTOM_RECIPES_STORAGE_KEY, game)
+}
+
+pub fn is_custom_id(id: &str) -> bool {
+    id.starts_with(CUSTOM_ID_PREFIX)
+}
+
+/// A fresh custom id derived from a display name, e.g. `Custom_IronPlate_2` when `Custom_IronPlate` is taken
+pub fn new_custom_id(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let stem: String = name.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let base = format!("{}{}", CUSTOM_ID_PREFIX, if stem.is_empty() { "Unnamed" } else { &stem });
+    if !taken(&base) {
+        return base;
+    }
+    (2..).map(|n| format!("{}_{}", base, n)).find(|id| !taken(id)).expect("some suffix is free")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CustomRecipes {
+    pub recipes: Vec<Recipe>,
+    /// Items only custom recipes use
+    pub items: Vec<Item>,
+}
+
+impl CustomRecipes {
+    /// Add a recipe or replace the one with the same id; the recipe is tagged as custom
+    pub fn upsert(&mut self, mut recipe: Recipe) {
+        recipe.source = Some(CUSTOM_SOURCE.to_string());
+        match self.recipes.iter_mut().find(|r| r.id == recipe.id) {
+            Some(existing) => *existing = recipe,
+            None => self.recipes.push(recipe),
+        }
+        self.drop_unused_items();
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.recipes.retain(|r| r.id != id);
+        self.drop_unused_items();
+    }
+
+    /// Id of the item labelled `label`, creating a custom item when neither the dataset nor this set has one
+    pub fn item_for_label(&mut self, label: &str, item_names: &HashMap<String, String>) -> String {
+        let label = label.trim();
+        if let Some(item) = self.items.iter().find(|i| i.name == label) {
+            return item.id.clone();
+        }
+        // Custom items in the map belong to the merged set, which may be older than this one
+        if let Some((id, _)) = item_names.iter().find(|(id, name)| name.as_str() == label && !is_custom_id(id)) {
+            return id.clone();
+        }
+        let id = new_custom_id(label, |id| item_names.contains_key(id) || self.items.iter().any(|i| i.id == id));
+        self.items.push(Item { id: id.clone(), name: label.to_string() });
+        id
+    }
+
+    fn drop_unused_items(&mut self) {
+        let recipes = &self.recipes;
+        self.items.retain(|item| {
+            recipes
+                .iter()
+                .any(|r| r.inputs.iter().chain(r.outputs.iter()).any(|s| s.item == item.id))
+        });
+    }
+}
+
+/// Custom recipes as kept in local storage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredCustomRecipes {
+    pub version: u32,
+    pub custom: CustomRecipes,
+}
+
+impl StoredCustomRecipes {
+    pub fn new(custom: CustomRecipes) -> Self {
+        StoredCustomRecipes {
+            version: CUSTOM_RECIPES_STORAGE_VERSION,
+            custom,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("custom recipes serialize")
+    }
+
+    /// Parse a stored entry; entries from another storage version are treated as absent
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str::<StoredCustomRecipes>(json)
+            .ok()
+            .filter(|c| c.version == CUSTOM_RECIPES_STORAGE_VERSION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{recipe::test_recipe as recipe, AppStore};
+
+    #[test]
+    fn test_custom_recipes_merge_into_store() {
+        let mut store = AppStore {
+            recipes: vec![recipe("Recipe_IronPlate_C", "Desc_IronIngot_C", "Desc_IronPlate_C")],
+            item_names: HashMap::from([
+                ("Desc_IronIngot_C".to_string(), "Iron Ingot".to_string()),
+                ("Desc_IronPlate_C".to_string(), "Iron Plate".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let mut custom = CustomRecipes::default();
+        let output = custom.item_for_label("Iron Pellet", &store.item_names);
+        assert_eq!(output, "Custom_IronPellet");
+        assert_eq!(custom.item_for_label("Iron Plate", &store.item_names), "Desc_IronPlate_C");
+        let id = new_custom_id("Iron Plate", |id| store.recipes.iter().any(|r| r.id == id));
+        custom.upsert(recipe(&id, "Desc_IronPlate_C", &output));
+        assert_eq!(custom.recipes[0].source.as_deref(), Some(CUSTOM_SOURCE));
+
+        store.set_custom_recipes(custom.clone());
+        assert_eq!(store.recipes.len(), 2);
+        assert_eq!(store.item_names["Custom_IronPellet"], "Iron Pellet");
+
+        // Replacing the set drops recipes and items that are no longer custom
+        custom.remove(&id);
+        assert!(custom.items.is_empty());
+        store.set_custom_recipes(custom);
+        assert_eq!(store.recipes.len(), 1);
+        assert!(!store.item_names.contains_key("Custom_IronPellet"));
+    }
+}
@@ -0,0 +1,221 @@
+//! Where the current dataset came from, the compiled dataset shipped with the app, and the
+//! compact form kept in browser storage
+//!
+//! A Docs file is several megabytes of UTF-16; the parsed recipes and item labels are a
+//! small fraction of that, so both the build and storage keep those instead of the original file.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::recipe::{CraftingMachine, Item, Recipe, Schematic};
+
+/// Local storage key of the user-supplied dataset
+pub const DATASET_STORAGE_KEY: &str = "factory-planner.dataset";
+
+/// Bumped whenever the stored layout or the recipe model changes; older entries are ignored
+pub const DATASET_STORAGE_VERSION: u32 = 5;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DatasetSource {
+    /// Nothing loaded yet
+    #[default]
+    Loading,
+    /// The selected game has no bundled dataset and no file was loaded
+    Missing,
+    /// The Docs file shipped with the app
+    Bundled,
+    /// A Docs file supplied by the user
+    Uploaded { file_name: String },
+}
+
+impl std::fmt::Display for DatasetSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatasetSource::Loading => write!(f, "Loading dataset..."),
+            DatasetSource::Missing => write!(f, "None, load a data file"),
+            DatasetSource::Bundled => write!(f, "Bundled dataset"),
+            DatasetSource::Uploaded { file_name } => write!(f, "{}", file_name),
+        }
+    }
+}
+
+/// A parsed user dataset as kept in local storage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredDataset {
+    pub version: u32,
+    pub file_name: String,
+    pub dataset: CompiledDataset,
+}
+
+impl StoredDataset {
+    pub fn new(file_name: String, dataset: CompiledDataset) -> Self {
+        StoredDataset {
+            version: DATASET_STORAGE_VERSION,
+            file_name,
+            dataset,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("dataset serializes")
+    }
+
+    /// Parse a stored entry; entries from another storage version are treated as absent
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str::<StoredDataset>(json)
+            .ok()
+            .filter(|d| d.version == DATASET_STORAGE_VERSION)
+    }
+}
+
+/// First bytes of a compiled dataset file
+pub const COMPILED_DATASET_MAGIC: [u8; 4] = *b"FPDS";
+
+/// Bumped whenever `CompiledDataset` or the recipe model changes; rebuild with `cargo run --bin build_dataset`
+pub const COMPILED_DATASET_VERSION: u32 = 5;
+
+/// File name of the compiled dataset for a locale, next to its Docs file
+pub fn compiled_dataset_file(locale: &str) -> String {
+    format!("satisfactory_{}.dataset", locale)
+}
+
+/// Labels of the units the dataset's quantities are expressed in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetUnits {
+    /// Item flows, e.g. items/min
+    pub rate: String,
+    /// Machine power draw, e.g. MW
+    pub power: String,
+}
+
+impl Default for DatasetUnits {
+    fn default() -> Self {
+        DatasetUnits {
+            rate: "items/min".to_string(),
+            power: "MW".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatasetError {
+    /// The bytes do not start with `COMPILED_DATASET_MAGIC`
+    NotADataset,
+    /// Written by another version of the build step
+    UnsupportedVersion { found: u32, expected: u32 },
+    /// The payload is truncated or does not match the model
+    Decode { message: String },
+}
+
+impl std::fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatasetError::NotADataset => write!(f, "not a compiled dataset"),
+            DatasetError::UnsupportedVersion { found, expected } => {
+                write!(f, "dataset version {} is not supported (expected {})", found, expected)
+            }
+            DatasetError::Decode { message } => write!(f, "failed to decode dataset: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DatasetError {}
+
+/// Normalized game data produced once from a Docs file by the `build_dataset` bin
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompiledDataset {
+    /// Id of the `GameAdapter` that produced the dataset
+    pub game: String,
+    /// Dataset version within the game, e.g. a release or difficulty
+    pub version: String,
+    /// Locale of the labels
+    pub locale: String,
+    pub units: DatasetUnits,
+    pub items: Vec<Item>,
+    pub machines: Vec<CraftingMachine>,
+    pub recipes: Vec<Recipe>,
+    pub schematics: Vec<Schematic>,
+    /// Item ids of the raw resources, from the game's resource descriptors
+    pub raw_resources: Vec<String>,
+    /// Items per inventory slot by item id; fluids and items whose game has no stacks are absent
+    pub stack_sizes: BTreeMap<String, u32>,
+    /// Recipes skipped while compiling
+    pub warnings: Vec<String>,
+}
+
+impl CompiledDataset {
+    /// Magic, little-endian format version, then the bincode payload
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = COMPILED_DATASET_MAGIC.to_vec();
+        bytes.extend(COMPILED_DATASET_VERSION.to_le_bytes());
+        bytes.extend(bincode::serialize(self).expect("dataset serializes"));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DatasetError> {
+        let header_len = COMPILED_DATASET_MAGIC.len() + 4;
+        if bytes.len() < header_len || bytes[..COMPILED_DATASET_MAGIC.len()] != COMPILED_DATASET_MAGIC {
+            return Err(DatasetError::NotADataset);
+        }
+        let version = u32::from_le_bytes(bytes[COMPILED_DATASET_MAGIC.len()..header_len].try_into().unwrap());
+        if version != COMPILED_DATASET_VERSION {
+            return Err(DatasetError::UnsupportedVersion {
+                found: version,
+                expected: COMPILED_DATASET_VERSION,
+            });
+        }
+        bincode::deserialize(&bytes[header_len..]).map_err(|e| DatasetError::Decode { message: e.to_string() })
+    }
+
+    /// Whether the bytes look like a compiled dataset rather than a Docs file
+    pub fn is_compiled(bytes: &[u8]) -> bool {
+        bytes.starts_with(&COMPILED_DATASET_MAGIC)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::ItemStack;
+
+    #[test]
+    fn test_compiled_dataset_round_trip() {
+        let machine = CraftingMachine {
+            id: "Build_SmelterMk1_C".to_string(),
+            name: "Smelter".to_string(),
+            power: 4.0,
+        };
+        let dataset = CompiledDataset {
+            game: "satisfactory".to_string(),
+            version: "1.0".to_string(),
+            locale: "en-US".to_string(),
+            units: DatasetUnits::default(),
+            items: vec![Item { id: "Desc_IronIngot_C".to_string(), name: "Iron Ingot".to_string() }],
+            machines: vec![machine.clone()],
+            recipes: vec![Recipe {
+                id: "Recipe_IngotIron_C".to_string(),
+                name: "Iron Ingot".to_string(),
+                inputs: vec![ItemStack { item: "Desc_OreIron_C".to_string(), quantity: 1 }],
+                outputs: vec![ItemStack { item: "Desc_IronIngot_C".to_string(), quantity: 1 }],
+                machine,
+                time: 2000,
+                enabled: true,
+                source: Some("RefinedPower".to_string()),
+            }],
+            schematics: vec![],
+            raw_resources: vec!["Desc_OreIron_C".to_string()],
+            stack_sizes: BTreeMap::from([("Desc_IronIngot_C".to_string(), 100)]),
+            warnings: vec![],
+        };
+        let mut bytes = dataset.to_bytes();
+        assert!(CompiledDataset::is_compiled(&bytes));
+        assert_eq!(CompiledDataset::from_bytes(&bytes).unwrap(), dataset);
+        bytes[4] = 99;
+        assert!(matches!(
+            CompiledDataset::from_bytes(&bytes),
+            Err(DatasetError::UnsupportedVersion { found: 99, .. })
+        ));
+        assert_eq!(CompiledDataset::from_bytes(b"[{}]"), Err(DatasetError::NotADataset));
+    }
+}
@@ -0,0 +1,156 @@
+//! Diagnostics for items that cannot be produced from raw resources.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::model::recipe::Recipe;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MissingLink {
+    /// No enabled recipe produces the item; lists the disabled recipes that would
+    NoEnabledRecipe { disabled_recipes: Vec<String> },
+    /// Every enabled recipe for the item needs at least one unreachable input
+    UnreachableInputs { recipes: Vec<(String, Vec<String>)> },
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UnreachableItem {
+    pub item: String,
+    pub missing: MissingLink,
+    /// Items without any enabled recipe that this item ultimately depends on
+    pub root_causes: Vec<String>,
+}
+
+/// Items producible from raw resources using only the enabled recipes
+pub fn compute_reachable_items(
+    recipes: &[Recipe],
+    enabled: &HashSet<String>,
+    raw_resources: &HashSet<String>,
+) -> HashSet<String> {
+    let mut reachable: HashSet<String> = raw_resources.clone();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for recipe in recipes.iter().filter(|r| enabled.contains(&r.id)) {
+            if recipe.inputs.iter().all(|i| reachable.contains(&i.item)) {
+                for output in &recipe.outputs {
+                    changed |= reachable.insert(output.item.clone());
+                }
+            }
+        }
+    }
+    reachable
+}
+
+/// List every item used by any recipe that cannot be produced under the enabled set, with its missing link
+pub fn compute_unreachable_items(
+    recipes: &[Recipe],
+    enabled: &HashSet<String>,
+    raw_resources: &HashSet<String>,
+) -> Vec<UnreachableItem> {
+    let reachable = compute_reachable_items(recipes, enabled, raw_resources);
+    let all_items: BTreeSet<&String> = recipes
+        .iter()
+        .flat_map(|r| r.inputs.iter().chain(r.outputs.iter()).map(|s| &s.item))
+        .collect();
+    let mut missing: BTreeMap<String, MissingLink> = BTreeMap::new();
+    for item in all_items.into_iter().filter(|i| !reachable.contains(*i)) {
+        let producers: Vec<&Recipe> = recipes
+            .iter()
+            .filter(|r| r.outputs.iter().any(|o| &o.item == item))
+            .collect();
+        let enabled_producers: Vec<&&Recipe> = producers.iter().filter(|r| enabled.contains(&r.id)).collect();
+        let link = if enabled_producers.is_empty() {
+            MissingLink::NoEnabledRecipe {
+                disabled_recipes: producers.iter().map(|r| r.name.clone()).collect(),
+            }
+        } else {
+            MissingLink::UnreachableInputs {
+                recipes: enabled_producers
+                    .iter()
+                    .map(|r| {
+                        let inputs = r
+                            .inputs
+                            .iter()
+                            .filter(|i| !reachable.contains(&i.item))
+                            .map(|i| i.item.clone())
+                            .collect();
+                        (r.name.clone(), inputs)
+                    })
+                    .collect(),
+            }
+        };
+        missing.insert(item.clone(), link);
+    }
+    missing
+        .iter()
+        .map(|(item, link)| UnreachableItem {
+            item: item.clone(),
+            missing: link.clone(),
+            root_causes: root_causes(item, &missing),
+        })
+        .collect()
+}
+
+fn root_causes(item: &str, missing: &BTreeMap<String, MissingLink>) -> Vec<String> {
+    let mut causes = BTreeSet::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![item.to_string()];
+    while let Some(current) = stack.pop() {
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+        match missing.get(&current) {
+            Some(MissingLink::NoEnabledRecipe { .. }) => {
+                causes.insert(current);
+            }
+            Some(MissingLink::UnreachableInputs { recipes }) => {
+                stack.extend(recipes.iter().flat_map(|(_, inputs)| inputs.iter().cloned()));
+            }
+            None => {}
+        }
+    }
+    causes.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::{CraftingMachine, ItemStack};
+
+    fn recipe(name: &str, inputs: &[&str], output: &str, enabled: bool) -> Recipe {
+        Recipe {
+            id: format!("Recipe_{}_C", name.replace(' ', "")),
+            name: name.to_string(),
+            inputs: inputs.iter().map(|i| ItemStack { item: i.to_string(), quantity: 1 }).collect(),
+            outputs: vec![ItemStack { item: output.to_string(), quantity: 1 }],
+            machine: CraftingMachine {
+                id: "Build_ConstructorMk1_C".to_string(),
+                name: "Constructor".to_string(),
+                power: 4.0,
+            },
+            time: 1000,
+            enabled,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_disabled_screw_recipe_is_reported() {
+        let recipes = vec![
+            recipe("Iron Ingot", &["Desc_OreIron_C"], "Desc_IronIngot_C", true),
+            recipe("Iron Rod", &["Desc_IronIngot_C"], "Desc_IronRod_C", true),
+            recipe("Screw", &["Desc_IronRod_C"], "Desc_IronScrew_C", false),
+            recipe("Reinforced Iron Plate", &["Desc_IronScrew_C", "Desc_IronIngot_C"], "Desc_IronPlateReinforced_C", true),
+        ];
+        let enabled = recipes.iter().filter(|r| r.enabled).map(|r| r.id.clone()).collect();
+        let raw_resources = HashSet::from(["Desc_OreIron_C".to_string()]);
+        let unreachable = compute_unreachable_items(&recipes, &enabled, &raw_resources);
+        let items: Vec<_> = unreachable.iter().map(|u| u.item.as_str()).collect();
+        assert_eq!(items, vec!["Desc_IronPlateReinforced_C", "Desc_IronScrew_C"]);
+        assert_eq!(
+            unreachable[1].missing,
+            MissingLink::NoEnabledRecipe { disabled_recipes: vec!["Screw".to_string()] }
+        );
+        assert_eq!(unreachable[0].root_causes, vec!["Desc_IronScrew_C".to_string()]);
+    }
+}
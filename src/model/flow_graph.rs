@@ -0,0 +1,393 @@
+//! The breakdown as a graph: one node per product with the items flowing between them, and the
+//! layouts the graph view draws it with
+
+use std::collections::{HashMap, HashSet};
+
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use crate::model::breakdown::BreakdownNode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowNodeKind {
+    /// Made by a recipe in the plan
+    Recipe,
+    RawResource,
+    /// Brought in from outside
+    Supplied,
+    /// Needed but made by no enabled recipe
+    Unproducible,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowNode {
+    pub item: String,
+    pub kind: FlowNodeKind,
+    pub recipe_name: Option<String>,
+    pub machine: Option<String>,
+    /// Summed over every place the product appears in the breakdown
+    pub machines_needed: f64,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowEdge {
+    pub item: String,
+    pub rate: f64,
+}
+
+/// Items flow along the edges, from the node making them to the node using them
+pub type FlowGraph = DiGraph<FlowNode, FlowEdge>;
+
+/// Merge the breakdown trees into one graph, so a product used in several places is one node
+pub fn build_flow_graph(breakdown: &[BreakdownNode], raw_resources: &HashSet<String>) -> FlowGraph {
+    fn add(
+        node: &BreakdownNode,
+        parent: Option<NodeIndex>,
+        graph: &mut FlowGraph,
+        index: &mut HashMap<(String, bool), NodeIndex>,
+        raw_resources: &HashSet<String>,
+    ) {
+        // A cycle leaf points back at a product already in the tree and adds no production of its own
+        let is_cycle = node.machine.is_none() && node.recipe_name.is_some();
+        let kind = if node.machine.is_some() {
+            FlowNodeKind::Recipe
+        } else if node.supplied {
+            FlowNodeKind::Supplied
+        } else if raw_resources.contains(&node.product) {
+            FlowNodeKind::RawResource
+        } else {
+            FlowNodeKind::Unproducible
+        };
+        // The supplied part of a product is its own node, feeding the node that produces the rest
+        let id = *index.entry((node.product.clone(), kind == FlowNodeKind::Supplied)).or_insert_with(|| {
+            graph.add_node(FlowNode {
+                item: node.product.clone(),
+                kind,
+                recipe_name: None,
+                machine: None,
+                machines_needed: 0.0,
+                rate: 0.0,
+            })
+        });
+        if !is_cycle {
+            let merged = &mut graph[id];
+            merged.rate += node.rate;
+            merged.machines_needed += node.machines_needed.unwrap_or(0.0);
+            if kind == FlowNodeKind::Recipe {
+                merged.kind = kind;
+                merged.recipe_name = merged.recipe_name.take().or_else(|| node.recipe_name.clone());
+                merged.machine = merged.machine.take().or_else(|| node.machine.clone());
+            }
+        }
+        if let Some(parent) = parent {
+            match graph.find_edge(id, parent) {
+                Some(edge) => graph[edge].rate += node.rate,
+                None => {
+                    graph.add_edge(id, parent, FlowEdge { item: node.product.clone(), rate: node.rate });
+                }
+            }
+        }
+        for child in &node.children {
+            add(child, Some(id), graph, index, raw_resources);
+        }
+    }
+    let mut graph = FlowGraph::new();
+    let mut index = HashMap::new();
+    for root in breakdown {
+        add(root, None, &mut graph, &mut index, raw_resources);
+    }
+    graph
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphLayout {
+    /// Columns from raw resources on the left to the outputs on the right
+    Layered,
+    ForceDirected,
+    /// Layered, with node heights and edge widths proportional to the rates
+    Sankey,
+}
+
+impl GraphLayout {
+    pub const ALL: [GraphLayout; 3] = [GraphLayout::Layered, GraphLayout::ForceDirected, GraphLayout::Sankey];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            GraphLayout::Layered => "layered",
+            GraphLayout::ForceDirected => "force",
+            GraphLayout::Sankey => "sankey",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphLayout::Layered => "Layered",
+            GraphLayout::ForceDirected => "Force-directed",
+            GraphLayout::Sankey => "Sankey",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.id() == id)
+    }
+}
+
+/// Top-left corner and size of a node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Where an edge leaves its source and reaches its target, with the width to draw it at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgePath {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub width: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowGraphLayout {
+    /// By node index
+    pub nodes: Vec<NodeBox>,
+    /// By edge index
+    pub edges: Vec<EdgePath>,
+    pub width: f64,
+    pub height: f64,
+}
+
+const NODE_WIDTH: f64 = 170.0;
+const NODE_HEIGHT: f64 = 44.0;
+const SANKEY_NODE_WIDTH: f64 = 18.0;
+const COLUMN_GAP: f64 = 90.0;
+const ROW_GAP: f64 = 24.0;
+/// Height of the tallest Sankey column, gaps excluded
+const SANKEY_HEIGHT: f64 = 480.0;
+const MAX_EDGE_WIDTH: f64 = 12.0;
+
+/// Column of each node: the longest path to it from a node nothing flows into; cycles are cut off
+fn layers(graph: &FlowGraph) -> Vec<usize> {
+    let mut layer = vec![0; graph.node_count()];
+    for _ in 0..graph.node_count() {
+        let mut changed = false;
+        for edge in graph.edge_references() {
+            let (from, to) = (edge.source().index(), edge.target().index());
+            if layer[to] < layer[from] + 1 && layer[from] + 1 < graph.node_count() {
+                layer[to] = layer[from] + 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    layer
+}
+
+/// Nodes of each column, ordered by the mean row of their neighbours to cut down on crossing edges
+fn ordered_columns(graph: &FlowGraph, layer: &[usize]) -> Vec<Vec<NodeIndex>> {
+    let columns = layer.iter().max().map_or(0, |max| max + 1);
+    let mut order: Vec<Vec<NodeIndex>> = vec![Vec::new(); columns];
+    for node in graph.node_indices() {
+        order[layer[node.index()]].push(node);
+    }
+    let mut row = vec![0.0; graph.node_count()];
+    let place = |order: &[Vec<NodeIndex>], row: &mut Vec<f64>| {
+        for column in order {
+            for (i, node) in column.iter().enumerate() {
+                row[node.index()] = i as f64;
+            }
+        }
+    };
+    place(&order, &mut row);
+    for sweep in 0..4 {
+        let direction = if sweep % 2 == 0 { Direction::Outgoing } else { Direction::Incoming };
+        for column in order.iter_mut() {
+            let key = |node: &NodeIndex| {
+                let rows: Vec<f64> = graph.neighbors_directed(*node, direction).map(|n| row[n.index()]).collect();
+                if rows.is_empty() { row[node.index()] } else { rows.iter().sum::<f64>() / rows.len() as f64 }
+            };
+            column.sort_by(|a, b| key(a).total_cmp(&key(b)));
+        }
+        place(&order, &mut row);
+    }
+    order
+}
+
+fn throughput(graph: &FlowGraph, node: NodeIndex) -> f64 {
+    let sum = |direction| graph.edges_directed(node, direction).map(|e| e.weight().rate).sum::<f64>();
+    sum(Direction::Incoming).max(sum(Direction::Outgoing)).max(graph[node].rate)
+}
+
+fn layered(graph: &FlowGraph, sankey: bool) -> (Vec<NodeBox>, f64) {
+    let layer = layers(graph);
+    let columns = ordered_columns(graph, &layer);
+    let tallest = columns
+        .iter()
+        .map(|column| column.iter().map(|n| throughput(graph, *n)).sum::<f64>())
+        .fold(0.0, f64::max);
+    let scale = if tallest > 0.0 { SANKEY_HEIGHT / tallest } else { 0.0 };
+    let width = if sankey { SANKEY_NODE_WIDTH } else { NODE_WIDTH };
+    let mut boxes = vec![NodeBox { x: 0.0, y: 0.0, width, height: NODE_HEIGHT }; graph.node_count()];
+    for (c, column) in columns.iter().enumerate() {
+        let mut y = 0.0;
+        for node in column {
+            let height = if sankey { (throughput(graph, *node) * scale).max(2.0) } else { NODE_HEIGHT };
+            // Sankey labels sit right of the bars, so their columns leave room for the text
+            boxes[node.index()] = NodeBox { x: c as f64 * (NODE_WIDTH + COLUMN_GAP), y, width, height };
+            y += height + ROW_GAP;
+        }
+    }
+    (boxes, scale)
+}
+
+/// Fruchterman-Reingold from the layered positions, so the result is the same on every render
+fn force_directed(graph: &FlowGraph) -> Vec<NodeBox> {
+    let (mut boxes, _) = layered(graph, false);
+    let n = graph.node_count();
+    if n < 2 {
+        return boxes;
+    }
+    let k = NODE_WIDTH * 1.2;
+    let mut temperature = k;
+    for _ in 0..150 {
+        let mut shift = vec![(0.0, 0.0); n];
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let (dx, dy) = (boxes[i].x - boxes[j].x, boxes[i].y - boxes[j].y);
+                let distance = (dx * dx + dy * dy).sqrt().max(1.0);
+                let force = k * k / distance;
+                shift[i].0 += dx / distance * force;
+                shift[i].1 += dy / distance * force;
+            }
+        }
+        for edge in graph.edge_references() {
+            let (a, b) = (edge.source().index(), edge.target().index());
+            let (dx, dy) = (boxes[a].x - boxes[b].x, boxes[a].y - boxes[b].y);
+            let distance = (dx * dx + dy * dy).sqrt().max(1.0);
+            let force = distance * distance / k;
+            shift[a].0 -= dx / distance * force;
+            shift[a].1 -= dy / distance * force;
+            shift[b].0 += dx / distance * force;
+            shift[b].1 += dy / distance * force;
+        }
+        for (node, (dx, dy)) in boxes.iter_mut().zip(shift) {
+            let length = (dx * dx + dy * dy).sqrt().max(1e-9);
+            node.x += dx / length * length.min(temperature);
+            node.y += dy / length * length.min(temperature);
+        }
+        temperature *= 0.95;
+    }
+    let (min_x, min_y) = boxes.iter().fold((f64::MAX, f64::MAX), |(x, y), b| (x.min(b.x), y.min(b.y)));
+    for node in boxes.iter_mut() {
+        node.x -= min_x;
+        node.y -= min_y;
+    }
+    boxes
+}
+
+/// Place the nodes and edges of a graph for drawing
+pub fn layout_flow_graph(graph: &FlowGraph, layout: GraphLayout) -> FlowGraphLayout {
+    let (nodes, scale) = match layout {
+        GraphLayout::Layered => layered(graph, false),
+        GraphLayout::Sankey => layered(graph, true),
+        GraphLayout::ForceDirected => (force_directed(graph), 0.0),
+    };
+    let max_rate = graph.edge_weights().map(|e| e.rate).fold(0.0, f64::max);
+    let mut edges = vec![EdgePath { from: (0.0, 0.0), to: (0.0, 0.0), width: 1.0 }; graph.edge_count()];
+    if layout == GraphLayout::Sankey {
+        // Bands stack down each node in the order of the nodes at their other end
+        let mut leaving = vec![0.0; graph.node_count()];
+        let mut arriving = vec![0.0; graph.node_count()];
+        let mut order: Vec<_> = graph.edge_references().collect();
+        order.sort_by(|a, b| nodes[a.target().index()].y.total_cmp(&nodes[b.target().index()].y));
+        for edge in &order {
+            let (source, width) = (&nodes[edge.source().index()], edge.weight().rate * scale);
+            let offset = &mut leaving[edge.source().index()];
+            edges[edge.id().index()].from = (source.x + source.width, source.y + *offset + width / 2.0);
+            edges[edge.id().index()].width = width.max(1.0);
+            *offset += width;
+        }
+        order.sort_by(|a, b| nodes[a.source().index()].y.total_cmp(&nodes[b.source().index()].y));
+        for edge in &order {
+            let (target, width) = (&nodes[edge.target().index()], edge.weight().rate * scale);
+            let offset = &mut arriving[edge.target().index()];
+            edges[edge.id().index()].to = (target.x, target.y + *offset + width / 2.0);
+            *offset += width;
+        }
+    } else {
+        for edge in graph.edge_references() {
+            let (source, target) = (&nodes[edge.source().index()], &nodes[edge.target().index()]);
+            edges[edge.id().index()] = EdgePath {
+                from: (source.x + source.width, source.y + source.height / 2.0),
+                to: (target.x, target.y + target.height / 2.0),
+                width: if max_rate > 0.0 { 1.0 + (MAX_EDGE_WIDTH - 1.0) * edge.weight().rate / max_rate } else { 1.0 },
+            };
+        }
+    }
+    let width = nodes.iter().map(|b| b.x + NODE_WIDTH).fold(0.0, f64::max);
+    let height = nodes.iter().map(|b| b.y + b.height).fold(0.0, f64::max);
+    FlowGraphLayout { nodes, edges, width, height }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(product: &str, rate: f64, machines: Option<f64>, children: Vec<BreakdownNode>) -> BreakdownNode {
+        BreakdownNode {
+            product: product.to_string(),
+            rate,
+            recipe_name: machines.map(|_| product.to_string()),
+            alternatives: vec![],
+            machine: machines.map(|_| "Constructor".to_string()),
+            machines_needed: machines,
+            supplied: false,
+            children,
+        }
+    }
+
+    #[test]
+    fn test_flow_graph_merges_products_and_lays_out() {
+        // Reinforced plates use ingots directly and through plates; the ingots become one node
+        let ore = |rate| node("Desc_OreIron_C", rate, None, vec![]);
+        let ingot = |rate| node("Desc_IronIngot_C", rate, Some(rate / 30.0), vec![ore(rate)]);
+        let breakdown = vec![node(
+            "Desc_IronPlateReinforced_C",
+            5.0,
+            Some(1.0),
+            vec![node("Desc_IronPlate_C", 30.0, Some(1.5), vec![ingot(45.0)]), ingot(15.0)],
+        )];
+        let raw = HashSet::from(["Desc_OreIron_C".to_string()]);
+        let graph = build_flow_graph(&breakdown, &raw);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        let find = |item: &str| graph.node_indices().find(|n| graph[*n].item == item).unwrap();
+        let ingots = &graph[find("Desc_IronIngot_C")];
+        assert_eq!((ingots.rate, ingots.machines_needed), (60.0, 2.0));
+        assert_eq!(graph[find("Desc_OreIron_C")].kind, FlowNodeKind::RawResource);
+
+        let layered = layout_flow_graph(&graph, GraphLayout::Layered);
+        let x = |item: &str| layered.nodes[find(item).index()].x;
+        assert!(x("Desc_OreIron_C") < x("Desc_IronIngot_C"));
+        assert!(x("Desc_IronPlate_C") < x("Desc_IronPlateReinforced_C"));
+
+        // Sankey heights follow the larger of what flows in and out: 60 ingots/min, 45 into the plates
+        let sankey = layout_flow_graph(&graph, GraphLayout::Sankey);
+        let height = |item: &str| sankey.nodes[find(item).index()].height;
+        assert!((height("Desc_IronIngot_C") / height("Desc_OreIron_C") - 1.0).abs() < 1e-9);
+        assert!((height("Desc_IronIngot_C") / height("Desc_IronPlate_C") - 60.0 / 45.0).abs() < 1e-9);
+
+        let force = layout_flow_graph(&graph, GraphLayout::ForceDirected);
+        assert!(force.nodes.iter().all(|b| b.x >= 0.0 && b.y >= 0.0 && b.x.is_finite()));
+        assert_eq!(GraphLayout::from_id("sankey"), Some(GraphLayout::Sankey));
+    }
+}
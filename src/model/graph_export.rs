@@ -0,0 +1,146 @@
+//! Text exports of the production graph for documentation: Graphviz DOT and Mermaid flowcharts
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::model::{
+    flow_graph::{FlowGraph, FlowNode, FlowNodeKind},
+    item_label,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl GraphFormat {
+    pub const ALL: [GraphFormat; 2] = [GraphFormat::Dot, GraphFormat::Mermaid];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mermaid",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "Graphviz DOT",
+            GraphFormat::Mermaid => "Mermaid",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mmd",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.id() == id)
+    }
+}
+
+/// Item name, then the machines making it or, for inputs from outside, the rate
+fn node_lines(node: &FlowNode, item_names: &HashMap<String, String>, rate_unit: &str) -> [String; 2] {
+    let detail = match &node.machine {
+        Some(machine) => format!("{:.2} × {}", node.machines_needed, machine),
+        None => format!("{:.2} {}", node.rate, rate_unit),
+    };
+    [item_label(item_names, &node.item), detail]
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// The graph as text in a format; rates are labelled with the dataset's rate unit, e.g. items/min
+pub fn export_flow_graph(
+    graph: &FlowGraph,
+    format: GraphFormat,
+    item_names: &HashMap<String, String>,
+    rate_unit: &str,
+) -> String {
+    let mut out = String::new();
+    match format {
+        GraphFormat::Dot => {
+            out.push_str("digraph plan {\n    rankdir=LR;\n    node [shape=box, style=rounded];\n");
+            for n in graph.node_indices() {
+                let [name, detail] = node_lines(&graph[n], item_names, rate_unit);
+                let shape = match graph[n].kind {
+                    FlowNodeKind::Recipe => "",
+                    FlowNodeKind::RawResource => ", shape=ellipse",
+                    FlowNodeKind::Supplied => ", shape=ellipse, style=dashed",
+                    FlowNodeKind::Unproducible => ", color=red",
+                };
+                let _ = writeln!(out, "    n{} [label=\"{}\\n{}\"{}];", n.index(), dot_escape(&name), dot_escape(&detail), shape);
+            }
+            for e in graph.edge_indices() {
+                let (from, to) = graph.edge_endpoints(e).expect("edge exists");
+                let _ = writeln!(out, "    n{} -> n{} [label=\"{:.2}\"];", from.index(), to.index(), graph[e].rate);
+            }
+            out.push_str("}\n");
+        }
+        GraphFormat::Mermaid => {
+            out.push_str("flowchart LR\n");
+            for n in graph.node_indices() {
+                let [name, detail] = node_lines(&graph[n], item_names, rate_unit);
+                let label = format!("{}<br/>{}", mermaid_escape(&name), mermaid_escape(&detail));
+                let _ = match graph[n].kind {
+                    FlowNodeKind::Recipe | FlowNodeKind::Unproducible => writeln!(out, "    n{}[\"{}\"]", n.index(), label),
+                    FlowNodeKind::RawResource | FlowNodeKind::Supplied => writeln!(out, "    n{}([\"{}\"])", n.index(), label),
+                };
+            }
+            for e in graph.edge_indices() {
+                let (from, to) = graph.edge_endpoints(e).expect("edge exists");
+                let _ = writeln!(out, "    n{} -->|\"{:.2}\"| n{}", from.index(), graph[e].rate, to.index());
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::flow_graph::FlowEdge;
+
+    #[test]
+    fn test_export_dot_and_mermaid() {
+        let mut graph = FlowGraph::new();
+        let ore = graph.add_node(FlowNode {
+            item: "Desc_OreIron_C".to_string(),
+            kind: FlowNodeKind::RawResource,
+            recipe_name: None,
+            machine: None,
+            machines_needed: 0.0,
+            rate: 30.0,
+        });
+        let ingot = graph.add_node(FlowNode {
+            item: "Desc_IronIngot_C".to_string(),
+            kind: FlowNodeKind::Recipe,
+            recipe_name: Some("Iron Ingot".to_string()),
+            machine: Some("Smelter".to_string()),
+            machines_needed: 1.0,
+            rate: 30.0,
+        });
+        graph.add_edge(ore, ingot, FlowEdge { item: "Desc_OreIron_C".to_string(), rate: 30.0 });
+        let names = HashMap::from([("Desc_IronIngot_C".to_string(), "Iron \"Ingot\"".to_string())]);
+
+        let dot = export_flow_graph(&graph, GraphFormat::Dot, &names, "items/min");
+        assert!(dot.contains("n0 [label=\"Desc_OreIron_C\\n30.00 items/min\", shape=ellipse];"), "{}", dot);
+        assert!(dot.contains("n1 [label=\"Iron \\\"Ingot\\\"\\n1.00 × Smelter\"];"), "{}", dot);
+        assert!(dot.contains("n0 -> n1 [label=\"30.00\"];"), "{}", dot);
+
+        let mermaid = export_flow_graph(&graph, GraphFormat::Mermaid, &names, "items/min");
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("n1[\"Iron #quot;Ingot#quot;<br/>1.00 × Smelter\"]"), "{}", mermaid);
+        assert!(mermaid.contains("n0 -->|\"30.00\"| n1"), "{}", mermaid);
+    }
+}
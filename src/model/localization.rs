@@ -0,0 +1,111 @@
+//! Localized labels for items, recipes and machines, keyed by their stable ids.
+//!
+//! Switching language only swaps labels; ids, enabled flags and plans are untouched.
+
+use std::collections::HashMap;
+
+use crate::model::recipe::Recipe;
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Locales the game ships a Docs file for, with their native names
+pub const SATISFACTORY_LOCALES: [(&str, &str); 14] = [
+    ("en-US", "English"),
+    ("de-DE", "Deutsch"),
+    ("es-ES", "Español"),
+    ("fr-FR", "Français"),
+    ("it-IT", "Italiano"),
+    ("hu-HU", "Magyar"),
+    ("pl-PL", "Polski"),
+    ("pt-BR", "Português (Brasil)"),
+    ("ru-RU", "Русский"),
+    ("tr-TR", "Türkçe"),
+    ("ja-JP", "日本語"),
+    ("ko-KR", "한국어"),
+    ("zh-Hans", "简体中文"),
+    ("zh-Hant", "繁體中文"),
+];
+
+/// Display names by class id for one language
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DatasetLabels {
+    pub names: HashMap<String, String>,
+}
+
+impl DatasetLabels {
+    /// Labels currently carried by the recipes and the item name map
+    pub fn from_recipes(recipes: &[Recipe], item_names: &HashMap<String, String>) -> Self {
+        let mut names = item_names.clone();
+        for recipe in recipes {
+            names.insert(recipe.id.clone(), recipe.name.clone());
+            names.insert(recipe.machine.id.clone(), recipe.machine.name.clone());
+        }
+        DatasetLabels { names }
+    }
+
+    /// Relabel recipes, machines and items in place; ids without a translation keep their current label
+    pub fn apply(&self, recipes: &mut [Recipe], item_names: &mut HashMap<String, String>) {
+        for recipe in recipes.iter_mut() {
+            if let Some(name) = self.names.get(&recipe.id) {
+                recipe.name = name.clone();
+            }
+            if let Some(name) = self.names.get(&recipe.machine.id) {
+                recipe.machine.name = name.clone();
+            }
+        }
+        for (id, label) in item_names.iter_mut() {
+            if let Some(name) = self.names.get(id) {
+                *label = name.clone();
+            }
+        }
+    }
+}
+
+/// File name of the Docs file for a locale
+pub fn satisfactory_docs_file(locale: &str) -> String {
+    format!("satisfactory_{}.json", locale)
+}
+
+/// Path of the Docs file for a locale in the source tree: the default language is bundled
+/// into the app, translations live in `assets/locales` and are served from `/locales`
+pub fn satisfactory_docs_path(locale: &str) -> String {
+    if locale == DEFAULT_LOCALE {
+        format!("assets/{}", satisfactory_docs_file(locale))
+    } else {
+        format!("assets/locales/{}", satisfactory_docs_file(locale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::test_recipe;
+
+    #[test]
+    fn test_switch_locale_and_back() {
+        let mut recipes = vec![test_recipe("Recipe_IronPlate_C", "Desc_IronIngot_C", "Desc_IronPlate_C")];
+        recipes[0].name = "Iron Plate".to_string();
+        let mut item_names = HashMap::from([
+            ("Desc_IronIngot_C".to_string(), "Iron Ingot".to_string()),
+            ("Desc_IronPlate_C".to_string(), "Iron Plate".to_string()),
+        ]);
+        let english = DatasetLabels::from_recipes(&recipes, &item_names);
+
+        // The German file lacks a label for the ingot
+        let german = DatasetLabels {
+            names: HashMap::from([
+                ("Recipe_IronPlate_C".to_string(), "Eisenplatte".to_string()),
+                ("Build_ConstructorMk1_C".to_string(), "Konstruktor".to_string()),
+                ("Desc_IronPlate_C".to_string(), "Eisenplatte".to_string()),
+            ]),
+        };
+        german.apply(&mut recipes, &mut item_names);
+        assert_eq!((recipes[0].name.as_str(), recipes[0].machine.name.as_str()), ("Eisenplatte", "Konstruktor"));
+        assert_eq!(item_names["Desc_IronPlate_C"], "Eisenplatte");
+        assert_eq!(item_names["Desc_IronIngot_C"], "Iron Ingot");
+        assert_eq!(recipes[0].id, "Recipe_IronPlate_C");
+
+        english.apply(&mut recipes, &mut item_names);
+        assert_eq!(DatasetLabels::from_recipes(&recipes, &item_names), english);
+    }
+}
@@ -0,0 +1,235 @@
+//! Sizing the trains, trucks and drones that carry an item flow between sites
+//!
+//! The vehicles below are Satisfactory's and are only defaults: the calculator lets every figure be
+//! changed, since round trips depend on the route and other games have other vehicles.
+
+/// What a unit of the flow takes up in a vehicle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cargo {
+    /// Solid items, this many per inventory slot
+    Stacks(u32),
+    /// Fluid, carried by volume
+    Fluid,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vehicle {
+    pub name: &'static str,
+    /// Inventory slots for solid items
+    pub slots: u32,
+    /// Fluid volume per vehicle; zero when it cannot carry fluids
+    pub fluid_capacity: f64,
+    /// MW drawn while travelling
+    pub power: f64,
+    /// What powers it, burned at `fuel_energy` MJ per item; `None` for vehicles on the grid
+    pub fuel: Option<&'static str>,
+    pub fuel_energy: f64,
+}
+
+pub const FREIGHT_CAR: Vehicle = Vehicle {
+    name: "Freight Car",
+    slots: 32,
+    fluid_capacity: 1600.0,
+    power: 0.0,
+    fuel: None,
+    fuel_energy: 0.0,
+};
+
+/// Draws grid power, up to this much while accelerating
+pub const LOCOMOTIVE: Vehicle = Vehicle {
+    name: "Electric Locomotive",
+    slots: 0,
+    fluid_capacity: 0.0,
+    power: 110.0,
+    fuel: None,
+    fuel_energy: 0.0,
+};
+
+pub const TRUCK: Vehicle = Vehicle {
+    name: "Truck",
+    slots: 48,
+    fluid_capacity: 0.0,
+    power: 75.0,
+    fuel: Some("Packaged Fuel"),
+    fuel_energy: 750.0,
+};
+
+pub const DRONE: Vehicle = Vehicle {
+    name: "Drone",
+    slots: 9,
+    fluid_capacity: 0.0,
+    power: 50.0,
+    fuel: Some("Battery"),
+    fuel_energy: 6000.0,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogisticsError {
+    /// The vehicle has no room for this cargo, e.g. fluids in a truck
+    CannotCarry { vehicle: &'static str },
+    /// A rate, time or size that must be positive is not
+    NotPositive { field: &'static str },
+    /// A rate or time is infinite or not a number
+    NotFinite { field: &'static str },
+}
+
+impl std::fmt::Display for LogisticsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogisticsError::CannotCarry { vehicle } => write!(f, "a {} cannot carry this item; package it first", vehicle),
+            LogisticsError::NotPositive { field } => write!(f, "{} must be greater than zero", field),
+            LogisticsError::NotFinite { field } => write!(f, "{} must be a finite number", field),
+        }
+    }
+}
+
+impl std::error::Error for LogisticsError {}
+
+/// One item flow between two sites
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemFlow {
+    /// Items, or volume for fluids, per minute
+    pub rate: f64,
+    pub cargo: Cargo,
+    /// Seconds from loading, to the other site and back to loading again
+    pub round_trip: f64,
+}
+
+impl ItemFlow {
+    fn check(&self) -> Result<(), LogisticsError> {
+        let positive = |value: f64, field| match value {
+            _ if !value.is_finite() => Err(LogisticsError::NotFinite { field }),
+            _ if value > 0.0 => Ok(()),
+            _ => Err(LogisticsError::NotPositive { field }),
+        };
+        positive(self.rate, "rate")?;
+        positive(self.round_trip, "round trip")?;
+        if self.cargo == Cargo::Stacks(0) {
+            return Err(LogisticsError::NotPositive { field: "stack size" });
+        }
+        Ok(())
+    }
+
+    /// What one trip has to move
+    pub fn load_per_trip(&self) -> f64 {
+        self.rate * self.round_trip / 60.0
+    }
+}
+
+/// What one vehicle holds of the cargo
+pub fn capacity(vehicle: &Vehicle, cargo: Cargo) -> Result<f64, LogisticsError> {
+    let capacity = match cargo {
+        Cargo::Stacks(size) => f64::from(vehicle.slots) * f64::from(size),
+        Cargo::Fluid => vehicle.fluid_capacity,
+    };
+    if capacity > 0.0 {
+        Ok(capacity)
+    } else {
+        Err(LogisticsError::CannotCarry { vehicle: vehicle.name })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainPlan {
+    pub trains: u32,
+    pub cars_per_train: u32,
+    /// Freight platforms at each station, one per car
+    pub platforms: u32,
+    /// Items, or volume, each platform loads or unloads per minute
+    pub platform_throughput: f64,
+    /// Share of the cars' capacity the flow uses
+    pub utilization: f64,
+    /// Grid power of the locomotives while they all travel
+    pub power: f64,
+}
+
+/// Trains for a flow, with at most `max_cars` freight cars each and one locomotive per train
+pub fn size_trains(flow: &ItemFlow, car: &Vehicle, locomotive: &Vehicle, max_cars: u32) -> Result<TrainPlan, LogisticsError> {
+    flow.check()?;
+    if max_cars == 0 {
+        return Err(LogisticsError::NotPositive { field: "cars per train" });
+    }
+    let per_car = capacity(car, flow.cargo)?;
+    let cars = (flow.load_per_trip() / per_car).ceil().max(1.0) as u32;
+    let trains = cars.div_ceil(max_cars);
+    let cars_per_train = cars.div_ceil(trains);
+    Ok(TrainPlan {
+        trains,
+        cars_per_train,
+        platforms: cars_per_train,
+        platform_throughput: flow.rate / f64::from(cars_per_train),
+        utilization: flow.load_per_trip() / (f64::from(trains) * f64::from(cars_per_train) * per_car),
+        power: f64::from(trains) * locomotive.power,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FleetPlan {
+    /// Vehicles on the route; drones also need a port each at the sending site
+    pub vehicles: u32,
+    /// Departures per minute across the fleet
+    pub trips_per_minute: f64,
+    pub utilization: f64,
+    /// Fuel items per minute while the whole fleet travels; zero for vehicles on the grid
+    pub fuel_per_minute: f64,
+}
+
+/// Trucks or drones for a flow, each making one round trip after the other
+pub fn size_fleet(flow: &ItemFlow, vehicle: &Vehicle) -> Result<FleetPlan, LogisticsError> {
+    flow.check()?;
+    let per_vehicle = capacity(vehicle, flow.cargo)?;
+    let trips_per_minute = flow.rate / per_vehicle;
+    let vehicles = (flow.load_per_trip() / per_vehicle).ceil().max(1.0) as u32;
+    let fuel_per_minute = match vehicle.fuel {
+        Some(_) if vehicle.fuel_energy > 0.0 => f64::from(vehicles) * vehicle.power * 60.0 / vehicle.fuel_energy,
+        _ => 0.0,
+    };
+    Ok(FleetPlan {
+        vehicles,
+        trips_per_minute,
+        utilization: flow.load_per_trip() / (f64::from(vehicles) * per_vehicle),
+        fuel_per_minute,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_trains_and_fleets() {
+        // 1200 plates/min of stack 200 on a 5 minute round trip: 6000 plates a trip, 0.94 of a car
+        let flow = ItemFlow { rate: 1200.0, cargo: Cargo::Stacks(200), round_trip: 300.0 };
+        let train = size_trains(&flow, &FREIGHT_CAR, &LOCOMOTIVE, 4).unwrap();
+        assert_eq!((train.trains, train.cars_per_train, train.platforms), (1, 1, 1));
+        assert!((train.utilization - 6000.0 / 6400.0).abs() < 1e-9);
+
+        // Ten cars' worth split over three trains of at most four cars
+        let heavy = ItemFlow { rate: 12_800.0, ..flow.clone() };
+        let train = size_trains(&heavy, &FREIGHT_CAR, &LOCOMOTIVE, 4).unwrap();
+        assert_eq!((train.trains, train.cars_per_train), (3, 4));
+        assert_eq!(train.platform_throughput, 3200.0);
+        assert_eq!(train.power, 330.0);
+
+        let trucks = size_fleet(&flow, &TRUCK).unwrap();
+        assert_eq!(trucks.vehicles, 1);
+        assert_eq!(trucks.trips_per_minute, 0.125);
+        assert_eq!(trucks.fuel_per_minute, 6.0);
+
+        let water = ItemFlow { rate: 600.0, cargo: Cargo::Fluid, round_trip: 120.0 };
+        assert_eq!(size_trains(&water, &FREIGHT_CAR, &LOCOMOTIVE, 4).unwrap().cars_per_train, 1);
+        assert_eq!(size_fleet(&water, &DRONE), Err(LogisticsError::CannotCarry { vehicle: "Drone" }));
+        assert_eq!(
+            size_fleet(&ItemFlow { round_trip: 0.0, ..flow }, &DRONE),
+            Err(LogisticsError::NotPositive { field: "round trip" })
+        );
+
+        // Out of range input from the editable figures is an error or a huge answer, never a panic
+        let endless = ItemFlow { rate: f64::INFINITY, ..water.clone() };
+        assert_eq!(size_fleet(&endless, &TRUCK), Err(LogisticsError::NotFinite { field: "rate" }));
+        let thimble = Vehicle { fluid_capacity: 1e-12, ..FREIGHT_CAR };
+        let train = size_trains(&water, &thimble, &LOCOMOTIVE, 4).unwrap();
+        assert_eq!(train.cars_per_train, 4);
+        assert!(train.utilization > 0.0);
+    }
+}
@@ -0,0 +1,295 @@
+//! Small dense simplex solver used by the planner.
+//!
+//! Plans have a few hundred recipes and items at most, so a dense two-phase
+//! tableau with Bland's rule is fast enough and keeps us free of native
+//! dependencies in the WASM build.
+
+const EPS: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    Le,
+    Ge,
+    Eq,
+}
+
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    /// Sparse coefficients as (variable index, coefficient)
+    pub coeffs: Vec<(usize, f64)>,
+    pub kind: ConstraintKind,
+    pub rhs: f64,
+}
+
+/// Minimize `objective · x` subject to `constraints`, with `x >= 0`
+#[derive(Debug, Clone, Default)]
+pub struct LinearProgram {
+    pub objective: Vec<f64>,
+    pub constraints: Vec<Constraint>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LpSolution {
+    pub objective: f64,
+    pub values: Vec<f64>,
+    /// Dual value per constraint: change of the optimal objective per unit increase of its rhs
+    pub duals: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LpError {
+    Infeasible,
+    Unbounded,
+}
+
+impl std::fmt::Display for LpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LpError::Infeasible => write!(f, "no plan satisfies all constraints"),
+            LpError::Unbounded => write!(f, "objective is unbounded"),
+        }
+    }
+}
+
+impl std::error::Error for LpError {}
+
+impl LinearProgram {
+    pub fn new(num_vars: usize) -> Self {
+        LinearProgram {
+            objective: vec![0.0; num_vars],
+            constraints: Vec::new(),
+        }
+    }
+
+    pub fn add_constraint(&mut self, coeffs: Vec<(usize, f64)>, kind: ConstraintKind, rhs: f64) -> usize {
+        self.constraints.push(Constraint { coeffs, kind, rhs });
+        self.constraints.len() - 1
+    }
+
+    pub fn solve(&self) -> Result<LpSolution, LpError> {
+        Tableau::build(self).solve(self)
+    }
+}
+
+struct Tableau {
+    rows: Vec<Vec<f64>>,
+    rhs: Vec<f64>,
+    basis: Vec<usize>,
+    num_vars: usize,
+    /// Columns at or after this index are artificial variables
+    first_artificial: usize,
+    /// Column that formed the initial identity for each row, used to read B^-1
+    identity_col: Vec<usize>,
+    /// -1.0 when the row was negated to make its rhs non-negative
+    row_sign: Vec<f64>,
+}
+
+impl Tableau {
+    fn build(lp: &LinearProgram) -> Self {
+        let m = lp.constraints.len();
+        let n = lp.objective.len();
+        let num_slack = lp.constraints.iter().filter(|c| c.kind != ConstraintKind::Eq).count();
+        let mut kinds = Vec::with_capacity(m);
+        let mut row_sign = Vec::with_capacity(m);
+        for c in &lp.constraints {
+            if c.rhs < 0.0 {
+                row_sign.push(-1.0);
+                kinds.push(match c.kind {
+                    ConstraintKind::Le => ConstraintKind::Ge,
+                    ConstraintKind::Ge => ConstraintKind::Le,
+                    ConstraintKind::Eq => ConstraintKind::Eq,
+                });
+            } else {
+                row_sign.push(1.0);
+                kinds.push(c.kind);
+            }
+        }
+        let num_artificial = kinds.iter().filter(|k| **k != ConstraintKind::Le).count();
+        let first_artificial = n + num_slack;
+        let width = first_artificial + num_artificial;
+        let mut rows = vec![vec![0.0; width]; m];
+        let mut rhs = vec![0.0; m];
+        let mut basis = vec![0; m];
+        let mut identity_col = vec![0; m];
+        let mut next_slack = n;
+        let mut next_artificial = first_artificial;
+        for (i, c) in lp.constraints.iter().enumerate() {
+            let sign = row_sign[i];
+            for &(j, a) in &c.coeffs {
+                rows[i][j] += sign * a;
+            }
+            rhs[i] = sign * c.rhs;
+            match kinds[i] {
+                ConstraintKind::Le => {
+                    rows[i][next_slack] = 1.0;
+                    basis[i] = next_slack;
+                    next_slack += 1;
+                }
+                ConstraintKind::Ge => {
+                    rows[i][next_slack] = -1.0;
+                    next_slack += 1;
+                    rows[i][next_artificial] = 1.0;
+                    basis[i] = next_artificial;
+                    next_artificial += 1;
+                }
+                ConstraintKind::Eq => {
+                    rows[i][next_artificial] = 1.0;
+                    basis[i] = next_artificial;
+                    next_artificial += 1;
+                }
+            }
+            identity_col[i] = basis[i];
+        }
+        Tableau { rows, rhs, basis, num_vars: n, first_artificial, identity_col, row_sign }
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        let p = self.rows[row][col];
+        for v in self.rows[row].iter_mut() {
+            *v /= p;
+        }
+        self.rhs[row] /= p;
+        let pivot_row = self.rows[row].clone();
+        let pivot_rhs = self.rhs[row];
+        for i in 0..self.rows.len() {
+            if i == row {
+                continue;
+            }
+            let factor = self.rows[i][col];
+            if factor.abs() > EPS {
+                for (v, pv) in self.rows[i].iter_mut().zip(&pivot_row) {
+                    *v -= factor * pv;
+                }
+                self.rhs[i] -= factor * pivot_rhs;
+            }
+        }
+        self.basis[row] = col;
+    }
+
+    /// Run the simplex method for the given column costs, never entering columns at or after `limit`
+    fn optimize(&mut self, costs: &[f64], limit: usize) -> Result<(), LpError> {
+        loop {
+            // Bland's rule: lowest-index column with negative reduced cost
+            let entering = (0..limit).find(|&j| {
+                if self.basis.contains(&j) {
+                    return false;
+                }
+                let reduced = costs[j]
+                    - self
+                        .basis
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &b)| costs[b] * self.rows[i][j])
+                        .sum::<f64>();
+                reduced < -EPS
+            });
+            let Some(col) = entering else {
+                return Ok(());
+            };
+            let mut leaving: Option<(usize, f64)> = None;
+            for i in 0..self.rows.len() {
+                let a = self.rows[i][col];
+                if a > EPS {
+                    let ratio = self.rhs[i] / a;
+                    let better = match leaving {
+                        None => true,
+                        Some((l, best)) => {
+                            ratio < best - EPS || (ratio < best + EPS && self.basis[i] < self.basis[l])
+                        }
+                    };
+                    if better {
+                        leaving = Some((i, ratio));
+                    }
+                }
+            }
+            match leaving {
+                Some((row, _)) => self.pivot(row, col),
+                None => return Err(LpError::Unbounded),
+            }
+        }
+    }
+
+    fn solve(mut self, lp: &LinearProgram) -> Result<LpSolution, LpError> {
+        let width = self.rows.first().map(|r| r.len()).unwrap_or(self.num_vars);
+        // Phase 1: minimize the sum of artificial variables
+        let mut phase1 = vec![0.0; width];
+        for c in phase1.iter_mut().skip(self.first_artificial) {
+            *c = 1.0;
+        }
+        self.optimize(&phase1, width)?;
+        let infeasibility: f64 = self
+            .basis
+            .iter()
+            .zip(&self.rhs)
+            .filter(|(b, _)| **b >= self.first_artificial)
+            .map(|(_, v)| *v)
+            .sum();
+        if infeasibility > 1e-7 {
+            return Err(LpError::Infeasible);
+        }
+        // Drive remaining zero-level artificials out of the basis where possible
+        for i in 0..self.rows.len() {
+            if self.basis[i] < self.first_artificial {
+                continue;
+            }
+            if let Some(col) = (0..self.first_artificial).find(|&j| self.rows[i][j].abs() > EPS) {
+                self.pivot(i, col);
+            }
+        }
+        // Phase 2: the real objective, artificials may no longer enter
+        let mut phase2 = vec![0.0; width];
+        phase2[..self.num_vars].copy_from_slice(&lp.objective);
+        self.optimize(&phase2, self.first_artificial)?;
+
+        let mut values = vec![0.0; self.num_vars];
+        for (i, &b) in self.basis.iter().enumerate() {
+            if b < self.num_vars {
+                values[b] = self.rhs[i];
+            }
+        }
+        let objective = lp.objective.iter().zip(&values).map(|(c, x)| c * x).sum();
+        let duals = (0..self.rows.len())
+            .map(|k| {
+                let col = self.identity_col[k];
+                let y: f64 = self
+                    .basis
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &b)| phase2[b] * self.rows[i][col])
+                    .sum();
+                y * self.row_sign[k]
+            })
+            .collect();
+        Ok(LpSolution { objective, values, duals })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_with_duals() {
+        // min 2a + 3b  s.t.  a + b >= 10,  a <= 4
+        let mut lp = LinearProgram::new(2);
+        lp.objective = vec![2.0, 3.0];
+        let demand = lp.add_constraint(vec![(0, 1.0), (1, 1.0)], ConstraintKind::Ge, 10.0);
+        let cap = lp.add_constraint(vec![(0, 1.0)], ConstraintKind::Le, 4.0);
+        let sol = lp.solve().expect("feasible");
+        assert!((sol.objective - 26.0).abs() < 1e-6);
+        assert!((sol.values[0] - 4.0).abs() < 1e-6);
+        assert!((sol.values[1] - 6.0).abs() < 1e-6);
+        // One more unit of demand is met by b, one more unit of cap saves 3 - 2
+        assert!((sol.duals[demand] - 3.0).abs() < 1e-6);
+        assert!((sol.duals[cap] + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_infeasible() {
+        let mut lp = LinearProgram::new(1);
+        lp.objective = vec![1.0];
+        lp.add_constraint(vec![(0, 1.0)], ConstraintKind::Ge, 5.0);
+        lp.add_constraint(vec![(0, 1.0)], ConstraintKind::Le, 2.0);
+        assert_eq!(lp.solve().unwrap_err(), LpError::Infeasible);
+    }
+}
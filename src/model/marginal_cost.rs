@@ -0,0 +1,173 @@
+//! Marginal costs (dual values) of items and raw-resource caps for the optimal plan.
+//!
+//! The plan is solved as a linear program over the enabled recipes: minimize total
+//! weight points (WP) of extracted raw resources, subject to meeting every requested
+//! output and staying within each raw resource's availability. The duals of that program tell how much
+//! WP one more item/min costs, and how much WP one more unit/min of a resource saves.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::model::{
+    lp::{ConstraintKind, LinearProgram, LpError},
+    recipe::Recipe,
+    resource_availability, resource_weight_points,
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecipeUsage {
+    pub recipe_name: String,
+    pub machine: String,
+    pub machines_needed: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ItemMarginalCost {
+    pub item: String,
+    /// WP added to the optimal plan per extra item/min requested
+    pub marginal_wp: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResourceShadowPrice {
+    pub resource: String,
+    pub used: f64,
+    pub available: f64,
+    /// WP saved per extra unit/min of availability (zero unless the cap binds)
+    pub shadow_price: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PlanOptimum {
+    pub total_wp: f64,
+    pub recipes: Vec<RecipeUsage>,
+    pub item_costs: Vec<ItemMarginalCost>,
+    pub resource_prices: Vec<ResourceShadowPrice>,
+}
+
+/// Solve the requested outputs over the enabled recipes and return the optimal plan with its duals
+pub fn compute_plan_optimum(
+    recipes: &[Recipe],
+    enabled: &HashSet<String>,
+    outputs: &[(String, f64)],
+    raw_resources: &HashSet<String>,
+) -> Result<PlanOptimum, LpError> {
+    let recipes: Vec<&Recipe> = recipes.iter().filter(|r| enabled.contains(&r.id)).collect();
+    let weights = resource_weight_points(raw_resources);
+
+    let mut items: BTreeSet<String> = BTreeSet::new();
+    for recipe in &recipes {
+        items.extend(recipe.inputs.iter().map(|i| i.item.clone()));
+        items.extend(recipe.outputs.iter().map(|o| o.item.clone()));
+    }
+    items.extend(outputs.iter().map(|(name, _)| name.clone()));
+    let items: Vec<String> = items.into_iter().collect();
+
+    // Variables: crafts/min per enabled recipe, then extraction/min per raw resource
+    let resources: Vec<(&str, f64)> = weights
+        .iter()
+        .map(|(name, _)| (name.as_str(), resource_availability(name)))
+        .collect();
+    let num_vars = recipes.len() + resources.len();
+    let mut lp = LinearProgram::new(num_vars);
+    for (k, (_, wp)) in weights.iter().enumerate() {
+        lp.objective[recipes.len() + k] = *wp;
+    }
+
+    let demand: HashMap<&str, f64> = outputs.iter().map(|(n, r)| (n.as_str(), *r)).collect();
+    let mut item_rows = Vec::with_capacity(items.len());
+    for item in &items {
+        let mut coeffs = Vec::new();
+        for (j, recipe) in recipes.iter().enumerate() {
+            let produced: f64 = recipe.outputs.iter().filter(|o| &o.item == item).map(|o| o.quantity as f64).sum();
+            let consumed: f64 = recipe.inputs.iter().filter(|i| &i.item == item).map(|i| i.quantity as f64).sum();
+            if produced != consumed {
+                coeffs.push((j, produced - consumed));
+            }
+        }
+        if let Some(k) = resources.iter().position(|(name, _)| name == item) {
+            coeffs.push((recipes.len() + k, 1.0));
+        }
+        let rhs = demand.get(item.as_str()).cloned().unwrap_or(0.0);
+        item_rows.push(lp.add_constraint(coeffs, ConstraintKind::Ge, rhs));
+    }
+    let mut cap_rows = Vec::with_capacity(resources.len());
+    for (k, (_, avail)) in resources.iter().enumerate() {
+        if avail.is_finite() {
+            cap_rows.push(Some(lp.add_constraint(vec![(recipes.len() + k, 1.0)], ConstraintKind::Le, *avail)));
+        } else {
+            cap_rows.push(None);
+        }
+    }
+
+    let solution = lp.solve()?;
+    let recipe_usage = recipes
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| solution.values[*j] > 1e-9)
+        .map(|(j, recipe)| RecipeUsage {
+            recipe_name: recipe.name.clone(),
+            machine: recipe.machine.name.clone(),
+            machines_needed: solution.values[j] * recipe.time as f64 / 60_000.0,
+        })
+        .collect();
+    let item_costs = items
+        .iter()
+        .zip(&item_rows)
+        .map(|(item, &row)| ItemMarginalCost {
+            item: item.clone(),
+            marginal_wp: solution.duals[row],
+        })
+        .collect();
+    let resource_prices = resources
+        .iter()
+        .enumerate()
+        .map(|(k, (name, avail))| ResourceShadowPrice {
+            resource: name.to_string(),
+            used: solution.values[recipes.len() + k],
+            available: *avail,
+            shadow_price: cap_rows[k].map(|row| (-solution.duals[row]).max(0.0)).unwrap_or(0.0),
+        })
+        .collect();
+    Ok(PlanOptimum {
+        total_wp: solution.objective,
+        recipes: recipe_usage,
+        item_costs,
+        resource_prices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::test_recipe;
+
+    const IRON: &str = "Desc_OreIron_C";
+    const URANIUM: &str = "Desc_OreUranium_C";
+    const PLATE: &str = "Desc_IronPlate_C";
+
+    #[test]
+    fn test_plan_optimum_with_binding_cap() {
+        // Plates from iron at 1 WP, or from uranium at 92100 / 2100 WP for the 900 beyond iron's cap
+        let recipes = vec![test_recipe("Recipe_IronPlate_C", IRON, PLATE), test_recipe("Recipe_UraniumPlate_C", URANIUM, PLATE)];
+        let enabled = recipes.iter().map(|r| r.id.clone()).collect();
+        let raw_resources = HashSet::from([IRON.to_string(), URANIUM.to_string()]);
+        let optimum = compute_plan_optimum(&recipes, &enabled, &[(PLATE.to_string(), 93_000.0)], &raw_resources).unwrap();
+        let uranium_wp = 92100.0 / 2100.0;
+
+        assert!((optimum.total_wp - (92100.0 + 900.0 * uranium_wp)).abs() < 1e-6);
+        // 2 s crafts: a machine makes 30 crafts/min
+        let machines: Vec<(&str, f64)> = optimum.recipes.iter().map(|r| (r.recipe_name.as_str(), r.machines_needed)).collect();
+        assert_eq!(machines.len(), 2);
+        assert!(machines.iter().any(|&(name, m)| name == "Recipe_IronPlate_C" && (m - 92100.0 / 30.0).abs() < 1e-6));
+        assert!(machines.iter().any(|&(name, m)| name == "Recipe_UraniumPlate_C" && (m - 900.0 / 30.0).abs() < 1e-6));
+
+        // One more plate comes from uranium; one more iron replaces a uranium plate
+        let plate = optimum.item_costs.iter().find(|c| c.item == PLATE).unwrap();
+        assert!((plate.marginal_wp - uranium_wp).abs() < 1e-6);
+        let price = |resource: &str| optimum.resource_prices.iter().find(|p| p.resource == resource).unwrap();
+        assert!((price(IRON).used - 92100.0).abs() < 1e-6);
+        assert!((price(IRON).shadow_price - (uranium_wp - 1.0)).abs() < 1e-6);
+        assert!((price(URANIUM).used - 900.0).abs() < 1e-6);
+        assert_eq!(price(URANIUM).shadow_price, 0.0);
+    }
+}
@@ -1,55 +1,165 @@
 pub mod recipe;
+pub mod lp;
+pub mod marginal_cost;
+pub mod pareto;
+pub mod diagnostics;
+pub mod localization;
+pub mod dataset;
+pub mod custom;
+pub mod plan;
+pub mod share;
+pub mod plan_file;
+pub mod breakdown;
+pub mod project;
+pub mod logistics;
+pub mod flow_graph;
+pub mod graph_export;
+pub mod report;
 
-use std::{io::{self, Write}, vec};
+use std::vec;
 
 use reactive_stores::Store;
 
-use crate::model::recipe::Recipe;
+use crate::model::{custom::CustomRecipes, dataset::{CompiledDataset, DatasetSource, DatasetUnits}, recipe::Recipe};
 
 #[derive(Default, Store, Clone)]
 pub struct AppStore {
-    #[store(key: String = |recipe| recipe.name.clone())]
+    #[store(key: String = |recipe| recipe.id.clone())]
     pub recipes: Vec<Recipe>,
+    /// Localized display name by item id
+    pub item_names: std::collections::HashMap<String, String>,
+    /// Item ids of the dataset's raw resources; the one list every calculation uses
+    pub raw_resources: std::collections::HashSet<String>,
+    /// Items per inventory slot by item id, for sizing transport; fluids are absent
+    pub stack_sizes: std::collections::HashMap<String, u32>,
+    /// Locale of the current labels, e.g. en-US
+    pub locale: String,
+    /// Problems found while loading the dataset, shown to the user
+    pub load_warnings: Vec<String>,
+    /// Where the current recipes came from
+    pub dataset: DatasetSource,
+    /// Id of the game adapter the dataset belongs to, e.g. satisfactory
+    pub game: String,
+    /// Dataset version within the game
+    pub dataset_version: String,
+    /// Units the tabs label quantities with
+    pub units: DatasetUnits,
+    /// User-made recipes currently merged into `recipes`
+    pub custom: CustomRecipes,
 }
 
-/// Raw resource availability and WP assignment (copied from analysis_tab.rs)
-pub const RESOURCE_AVAIL: [(&str, f64); 15] = [
-    ("Bauxite", 12300.0),
-    ("Caterium Ore", 15000.0),
-    ("Coal", 42300.0),
-    ("Copper Ore", 36900.0),
-    ("Crude Oil", 12600.0),
-    ("Iron Ore", 92100.0),
-    ("Limestone", 69900.0),
-    ("Nitrogen Gas", 12000.0),
-    ("Raw Quartz", 13500.0),
-    ("Sulfur", 10800.0),
-    ("Uranium", 2100.0),
-    ("SAM", 10200.0),
-    ("Water", f64::INFINITY),
-    ("Excited Photonic Matter", f64::INFINITY),
-    ("Dark Matter Residue", f64::INFINITY),
+impl AppStore {
+    /// Swap in a freshly loaded dataset, keeping the enabled flag of recipes that exist in both
+    pub fn replace_dataset(&mut self, dataset: CompiledDataset, source: DatasetSource) {
+        let enabled: std::collections::HashMap<String, bool> =
+            self.recipes.iter().map(|r| (r.id.clone(), r.enabled)).collect();
+        let mut recipes = dataset.recipes;
+        for recipe in recipes.iter_mut() {
+            if let Some(flag) = enabled.get(&recipe.id) {
+                recipe.enabled = *flag;
+            }
+        }
+        self.recipes = recipes;
+        self.item_names = dataset.items.into_iter().map(|i| (i.id, i.name)).collect();
+        self.raw_resources = dataset.raw_resources.into_iter().collect();
+        self.stack_sizes = dataset.stack_sizes.into_iter().collect();
+        self.load_warnings = dataset.warnings;
+        self.game = dataset.game;
+        self.dataset_version = dataset.version;
+        self.units = dataset.units;
+        // The new recipe list has none of the custom recipes merged in
+        self.custom = CustomRecipes::default();
+        // Labels come from the file itself, which is read as the base language
+        self.locale = localization::DEFAULT_LOCALE.to_string();
+        self.dataset = source;
+    }
+
+    /// Replace the merged custom recipes and items with another set
+    pub fn set_custom_recipes(&mut self, custom: CustomRecipes) {
+        let old = std::mem::take(&mut self.custom);
+        let mut enabled = std::collections::HashMap::new();
+        self.recipes.retain(|r| {
+            let is_old = old.recipes.iter().any(|c| c.id == r.id);
+            if is_old {
+                enabled.insert(r.id.clone(), r.enabled);
+            }
+            !is_old
+        });
+        for item in &old.items {
+            self.item_names.remove(&item.id);
+        }
+        // Recipes that stay keep the enabled flag the user set in the recipe list
+        self.recipes.extend(custom.recipes.iter().cloned().map(|mut r| {
+            r.enabled = enabled.get(&r.id).copied().unwrap_or(r.enabled);
+            r
+        }));
+        self.item_names.extend(custom.items.iter().map(|i| (i.id.clone(), i.name.clone())));
+        self.custom = custom;
+    }
+}
+
+/// Map-wide availability (items/min) of the base game's raw resources, keyed by item id.
+/// Which items are raw comes from the dataset; this only sets their WP.
+pub const RESOURCE_AVAIL: [(&str, f64); 13] = [
+    ("Desc_OreBauxite_C", 12300.0),    // Bauxite
+    ("Desc_OreGold_C", 15000.0),       // Caterium Ore
+    ("Desc_Coal_C", 42300.0),          // Coal
+    ("Desc_OreCopper_C", 36900.0),     // Copper Ore
+    ("Desc_LiquidOil_C", 12600.0),     // Crude Oil
+    ("Desc_OreIron_C", 92100.0),       // Iron Ore
+    ("Desc_Stone_C", 69900.0),         // Limestone
+    ("Desc_NitrogenGas_C", 12000.0),   // Nitrogen Gas
+    ("Desc_RawQuartz_C", 13500.0),     // Raw Quartz
+    ("Desc_Sulfur_C", 10800.0),        // Sulfur
+    ("Desc_OreUranium_C", 2100.0),     // Uranium
+    ("Desc_SAM_C", 10200.0),           // SAM
+    ("Desc_Water_C", f64::INFINITY),   // Water
 ];
 
-pub fn resource_weight_points() -> Vec<(String, f64)> {
+/// Localized label for an item id, falling back to the id itself
+pub fn item_label(item_names: &std::collections::HashMap<String, String>, id: &str) -> String {
+    item_names.get(id).cloned().unwrap_or_else(|| id.to_string())
+}
+
+/// Availability of a raw resource; resources the table does not know, e.g. from patches or mods, are unlimited
+pub fn resource_availability(id: &str) -> f64 {
+    RESOURCE_AVAIL
+        .iter()
+        .find(|(name, _)| *name == id)
+        .map(|(_, avail)| *avail)
+        .unwrap_or(f64::INFINITY)
+}
+
+/// WP of each raw resource of the dataset, sorted by id
+pub fn resource_weight_points(raw_resources: &std::collections::HashSet<String>) -> Vec<(String, f64)> {
     let most_common = RESOURCE_AVAIL.iter()
         .filter(|(_, avail)| avail.is_finite())
         .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
         .unwrap().1;
 
-    RESOURCE_AVAIL.iter()
-        .map(|(name, avail)| (name.to_string(), most_common / *avail))
+    let mut weights: Vec<(String, f64)> = raw_resources.iter()
+        .map(|name| (name.clone(), most_common / resource_availability(name)))
+        .collect();
+    weights.sort_by(|a, b| a.0.cmp(&b.0));
+    weights
+}
+
+/// Machine power (MW) by machine id, as carried on the recipes themselves
+pub fn machine_power_map_from_recipes(recipes: &[Recipe]) -> std::collections::HashMap<String, f64> {
+    recipes
+        .iter()
+        .map(|r| (r.machine.id.clone(), r.machine.power))
         .collect()
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ItemAnalysis {
     pub wp: f64,
     pub power: f64,
     pub recipes_analysis: Vec<ItemRecipeAnalysis>,
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ItemRecipeAnalysis {
     pub recipe_name: String, // Added recipe name
     pub inputs: Vec<ItemInputAnalysis>,
@@ -59,7 +169,7 @@ pub struct ItemRecipeAnalysis {
     pub wp_flow: f64,
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ItemInputAnalysis {
     pub item: String,
     pub quantity: f64,
@@ -71,24 +181,15 @@ pub struct ItemInputAnalysis {
 pub fn compute_item_analysis(
     recipes: &[Recipe],
     machine_power_map: &std::collections::HashMap<String, f64>,
+    raw_resources: &std::collections::HashSet<String>,
 ) -> std::collections::HashMap<String, ItemAnalysis> {
-    use std::collections::{HashMap, HashSet};
-    let resource_weights = resource_weight_points();
-    let raw_resource_names: HashSet<String> = RESOURCE_AVAIL.iter().map(|(name, _)| name.to_string()).collect();
-    // Initialize for all raw resources
+    use std::collections::HashMap;
+    let resource_weights = resource_weight_points(raw_resources);
+    // Initialize for all raw resources; items that are neither raw nor produced stay unknown
     let mut item_analysis: HashMap<String, ItemAnalysis> = HashMap::new();
     for (name, wp) in &resource_weights {
         item_analysis.insert(name.clone(), ItemAnalysis { wp: *wp, power: 0.0, recipes_analysis: vec![] });
     }
-    // Also, for any item that is only an input and never an output, assign WP/power (raw)
-    let all_outputs: HashSet<_> = recipes.iter().flat_map(|r| r.outputs.iter().map(|o| o.item.clone())).collect();
-    let all_inputs: HashSet<_> = recipes.iter().flat_map(|r| r.inputs.iter().map(|i| i.item.clone())).collect();
-    for item in all_inputs.difference(&all_outputs) {
-        let wp = resource_weights.iter().find_map(|(name, wp)| {
-            if item == name { Some(*wp) } else { None }
-        }).unwrap_or(f64::INFINITY);
-        item_analysis.insert(item.clone(), ItemAnalysis { wp, power: 0.0, recipes_analysis: vec![] });
-    }
     // Fixed-point iteration: propagate values through recipes until convergence
     let mut changed = true;
     let threshold = 1e-6;
@@ -96,7 +197,7 @@ pub fn compute_item_analysis(
         changed = false;
         for recipe in recipes {
             for output in &recipe.outputs {
-                if raw_resource_names.contains(&output.item) {
+                if raw_resources.contains(&output.item) {
                     continue;
                 }
                 let out_qty = output.quantity as f64;
@@ -113,7 +214,7 @@ pub fn compute_item_analysis(
                     }
                 }
                 // Add direct machine power for this recipe
-                let machine_power = machine_power_map.get(&recipe.machine.name).cloned().unwrap_or(0.0); // MW
+                let machine_power = machine_power_map.get(&recipe.machine.id).cloned().unwrap_or(0.0); // MW
                 let time_s = recipe.time as f64 / 1000.0;
                 let machine_mj = machine_power * time_s / out_qty; // MW * s = MJ
                 if all_known {
@@ -125,15 +226,12 @@ pub fn compute_item_analysis(
                     let entry = item_analysis.entry(output.item.clone()).or_insert(ItemAnalysis { wp: f64::INFINITY, power: f64::INFINITY, recipes_analysis: vec![] });
                     if total.wp + threshold < entry.wp || total.power + threshold < entry.power {
                         if total.wp + threshold < entry.wp {
-                            println!("Updating {}: old WP = {:.4}, new WP = {:.4}", output.item, entry.wp, total.wp);
                             entry.wp = total.wp;
                         }
                         if total.power + threshold < entry.power {
-                            println!("Updating {}: old Power = {:.2}, new Power = {:.2}", output.item, entry.power, total.power);
                             entry.power = total.power;
                         }
                         changed = true;
-                        io::stdout().flush().unwrap(); // Ensure output is flushed
                     }
                 }
             }
@@ -142,7 +240,7 @@ pub fn compute_item_analysis(
     // Now, fill in recipes_analysis for each item
     for recipe in recipes {
         for output in &recipe.outputs {
-            if raw_resource_names.contains(&output.item) {
+            if raw_resources.contains(&output.item) {
                 continue;
             }
             let out_qty = output.quantity as f64;
@@ -166,7 +264,7 @@ pub fn compute_item_analysis(
                     break;
                 }
             }
-            let machine_power = machine_power_map.get(&recipe.machine.name).cloned().unwrap_or(0.0); // MW
+            let machine_power = machine_power_map.get(&recipe.machine.id).cloned().unwrap_or(0.0); // MW
             let time_s = recipe.time as f64 / 1000.0;
             let machine_mj = machine_power * time_s / out_qty;
             if all_known {
@@ -184,7 +282,7 @@ pub fn compute_item_analysis(
             }
         }
     }
-    // Remove all items with infinite WP or power
+    // Remove all items with infinite WP or power; `diagnostics::compute_unreachable_items` explains why
     item_analysis.retain(|_, ia| ia.wp != f64::INFINITY && ia.power != f64::INFINITY);
     item_analysis
 }
@@ -198,10 +296,11 @@ mod tests {
         use crate::adapters::satisfactory_adapter::build_machine_power_map_from_assets;
         let path = "assets/satisfactory_en-US.json";
         let json_str = std::fs::read_to_string(path).expect("Failed to read JSON file");
-        let recipes = load_satisfactory_recipes_from_json(&json_str).expect("Failed to load recipes");
-        let assets: Vec<crate::adapters::satisfactory_asset::SatisfactoryAsset> = serde_json::from_str(&json_str).expect("Failed to parse JSON");
+        let loaded = load_satisfactory_recipes_from_json(&json_str).expect("Failed to load recipes");
+        let assets = crate::adapters::satisfactory_asset::parse_satisfactory_assets(&json_str).expect("Failed to parse JSON");
         let machine_power_map = build_machine_power_map_from_assets(&assets);
-        let item_analysis = compute_item_analysis(&recipes, &machine_power_map);
+        let raw_resources = loaded.raw_resources.iter().cloned().collect();
+        let item_analysis = compute_item_analysis(&loaded.recipes, &machine_power_map, &raw_resources);
         let mut items: Vec<_> = item_analysis.iter().collect();
         items.sort_by(|a, b| a.0.cmp(b.0));
         println!("{:<32} | {:>10} | {:>15}", "Item", "WP", "Power (J)");
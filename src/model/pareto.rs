@@ -0,0 +1,226 @@
+//! Pareto frontier of recipe-chain choices across WP, power and building count.
+//!
+//! `compute_item_analysis` keeps one minimum per metric, and those minima can come
+//! from different recipes. Here every item keeps the set of non-dominated chains
+//! instead, so the trade-off between the metrics stays visible.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::model::{recipe::Recipe, resource_weight_points};
+
+/// Upper bound of points kept per item, so chains with many inputs stay tractable
+const MAX_FRONTIER_POINTS: usize = 12;
+const MAX_ITERATIONS: usize = 64;
+const EPS: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ParetoPoint {
+    /// Weight points per item
+    pub wp: f64,
+    /// Energy per item in MJ
+    pub power: f64,
+    /// Buildings needed per item/min
+    pub buildings: f64,
+    /// Recipe chosen for each item of the chain, starting with the item itself
+    pub chain: Vec<(String, String)>,
+}
+
+impl ParetoPoint {
+    fn dominates(&self, other: &ParetoPoint) -> bool {
+        self.wp <= other.wp + EPS
+            && self.power <= other.power + EPS
+            && self.buildings <= other.buildings + EPS
+            && (self.wp + EPS < other.wp || self.power + EPS < other.power || self.buildings + EPS < other.buildings)
+    }
+
+    fn same_cost(&self, other: &ParetoPoint) -> bool {
+        (self.wp - other.wp).abs() <= EPS
+            && (self.power - other.power).abs() <= EPS
+            && (self.buildings - other.buildings).abs() <= EPS
+    }
+
+    pub fn recipe_name(&self) -> Option<&str> {
+        self.chain.first().map(|(_, recipe)| recipe.as_str())
+    }
+}
+
+/// Keep only non-dominated points, at most `MAX_FRONTIER_POINTS` of them
+fn prune(points: Vec<ParetoPoint>) -> Vec<ParetoPoint> {
+    let mut kept: Vec<ParetoPoint> = Vec::new();
+    for p in points {
+        if kept.iter().any(|k| k.dominates(&p) || k.same_cost(&p)) {
+            continue;
+        }
+        kept.retain(|k| !p.dominates(k));
+        kept.push(p);
+    }
+    kept.sort_by(|a, b| a.wp.partial_cmp(&b.wp).unwrap_or(std::cmp::Ordering::Equal));
+    if kept.len() <= MAX_FRONTIER_POINTS {
+        return kept;
+    }
+    // Always keep the minimum of each metric, then spread the rest evenly along WP
+    let mut chosen: HashSet<usize> = HashSet::new();
+    chosen.insert(0);
+    for metric in [|p: &ParetoPoint| p.power, |p: &ParetoPoint| p.buildings] {
+        let best = (0..kept.len())
+            .min_by(|&a, &b| metric(&kept[a]).partial_cmp(&metric(&kept[b])).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap();
+        chosen.insert(best);
+    }
+    let step = kept.len() as f64 / MAX_FRONTIER_POINTS as f64;
+    let mut i = 0.0;
+    while chosen.len() < MAX_FRONTIER_POINTS && (i as usize) < kept.len() {
+        chosen.insert(i as usize);
+        i += step;
+    }
+    kept.into_iter()
+        .enumerate()
+        .filter(|(i, _)| chosen.contains(i))
+        .map(|(_, p)| p)
+        .collect()
+}
+
+/// Add an input's chain to a candidate's, or `None` when the two make an item with different
+/// recipes, since the candidate's costs would then not be those of the one chain it shows
+fn merge_chain(chain: &[(String, String)], other: &[(String, String)]) -> Option<Vec<(String, String)>> {
+    let mut merged = chain.to_vec();
+    for (item, recipe) in other {
+        match merged.iter().find(|(i, _)| i == item) {
+            Some((_, existing)) if existing != recipe => return None,
+            Some(_) => {}
+            None => merged.push((item.clone(), recipe.clone())),
+        }
+    }
+    Some(merged)
+}
+
+/// Compute the non-dominated (WP, power, buildings) chains for every producible item
+pub fn compute_pareto_frontiers(
+    recipes: &[Recipe],
+    machine_power_map: &HashMap<String, f64>,
+    raw_resources: &HashSet<String>,
+) -> HashMap<String, Vec<ParetoPoint>> {
+    let mut frontiers: HashMap<String, Vec<ParetoPoint>> = HashMap::new();
+    for (name, wp) in resource_weight_points(raw_resources) {
+        frontiers.insert(name, vec![ParetoPoint { wp, power: 0.0, buildings: 0.0, chain: vec![] }]);
+    }
+    let mut changed = true;
+    let mut iterations = 0;
+    while changed && iterations < MAX_ITERATIONS {
+        changed = false;
+        iterations += 1;
+        for recipe in recipes {
+            for output in &recipe.outputs {
+                if raw_resources.contains(&output.item) {
+                    continue;
+                }
+                let out_qty = output.quantity as f64;
+                let time_s = recipe.time as f64 / 1000.0;
+                let machine_power = machine_power_map.get(&recipe.machine.id).cloned().unwrap_or(0.0);
+                let mut candidates = vec![ParetoPoint {
+                    wp: 0.0,
+                    power: machine_power * time_s / out_qty,
+                    buildings: time_s / 60.0 / out_qty,
+                    chain: vec![(output.item.clone(), recipe.name.clone())],
+                }];
+                let mut all_known = true;
+                for input in &recipe.inputs {
+                    let Some(input_frontier) = frontiers.get(&input.item) else {
+                        all_known = false;
+                        break;
+                    };
+                    let scale = input.quantity as f64 / out_qty;
+                    let mut combined = Vec::with_capacity(candidates.len() * input_frontier.len());
+                    for base in &candidates {
+                        for p in input_frontier {
+                            let Some(chain) = merge_chain(&base.chain, &p.chain) else {
+                                continue;
+                            };
+                            combined.push(ParetoPoint {
+                                wp: base.wp + p.wp * scale,
+                                power: base.power + p.power * scale,
+                                buildings: base.buildings + p.buildings * scale,
+                                chain,
+                            });
+                        }
+                    }
+                    candidates = prune(combined);
+                }
+                if !all_known {
+                    continue;
+                }
+                let entry = frontiers.entry(output.item.clone()).or_default();
+                let improves = candidates
+                    .iter()
+                    .any(|c| !entry.iter().any(|e| e.dominates(c) || e.same_cost(c)));
+                if improves {
+                    let mut merged = entry.clone();
+                    merged.extend(candidates);
+                    *entry = prune(merged);
+                    changed = true;
+                }
+            }
+        }
+    }
+    frontiers.retain(|item, _| !raw_resources.contains(item));
+    frontiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::{CraftingMachine, ItemStack};
+
+    fn recipe(name: &str, input: (&str, u32), output: (&str, u32), power: f64) -> Recipe {
+        Recipe {
+            id: name.to_string(),
+            name: name.to_string(),
+            inputs: vec![ItemStack { item: input.0.to_string(), quantity: input.1 }],
+            outputs: vec![ItemStack { item: output.0.to_string(), quantity: output.1 }],
+            machine: CraftingMachine { id: name.to_string(), name: name.to_string(), power },
+            time: 6000,
+            enabled: true,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_frontier_keeps_trade_off() {
+        // Cheap in ore but power hungry, versus the reverse, versus strictly worse
+        let recipes = vec![
+            recipe("Lean", ("Desc_OreIron_C", 1), ("Desc_IronIngot_C", 1), 100.0),
+            recipe("Frugal", ("Desc_OreIron_C", 2), ("Desc_IronIngot_C", 1), 1.0),
+            recipe("Wasteful", ("Desc_OreIron_C", 3), ("Desc_IronIngot_C", 1), 200.0),
+        ];
+        let power_map = recipes.iter().map(|r| (r.machine.id.clone(), r.machine.power)).collect();
+        let raw_resources = HashSet::from(["Desc_OreIron_C".to_string()]);
+        let frontiers = compute_pareto_frontiers(&recipes, &power_map, &raw_resources);
+        let ingot = &frontiers["Desc_IronIngot_C"];
+        let names: HashSet<_> = ingot.iter().filter_map(|p| p.recipe_name()).collect();
+        assert_eq!(names, HashSet::from(["Lean", "Frugal"]));
+    }
+
+    #[test]
+    fn test_shared_intermediate_uses_one_recipe() {
+        // Frames and rotors both need ingots; a frame with lean ingots and a rotor with frugal ones
+        // would cost less ore than all frugal and less power than all lean, but is not one chain
+        let mut motor = recipe("Motor", ("Desc_ModularFrame_C", 1), ("Desc_Motor_C", 1), 1.0);
+        motor.inputs.push(ItemStack { item: "Desc_Rotor_C".to_string(), quantity: 1 });
+        let recipes = vec![
+            recipe("Lean", ("Desc_OreIron_C", 1), ("Desc_IronIngot_C", 1), 100.0),
+            recipe("Frugal", ("Desc_OreIron_C", 2), ("Desc_IronIngot_C", 1), 1.0),
+            recipe("Frame", ("Desc_IronIngot_C", 1), ("Desc_ModularFrame_C", 1), 1.0),
+            recipe("Rotor", ("Desc_IronIngot_C", 1), ("Desc_Rotor_C", 1), 1.0),
+            motor,
+        ];
+        let power_map = recipes.iter().map(|r| (r.machine.id.clone(), r.machine.power)).collect();
+        let raw_resources = HashSet::from(["Desc_OreIron_C".to_string()]);
+        let frontiers = compute_pareto_frontiers(&recipes, &power_map, &raw_resources);
+        let motor = &frontiers["Desc_Motor_C"];
+        assert_eq!(motor.len(), 2);
+        for point in motor {
+            let ingot: Vec<_> = point.chain.iter().filter(|(item, _)| item == "Desc_IronIngot_C").collect();
+            assert_eq!(ingot.len(), 1);
+        }
+    }
+}
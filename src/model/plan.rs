@@ -0,0 +1,311 @@
+//! The user's working plan: one or more named factories with their requested outputs, supplies and
+//! recipe choices, the enabled recipes they share, plus UI settings.
+//!
+//! Unlike the dataset caches, a plan is user data, so a stored plan is never discarded for being
+//! old: each schema change bumps the version and adds a migration that rewrites the previous version.
+//! New fields with a default need neither, since missing fields read as their default.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::recipe::Recipe;
+
+/// Local storage key prefix of the plan; the game id is appended
+pub const PLAN_STORAGE_KEY: &str = "factory-planner.plan";
+
+/// Upgrades from each stored version to the next, oldest first; append one whenever the layout changes
+const PLAN_MIGRATIONS: [fn(&mut Value); 1] = [projects_from_single_plan];
+
+/// Version 2 holds several factories; the single plan of version 1 becomes the first
+fn projects_from_single_plan(plan: &mut Value) {
+    nest_into_first_factory(plan, &SINGLE_FACTORY_FIELDS);
+}
+
+/// Fields of the single factory that plans had before projects
+pub(crate) const SINGLE_FACTORY_FIELDS: [&str; 5] = ["outputs", "supplies", "recipe_choices", "clocks", "notes"];
+
+/// Name of the factory older plans become
+pub const DEFAULT_FACTORY_NAME: &str = "Factory 1";
+
+/// Move top-level fields of a single-factory plan into the first entry of `factories`
+pub(crate) fn nest_into_first_factory(plan: &mut Value, fields: &[&str]) {
+    let Some(object) = plan.as_object_mut() else {
+        return;
+    };
+    let mut factory = serde_json::Map::new();
+    factory.insert("name".to_string(), DEFAULT_FACTORY_NAME.into());
+    for field in fields {
+        if let Some(value) = object.remove(*field) {
+            factory.insert(field.to_string(), value);
+        }
+    }
+    object.insert("factories".to_string(), Value::Array(vec![Value::Object(factory)]));
+    object.insert("active".to_string(), 0.into());
+}
+
+/// Version of the layout this build writes
+pub const PLAN_STORAGE_VERSION: u32 = PLAN_MIGRATIONS.len() as u32 + 1;
+
+pub fn plan_storage_key(game: &str) -> String {
+    format!("{}.{}", PLAN_STORAGE_KEY, game)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanSettings {
+    /// Active tab of the app
+    pub tab: String,
+    /// Output filter of the recipe list
+    pub recipe_filter: String,
+}
+
+impl Default for PlanSettings {
+    fn default() -> Self {
+        PlanSettings {
+            tab: "calc".to_string(),
+            recipe_filter: String::new(),
+        }
+    }
+}
+
+/// One factory of a project, planned on its own
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FactoryPlan {
+    pub name: String,
+    /// Requested products and their rates, in the order they were added
+    pub outputs: Vec<(String, f64)>,
+    /// Items brought in from outside at a rate, which the breakdown does not produce
+    pub supplies: Vec<(String, f64)>,
+    /// Factory of the project each supplied item comes from; absent when it comes from outside the project
+    pub supply_sources: BTreeMap<String, String>,
+    /// Recipe the breakdown uses for a product that several enabled recipes make
+    pub recipe_choices: BTreeMap<String, String>,
+    /// Clock speed in percent of the machines making a product; 100 when absent
+    pub clocks: BTreeMap<String, f64>,
+    pub notes: String,
+}
+
+impl FactoryPlan {
+    pub fn named(name: &str) -> Self {
+        FactoryPlan {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanState {
+    /// Never empty
+    pub factories: Vec<FactoryPlan>,
+    /// Index of the factory the calculator shows
+    pub active: usize,
+    /// Enabled flag by recipe id; recipes that are not listed keep the dataset's default
+    pub enabled: BTreeMap<String, bool>,
+    pub settings: PlanSettings,
+}
+
+impl Default for PlanState {
+    fn default() -> Self {
+        PlanState {
+            factories: vec![FactoryPlan::named(DEFAULT_FACTORY_NAME)],
+            active: 0,
+            enabled: BTreeMap::new(),
+            settings: PlanSettings::default(),
+        }
+    }
+}
+
+impl PlanState {
+    /// Restore the invariants a hand-edited or migrated plan may break: at least one factory and a valid `active`
+    pub fn normalize(&mut self) {
+        if self.factories.is_empty() {
+            self.factories.push(FactoryPlan::named(DEFAULT_FACTORY_NAME));
+        }
+        self.active = self.active.min(self.factories.len() - 1);
+    }
+
+    pub fn active_factory(&self) -> &FactoryPlan {
+        &self.factories[self.active]
+    }
+
+    /// A factory name not taken yet, numbered after the existing factories
+    pub fn unused_factory_name(&self) -> String {
+        (self.factories.len() + 1..)
+            .map(|n| format!("Factory {}", n))
+            .find(|name| self.factories.iter().all(|f| &f.name != name))
+            .expect("some number is unused")
+    }
+
+    /// Append an empty factory and make it the active one
+    pub fn add_factory(&mut self) {
+        self.factories.push(FactoryPlan::named(&self.unused_factory_name()));
+        self.active = self.factories.len() - 1;
+    }
+
+    /// Remove a factory unless it is the last one; supplies it delivered come from outside the project afterwards
+    pub fn remove_factory(&mut self, index: usize) {
+        if self.factories.len() <= 1 || index >= self.factories.len() {
+            return;
+        }
+        let removed = self.factories.remove(index);
+        for factory in &mut self.factories {
+            factory.supply_sources.retain(|_, source| *source != removed.name);
+        }
+        if self.active > index || self.active == self.factories.len() {
+            self.active -= 1;
+        }
+    }
+
+    /// Rename a factory and the supplies that refer to it; false when the name is empty or taken
+    pub fn rename_factory(&mut self, index: usize, name: &str) -> bool {
+        let name = name.trim();
+        let taken = self.factories.iter().enumerate().any(|(i, f)| i != index && f.name == name);
+        if name.is_empty() || index >= self.factories.len() || taken {
+            return false;
+        }
+        let old = std::mem::replace(&mut self.factories[index].name, name.to_string());
+        for factory in &mut self.factories {
+            for source in factory.supply_sources.values_mut().filter(|source| **source == old) {
+                *source = name.to_string();
+            }
+        }
+        true
+    }
+
+    /// Apply the stored enabled flags to the recipes they mention
+    pub fn apply_enabled(&self, recipes: &mut [Recipe]) {
+        for recipe in recipes.iter_mut() {
+            if let Some(flag) = self.enabled.get(&recipe.id) {
+                recipe.enabled = *flag;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanStorageError {
+    /// Not a stored plan at all
+    Invalid(String),
+    /// Written by a newer build, whose layout this one cannot read
+    Newer(u32),
+}
+
+impl std::fmt::Display for PlanStorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanStorageError::Invalid(message) => write!(f, "the saved plan is unreadable: {}", message),
+            PlanStorageError::Newer(version) => write!(
+                f,
+                "the saved plan has version {}, newer than this app supports ({})",
+                version, PLAN_STORAGE_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlanStorageError {}
+
+/// A plan as kept in local storage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredPlan {
+    pub version: u32,
+    pub plan: PlanState,
+}
+
+impl StoredPlan {
+    pub fn new(plan: PlanState) -> Self {
+        StoredPlan {
+            version: PLAN_STORAGE_VERSION,
+            plan,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("plan serializes")
+    }
+
+    /// Parse a stored entry, migrating it from an older version
+    pub fn from_json(json: &str) -> Result<PlanState, PlanStorageError> {
+        let mut value: Value = serde_json::from_str(json).map_err(|e| PlanStorageError::Invalid(e.to_string()))?;
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| PlanStorageError::Invalid("missing version".to_string()))?;
+        if version > PLAN_STORAGE_VERSION {
+            return Err(PlanStorageError::Newer(version));
+        }
+        let plan = value
+            .get_mut("plan")
+            .ok_or_else(|| PlanStorageError::Invalid("missing plan".to_string()))?;
+        for migrate in &PLAN_MIGRATIONS[version as usize - 1..] {
+            migrate(plan);
+        }
+        let mut plan: PlanState = serde_json::from_value(plan.take()).map_err(|e| PlanStorageError::Invalid(e.to_string()))?;
+        plan.normalize();
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_plan_versions() {
+        let plan = PlanState {
+            factories: vec![
+                FactoryPlan {
+                    outputs: vec![("Desc_IronPlate_C".to_string(), 30.0)],
+                    recipe_choices: BTreeMap::from([("Desc_IronIngot_C".to_string(), "Recipe_IngotIron_C".to_string())]),
+                    ..FactoryPlan::named("Smelting")
+                },
+                FactoryPlan {
+                    supplies: vec![("Desc_IronPlate_C".to_string(), 30.0)],
+                    supply_sources: BTreeMap::from([("Desc_IronPlate_C".to_string(), "Smelting".to_string())]),
+                    ..FactoryPlan::named("Assembly")
+                },
+            ],
+            active: 1,
+            enabled: BTreeMap::from([("Recipe_Alternate_PureIronIngot_C".to_string(), true)]),
+            settings: PlanSettings {
+                tab: "recipes".to_string(),
+                recipe_filter: "Iron".to_string(),
+            },
+        };
+        assert_eq!(StoredPlan::from_json(&StoredPlan::new(plan.clone()).to_json()), Ok(plan.clone()));
+
+        // A single-factory plan of version 1 becomes the first factory; missing fields take their defaults
+        let v1 = r#"{"version": 1, "plan": {"outputs": [["Desc_Rotor_C", 5.0]], "notes": "rotors", "settings": {"tab": "recipes"}}}"#;
+        let v1 = StoredPlan::from_json(v1).unwrap();
+        assert_eq!(v1.factories.len(), 1);
+        assert_eq!(v1.active_factory().name, DEFAULT_FACTORY_NAME);
+        assert_eq!(v1.active_factory().outputs, vec![("Desc_Rotor_C".to_string(), 5.0)]);
+        assert_eq!(v1.active_factory().notes, "rotors");
+        assert_eq!(v1.settings.tab, "recipes");
+        assert_eq!(v1.settings.recipe_filter, "");
+
+        let empty = format!(r#"{{"version": {}, "plan": {{"factories": [], "active": 3}}}}"#, PLAN_STORAGE_VERSION);
+        assert_eq!(StoredPlan::from_json(&empty).unwrap(), PlanState::default());
+
+        let mut project = plan.clone();
+        assert!(project.rename_factory(0, "Iron"));
+        assert!(!project.rename_factory(1, "Iron"));
+        assert_eq!(project.factories[1].supply_sources["Desc_IronPlate_C"], "Iron");
+        project.add_factory();
+        assert_eq!((project.active, project.active_factory().name.as_str()), (2, "Factory 3"));
+        project.remove_factory(0);
+        assert_eq!(project.active, 1);
+        assert!(project.factories[0].supply_sources.is_empty());
+
+        let newer = format!(r#"{{"version": {}, "plan": {{}}}}"#, PLAN_STORAGE_VERSION + 1);
+        assert_eq!(StoredPlan::from_json(&newer), Err(PlanStorageError::Newer(PLAN_STORAGE_VERSION + 1)));
+        assert!(matches!(StoredPlan::from_json("[]"), Err(PlanStorageError::Invalid(_))));
+    }
+}
@@ -0,0 +1,363 @@
+//! Plan files: a plan saved as TOML or JSON, to keep next to notes in git and review as a diff
+//!
+//! ```toml
+//! format = 2                          # required, the version of this format
+//! game = "satisfactory"
+//! dataset_version = "1.0"
+//! enabled_recipes = ["Recipe_IngotIron_C", "Recipe_IronPlate_C"]
+//!
+//! [[factories]]
+//! name = "Smelting"
+//! notes = "Iron for the main bus"
+//!
+//! [[factories.outputs]]
+//! item = "Desc_IronPlate_C"
+//! name = "Iron Plate"                 # label for readers; used when the id is unknown
+//! rate = 60.0
+//!
+//! [[factories.supplies]]              # items brought in, same fields as outputs
+//! item = "Desc_OreIron_C"
+//! rate = 120.0
+//! from = "Mine"                       # factory of the project it comes from; omitted for outside
+//!
+//! [[factories.machines]]              # recipe and clock speed per product, both optional
+//! item = "Desc_IronIngot_C"
+//! recipe = "Recipe_IngotIron_C"
+//! clock = 250.0
+//! ```
+//!
+//! Every list is sorted or kept in plan order, so saving an unchanged plan gives the same file. Files
+//! of an older format are migrated on load; each format change appends a migration. Format 1 had a
+//! single factory, with its fields at the top level.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::item_label;
+use crate::model::plan::{nest_into_first_factory, FactoryPlan, PlanState};
+use crate::model::recipe::Recipe;
+
+/// Upgrades from each format to the next, oldest first; append one whenever the format changes
+const PLAN_FILE_MIGRATIONS: [fn(&mut Value); 1] = [projects_from_single_plan];
+
+/// Version of the format this build writes
+pub const PLAN_FILE_FORMAT: u32 = PLAN_FILE_MIGRATIONS.len() as u32 + 1;
+
+fn projects_from_single_plan(file: &mut Value) {
+    nest_into_first_factory(file, &["notes", "outputs", "supplies", "machines"]);
+    // The active factory is UI state, not part of files
+    if let Some(object) = file.as_object_mut() {
+        object.remove("active");
+    }
+}
+
+/// An item and a rate, named for readers of the file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanFileRate {
+    pub item: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    pub rate: f64,
+    /// For supplies, the factory of the project the item comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanFileMachine {
+    pub item: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<String>,
+    /// Clock speed in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanFileFactory {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    #[serde(default)]
+    pub outputs: Vec<PlanFileRate>,
+    #[serde(default)]
+    pub supplies: Vec<PlanFileRate>,
+    #[serde(default)]
+    pub machines: Vec<PlanFileMachine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanFile {
+    pub format: u32,
+    pub game: String,
+    pub dataset_version: String,
+    /// Ids of the enabled recipes; every other recipe is disabled
+    #[serde(default)]
+    pub enabled_recipes: Vec<String>,
+    #[serde(default)]
+    pub factories: Vec<PlanFileFactory>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanFileError {
+    Parse(String),
+    /// Written by a newer build
+    Newer(u32),
+    /// Made for another game than the loaded dataset
+    WrongGame { file: String, loaded: String },
+}
+
+impl std::fmt::Display for PlanFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanFileError::Parse(message) => write!(f, "not a plan file: {}", message),
+            PlanFileError::Newer(format) => write!(
+                f,
+                "the plan file has format {}, newer than this app supports ({})",
+                format, PLAN_FILE_FORMAT
+            ),
+            PlanFileError::WrongGame { file, loaded } => {
+                write!(f, "the plan is for {}, but the loaded dataset is {}", file, loaded)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlanFileError {}
+
+fn rates(
+    rates: &[(String, f64)],
+    sources: &BTreeMap<String, String>,
+    item_names: &HashMap<String, String>,
+) -> Vec<PlanFileRate> {
+    rates
+        .iter()
+        .map(|(item, rate)| PlanFileRate {
+            item: item.clone(),
+            name: item_names.get(item).cloned().unwrap_or_default(),
+            rate: *rate,
+            from: sources.get(item).cloned(),
+        })
+        .collect()
+}
+
+impl PlanFileFactory {
+    fn new(factory: &FactoryPlan, item_names: &HashMap<String, String>) -> Self {
+        let items: BTreeSet<&String> = factory.recipe_choices.keys().chain(factory.clocks.keys()).collect();
+        PlanFileFactory {
+            name: factory.name.clone(),
+            notes: factory.notes.clone(),
+            outputs: rates(&factory.outputs, &BTreeMap::new(), item_names),
+            supplies: rates(&factory.supplies, &factory.supply_sources, item_names),
+            machines: items
+                .into_iter()
+                .map(|item| PlanFileMachine {
+                    item: item.clone(),
+                    recipe: factory.recipe_choices.get(item).cloned(),
+                    clock: factory.clocks.get(item).copied(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PlanFile {
+    /// Capture a plan with the enabled flags of the current recipes
+    pub fn new(
+        game: &str,
+        dataset_version: &str,
+        plan: &PlanState,
+        recipes: &[Recipe],
+        item_names: &HashMap<String, String>,
+    ) -> Self {
+        let mut enabled_recipes: Vec<String> = recipes.iter().filter(|r| r.enabled).map(|r| r.id.clone()).collect();
+        enabled_recipes.sort();
+        PlanFile {
+            format: PLAN_FILE_FORMAT,
+            game: game.to_string(),
+            dataset_version: dataset_version.to_string(),
+            enabled_recipes,
+            factories: plan.factories.iter().map(|f| PlanFileFactory::new(f, item_names)).collect(),
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("plan file serializes")
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("plan file serializes")
+    }
+
+    /// Parse a plan file, as JSON if it is an object and as TOML otherwise, migrating older formats
+    pub fn parse(text: &str) -> Result<Self, PlanFileError> {
+        let text = text.trim_start_matches('\u{FEFF}');
+        let mut value: Value = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|e| PlanFileError::Parse(e.to_string()))?
+        } else {
+            toml::from_str(text).map_err(|e| PlanFileError::Parse(e.to_string()))?
+        };
+        let format = value
+            .get("format")
+            .and_then(Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| PlanFileError::Parse("missing format".to_string()))?;
+        if format > PLAN_FILE_FORMAT {
+            return Err(PlanFileError::Newer(format));
+        }
+        for migrate in &PLAN_FILE_MIGRATIONS[format as usize - 1..] {
+            migrate(&mut value);
+        }
+        value["format"] = PLAN_FILE_FORMAT.into();
+        serde_json::from_value(value).map_err(|e| PlanFileError::Parse(e.to_string()))
+    }
+
+    /// The plan for the loaded recipes, with a warning for each item or recipe the dataset lacks.
+    /// Items are matched by id, falling back to their name.
+    pub fn to_plan(
+        &self,
+        game: &str,
+        recipes: &[Recipe],
+        item_names: &HashMap<String, String>,
+    ) -> Result<(PlanState, Vec<String>), PlanFileError> {
+        if self.game != game {
+            return Err(PlanFileError::WrongGame {
+                file: self.game.clone(),
+                loaded: game.to_string(),
+            });
+        }
+        let mut warnings = Vec::new();
+        let mut resolve = |rate: &PlanFileRate| -> Option<(String, f64)> {
+            if item_names.contains_key(&rate.item) {
+                return Some((rate.item.clone(), rate.rate));
+            }
+            let by_name = item_names.iter().find(|(_, name)| !rate.name.is_empty() && **name == rate.name);
+            if by_name.is_none() {
+                warnings.push(format!("Unknown item {}", item_label(item_names, &rate.item)));
+            }
+            by_name.map(|(id, _)| (id.clone(), rate.rate))
+        };
+        let mut factories = Vec::new();
+        for factory in &self.factories {
+            let outputs = factory.outputs.iter().filter_map(&mut resolve).collect();
+            let mut supplies = Vec::new();
+            let mut supply_sources = BTreeMap::new();
+            for supply in &factory.supplies {
+                if let Some((item, rate)) = resolve(supply) {
+                    if let Some(from) = &supply.from {
+                        supply_sources.insert(item.clone(), from.clone());
+                    }
+                    supplies.push((item, rate));
+                }
+            }
+            factories.push(FactoryPlan {
+                name: factory.name.clone(),
+                outputs,
+                supplies,
+                supply_sources,
+                recipe_choices: factory
+                    .machines
+                    .iter()
+                    .filter_map(|m| m.recipe.clone().map(|recipe| (m.item.clone(), recipe)))
+                    .collect(),
+                clocks: factory.machines.iter().filter_map(|m| m.clock.map(|clock| (m.item.clone(), clock))).collect(),
+                notes: factory.notes.clone(),
+            });
+        }
+        let enabled_ids: HashSet<&str> = self.enabled_recipes.iter().map(String::as_str).collect();
+        let known: HashSet<&str> = recipes.iter().map(|r| r.id.as_str()).collect();
+        warnings.extend(
+            self.enabled_recipes
+                .iter()
+                .chain(self.factories.iter().flat_map(|f| f.machines.iter().filter_map(|m| m.recipe.as_ref())))
+                .filter(|id| !known.contains(id.as_str()))
+                .map(|id| format!("Unknown recipe {}", id)),
+        );
+        let mut plan = PlanState {
+            factories,
+            active: 0,
+            enabled: recipes
+                .iter()
+                .map(|r| (r.id.clone(), enabled_ids.contains(r.id.as_str())))
+                .collect(),
+            settings: Default::default(),
+        };
+        plan.normalize();
+        Ok((plan, warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::test_recipe;
+
+    #[test]
+    fn test_plan_file_round_trip() {
+        let mut recipes = vec![
+            test_recipe("Recipe_IngotIron_C", "Desc_OreIron_C", "Desc_IronIngot_C"),
+            test_recipe("Recipe_Alternate_PureIronIngot_C", "Desc_OreIron_C", "Desc_IronIngot_C"),
+        ];
+        recipes[1].enabled = false;
+        let item_names = HashMap::from([
+            ("Desc_OreIron_C".to_string(), "Iron Ore".to_string()),
+            ("Desc_IronIngot_C".to_string(), "Iron Ingot".to_string()),
+        ]);
+        let plan = PlanState {
+            factories: vec![
+                FactoryPlan {
+                    outputs: vec![("Desc_IronIngot_C".to_string(), 60.0)],
+                    supplies: vec![("Desc_OreIron_C".to_string(), 30.0)],
+                    recipe_choices: BTreeMap::from([("Desc_IronIngot_C".to_string(), "Recipe_IngotIron_C".to_string())]),
+                    clocks: BTreeMap::from([("Desc_IronIngot_C".to_string(), 250.0)]),
+                    notes: "Starter iron".to_string(),
+                    ..FactoryPlan::named("Smelting")
+                },
+                FactoryPlan {
+                    supplies: vec![("Desc_IronIngot_C".to_string(), 60.0)],
+                    supply_sources: BTreeMap::from([("Desc_IronIngot_C".to_string(), "Smelting".to_string())]),
+                    ..FactoryPlan::named("Assembly")
+                },
+            ],
+            ..Default::default()
+        };
+        let file = PlanFile::new("satisfactory", "1.0", &plan, &recipes, &item_names);
+        let toml = file.to_toml();
+        assert!(toml.contains("name = \"Iron Ingot\""), "{}", toml);
+        assert!(toml.contains("from = \"Smelting\""), "{}", toml);
+        assert_eq!(PlanFile::parse(&toml), Ok(file.clone()));
+        assert_eq!(PlanFile::parse(&file.to_json()), Ok(file.clone()));
+
+        let (read, warnings) = file.to_plan("satisfactory", &recipes, &item_names).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(read.enabled.get("Recipe_Alternate_PureIronIngot_C"), Some(&false));
+        assert_eq!(PlanState { enabled: BTreeMap::new(), ..read }, plan);
+
+        // A format 1 file is one factory; outputs whose id changed are found by name
+        let v1 = r#"
+            format = 1
+            game = "satisfactory"
+            dataset_version = "1.0"
+            notes = "old"
+            [[outputs]]
+            item = "Desc_IronIngotOld_C"
+            name = "Iron Ingot"
+            rate = 10.0
+            [[outputs]]
+            item = "Desc_Unobtainium_C"
+            rate = 1.0
+        "#;
+        let (read, warnings) = PlanFile::parse(v1).unwrap().to_plan("satisfactory", &recipes, &item_names).unwrap();
+        assert_eq!(read.factories.len(), 1);
+        assert_eq!(read.active_factory().notes, "old");
+        assert_eq!(read.active_factory().outputs, vec![("Desc_IronIngot_C".to_string(), 10.0)]);
+        assert_eq!(warnings, vec!["Unknown item Desc_Unobtainium_C".to_string()]);
+
+        assert!(matches!(file.to_plan("factorio", &recipes, &item_names), Err(PlanFileError::WrongGame { .. })));
+        let newer = format!("format = {}\ngame = \"satisfactory\"\ndataset_version = \"1.0\"", PLAN_FILE_FORMAT + 1);
+        assert_eq!(PlanFile::parse(&newer), Err(PlanFileError::Newer(PLAN_FILE_FORMAT + 1)));
+    }
+}
@@ -0,0 +1,188 @@
+//! Projects: the factories of a plan together, with the items they pass to each other and the raw
+//! resources they use between them
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::model::{
+    breakdown::{factory_breakdown, leaf_totals},
+    plan::FactoryPlan,
+    recipe::Recipe,
+    resource_availability,
+};
+
+/// Items one factory of the project supplies to another
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectFlow {
+    pub from: String,
+    pub to: String,
+    pub item: String,
+    /// Rate the receiving factory plans to take
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceUse {
+    pub resource: String,
+    /// Summed over the factories of the project
+    pub used: f64,
+    pub available: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectWarning {
+    /// A supply names a factory the project does not have
+    UnknownSource { factory: String, item: String, source: String },
+    /// The factories taking an item from another need more than it makes
+    ShortSource { source: String, item: String, made: f64, taken: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProjectSummary {
+    pub flows: Vec<ProjectFlow>,
+    /// Raw resources the project uses, sorted by id
+    pub resources: Vec<ResourceUse>,
+    pub warnings: Vec<ProjectWarning>,
+}
+
+/// Flows between the factories and their combined raw resource use, against the map's availability.
+/// Raw resources one factory takes from another are counted once, where they are mined.
+pub fn summarize_project(
+    factories: &[FactoryPlan],
+    recipes: &[Recipe],
+    enabled: &HashSet<String>,
+    raw_resources: &HashSet<String>,
+) -> ProjectSummary {
+    let mut summary = ProjectSummary::default();
+    let mut used: BTreeMap<String, f64> = BTreeMap::new();
+    for factory in factories {
+        let (raw, _) = leaf_totals(&factory_breakdown(factory, recipes, enabled, raw_resources), raw_resources);
+        for (resource, rate) in raw {
+            *used.entry(resource).or_insert(0.0) += rate;
+        }
+        for (item, rate) in &factory.supplies {
+            let Some(source) = factory.supply_sources.get(item) else {
+                continue;
+            };
+            if factories.iter().any(|f| &f.name == source) {
+                summary.flows.push(ProjectFlow {
+                    from: source.clone(),
+                    to: factory.name.clone(),
+                    item: item.clone(),
+                    rate: *rate,
+                });
+            } else {
+                summary.warnings.push(ProjectWarning::UnknownSource {
+                    factory: factory.name.clone(),
+                    item: item.clone(),
+                    source: source.clone(),
+                });
+            }
+        }
+    }
+
+    let mut taken: BTreeMap<(&str, &str), f64> = BTreeMap::new();
+    for flow in &summary.flows {
+        *taken.entry((flow.from.as_str(), flow.item.as_str())).or_insert(0.0) += flow.rate;
+    }
+    for ((source, item), taken) in taken {
+        let made: f64 = factories
+            .iter()
+            .filter(|f| f.name == source)
+            .flat_map(|f| f.outputs.iter())
+            .filter(|(output, _)| output == item)
+            .map(|(_, rate)| rate)
+            .sum();
+        if taken > made + 1e-6 {
+            summary.warnings.push(ProjectWarning::ShortSource {
+                source: source.to_string(),
+                item: item.to_string(),
+                made,
+                taken,
+            });
+        }
+    }
+
+    summary.resources = used
+        .into_iter()
+        .map(|(resource, used)| ResourceUse {
+            available: resource_availability(&resource),
+            resource,
+            used,
+        })
+        .collect();
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recipe::test_recipe as recipe;
+
+    #[test]
+    fn test_project_flows_and_resources() {
+        let recipes = vec![
+            recipe("Recipe_IngotIron_C", "Desc_OreIron_C", "Desc_IronIngot_C"),
+            recipe("Recipe_IronRod_C", "Desc_IronIngot_C", "Desc_IronRod_C"),
+        ];
+        let enabled = recipes.iter().map(|r| r.id.clone()).collect();
+        let raw_resources = HashSet::from(["Desc_OreIron_C".to_string()]);
+        let factories = vec![
+            FactoryPlan {
+                outputs: vec![("Desc_IronIngot_C".to_string(), 30.0)],
+                ..FactoryPlan::named("Smelting")
+            },
+            FactoryPlan {
+                outputs: vec![("Desc_IronRod_C".to_string(), 40.0)],
+                supplies: vec![("Desc_IronIngot_C".to_string(), 40.0)],
+                supply_sources: BTreeMap::from([("Desc_IronIngot_C".to_string(), "Smelting".to_string())]),
+                ..FactoryPlan::named("Rods")
+            },
+            FactoryPlan {
+                outputs: vec![("Desc_IronRod_C".to_string(), 10.0)],
+                supplies: vec![("Desc_IronIngot_C".to_string(), 10.0)],
+                supply_sources: BTreeMap::from([("Desc_IronIngot_C".to_string(), "Gone".to_string())]),
+                ..FactoryPlan::named("Spare")
+            },
+            FactoryPlan {
+                outputs: vec![("Desc_IronIngot_C".to_string(), 50.0)],
+                supplies: vec![("Desc_OreIron_C".to_string(), 20.0)],
+                ..FactoryPlan::named("Topped up")
+            },
+        ];
+        let summary = summarize_project(&factories, &recipes, &enabled, &raw_resources);
+        assert_eq!(
+            summary.flows,
+            vec![ProjectFlow {
+                from: "Smelting".to_string(),
+                to: "Rods".to_string(),
+                item: "Desc_IronIngot_C".to_string(),
+                rate: 40.0,
+            }]
+        );
+        assert_eq!(
+            summary.warnings,
+            vec![
+                ProjectWarning::UnknownSource {
+                    factory: "Spare".to_string(),
+                    item: "Desc_IronIngot_C".to_string(),
+                    source: "Gone".to_string(),
+                },
+                ProjectWarning::ShortSource {
+                    source: "Smelting".to_string(),
+                    item: "Desc_IronIngot_C".to_string(),
+                    made: 30.0,
+                    taken: 40.0,
+                },
+            ]
+        );
+        // The rod factories take ingots; the last factory mines what its ore supply does not cover
+        assert_eq!(
+            summary.resources,
+            vec![ResourceUse {
+                resource: "Desc_OreIron_C".to_string(),
+                used: 30.0 + 30.0,
+                available: resource_availability("Desc_OreIron_C"),
+            }]
+        );
+    }
+}
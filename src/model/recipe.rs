@@ -1,25 +1,60 @@
 use reactive_stores::Store;
+use serde::{Deserialize, Serialize};
 
 // Data structures for factory building game recipes
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub id: String,   // stable class-based identifier, e.g. Desc_IronIngot_C
+    pub name: String, // localized display name
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ItemStack {
-    pub item: String,
+    pub item: String, // item id
     pub quantity: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CraftingMachine {
-    pub name: String,
-    // Add more fields as needed (e.g., speed, power usage)
+    pub id: String,   // stable class-based identifier, e.g. Build_ConstructorMk1_C
+    pub name: String, // localized display name
+    pub power: f64, // power consumption in MW
+    // Add more fields as needed (e.g., speed)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Store)]
+#[derive(Debug, Clone, PartialEq, Store, Serialize, Deserialize)]
 pub struct Recipe {
-    pub name: String,
+    pub id: String,   // stable class-based identifier, e.g. Recipe_IronPlate_C
+    pub name: String, // localized display name
     pub inputs: Vec<ItemStack>,
     pub outputs: Vec<ItemStack>,
     pub machine: CraftingMachine,
     pub time: u32, // crafting time in milliseconds
     pub enabled: bool, // true if recipe is enabled by default
+    #[serde(default)]
+    pub source: Option<String>, // mod that added or last changed the recipe; None for the base game
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schematic {
+    pub id: String,   // stable class-based identifier, e.g. Schematic_1-1_C
+    pub name: String, // localized display name
+    pub tier: u32,    // tech tier, 0 for tutorial and non-milestone schematics
+    pub unlocked_recipes: Vec<String>, // recipe ids
+}
+
+/// A recipe turning one input into one output in a 4 MW constructor every 2 s, for tests
+#[cfg(test)]
+pub(crate) fn test_recipe(id: &str, input: &str, output: &str) -> Recipe {
+    Recipe {
+        id: id.to_string(),
+        name: id.to_string(),
+        inputs: vec![ItemStack { item: input.to_string(), quantity: 1 }],
+        outputs: vec![ItemStack { item: output.to_string(), quantity: 1 }],
+        machine: CraftingMachine { id: "Build_ConstructorMk1_C".to_string(), name: "Constructor".to_string(), power: 4.0 },
+        time: 2000,
+        enabled: true,
+        source: None,
+    }
 }
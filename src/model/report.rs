@@ -0,0 +1,205 @@
+//! Results of the command-line planner as rows, written as an aligned text table, JSON or CSV
+
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 3] = [ReportFormat::Table, ReportFormat::Json, ReportFormat::Csv];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            ReportFormat::Table => "table",
+            ReportFormat::Json => "json",
+            ReportFormat::Csv => "csv",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.id() == id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    /// Written with two decimals in tables and as a JSON number
+    Number(f64),
+    Count(u64),
+    Flag(bool),
+    Empty,
+}
+
+impl Cell {
+    fn text(&self) -> String {
+        match self {
+            Cell::Text(text) => text.clone(),
+            Cell::Number(n) => format!("{:.2}", n),
+            Cell::Count(n) => n.to_string(),
+            Cell::Flag(flag) => flag.to_string(),
+            Cell::Empty => String::new(),
+        }
+    }
+
+    fn json(&self) -> Value {
+        match self {
+            Cell::Text(text) => Value::from(text.as_str()),
+            Cell::Number(n) => serde_json::Number::from_f64(*n).map(Value::Number).unwrap_or(Value::Null),
+            Cell::Count(n) => Value::from(*n),
+            Cell::Flag(flag) => Value::Bool(*flag),
+            Cell::Empty => Value::Null,
+        }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::Text(text.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::Text(text)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(n: f64) -> Self {
+        Cell::Number(n)
+    }
+}
+
+impl From<u64> for Cell {
+    fn from(n: u64) -> Self {
+        Cell::Count(n)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(flag: bool) -> Self {
+        Cell::Flag(flag)
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Cell::Empty)
+    }
+}
+
+/// Rows under named columns; the column names are the keys of the JSON objects
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Report {
+    pub fn new(columns: &[&'static str]) -> Self {
+        Report { columns: columns.to_vec(), rows: Vec::new() }
+    }
+
+    /// Add a row; it must have a cell per column
+    pub fn push(&mut self, row: Vec<Cell>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Table => self.to_table(),
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Csv => self.to_csv(),
+        }
+    }
+
+    /// Columns padded to their widest cell; numbers are right-aligned
+    fn to_table(&self) -> String {
+        let texts: Vec<Vec<String>> = self.rows.iter().map(|row| row.iter().map(Cell::text).collect()).collect();
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|c| texts.iter().map(|row| row[c].chars().count()).chain([self.columns[c].len()]).max().unwrap_or(0))
+            .collect();
+        let line = |cells: Vec<(String, bool)>| {
+            let padded: Vec<String> = cells
+                .into_iter()
+                .zip(&widths)
+                .map(|((text, right), width)| if right { format!("{:>width$}", text) } else { format!("{:<width$}", text) })
+                .collect();
+            format!("{}\n", padded.join("  ").trim_end())
+        };
+        let mut out = line(self.columns.iter().map(|c| (c.to_string(), false)).collect());
+        out.push_str(&line(widths.iter().map(|w| ("-".repeat(*w), false)).collect()));
+        for (row, texts) in self.rows.iter().zip(texts) {
+            out.push_str(&line(row.iter().zip(texts).map(|(cell, text)| (text, matches!(cell, Cell::Number(_) | Cell::Count(_)))).collect()));
+        }
+        out
+    }
+
+    fn to_json(&self) -> String {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| Value::Object(self.columns.iter().map(|c| c.to_string()).zip(row.iter().map(Cell::json)).collect::<Map<_, _>>()))
+            .collect();
+        serde_json::to_string_pretty(&rows).expect("report is valid JSON") + "\n"
+    }
+
+    /// Numbers keep their full precision; cells with commas, quotes or line breaks are quoted
+    fn to_csv(&self) -> String {
+        fn field(text: &str) -> String {
+            if text.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", text.replace('"', "\"\""))
+            } else {
+                text.to_string()
+            }
+        }
+        let mut out = self.columns.iter().map(|c| field(c)).collect::<Vec<_>>().join(",") + "\n";
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Number(n) => n.to_string(),
+                    cell => field(&cell.text()),
+                })
+                .collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_formats() {
+        let mut report = Report::new(&["item", "rate", "raw", "stack"]);
+        report.push(vec!["Iron Ore".into(), 60.0.into(), true.into(), 100_u64.into()]);
+        report.push(vec!["Plate, \"Reinforced\"".into(), 2.5.into(), Cell::Empty, Cell::Empty]);
+
+        let table = report.render(ReportFormat::Table);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "item                 rate   raw   stack");
+        assert_eq!(lines[1], "-------------------  -----  ----  -----");
+        assert_eq!(lines[2], "Iron Ore             60.00  true    100");
+        assert_eq!(lines[3], "Plate, \"Reinforced\"   2.50");
+        assert_eq!(
+            report.render(ReportFormat::Csv),
+            "item,rate,raw,stack\nIron Ore,60,true,100\n\"Plate, \"\"Reinforced\"\"\",2.5,,\n"
+        );
+        let json: Value = serde_json::from_str(&report.render(ReportFormat::Json)).unwrap();
+        assert_eq!(json[0]["rate"], 60.0);
+        assert_eq!(json[0]["stack"], 100);
+        assert_eq!(json[1]["item"], "Plate, \"Reinforced\"");
+        assert_eq!(json[1]["raw"], Value::Null);
+        assert_eq!(ReportFormat::from_id("csv"), Some(ReportFormat::Csv));
+    }
+}
@@ -0,0 +1,195 @@
+//! Plans packed into a URL fragment, so a link restores the calculator and recipe list of whoever shared it
+//!
+//! The fragment is `plan=` followed by the plan as JSON, deflated and base64url-encoded. Unlike the
+//! stored plan it lists exactly the enabled recipes, so every recipe it does not list opens disabled.
+
+use std::collections::HashSet;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::model::plan::{nest_into_first_factory, FactoryPlan, PlanSettings, PlanState, SINGLE_FACTORY_FIELDS};
+use crate::model::recipe::Recipe;
+
+/// Start of the URL fragment of a shared plan, after the `#`
+pub const SHARE_FRAGMENT_PREFIX: &str = "plan=";
+
+/// Upgrades from each link version to the next, oldest first; append one whenever the layout changes
+const SHARE_LINK_MIGRATIONS: [fn(&mut serde_json::Value); 1] = [projects_from_single_plan];
+
+/// Version of the links this build makes; newer links are refused
+pub const SHARE_LINK_VERSION: u32 = SHARE_LINK_MIGRATIONS.len() as u32 + 1;
+
+/// Version 2 shares every factory of the project; the single plan of version 1 becomes the first
+fn projects_from_single_plan(shared: &mut serde_json::Value) {
+    nest_into_first_factory(shared, &SINGLE_FACTORY_FIELDS);
+}
+
+/// Upper bound of an inflated plan, so a crafted link cannot exhaust memory
+const MAX_SHARED_PLAN_BYTES: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedPlan {
+    pub version: u32,
+    /// Game adapter and dataset version the plan was made with
+    pub game: String,
+    pub dataset_version: String,
+    #[serde(default)]
+    pub factories: Vec<FactoryPlan>,
+    #[serde(default)]
+    pub active: usize,
+    /// Ids of the enabled recipes
+    #[serde(default)]
+    pub enabled: Vec<String>,
+    #[serde(default)]
+    pub settings: PlanSettings,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShareLinkError {
+    /// The fragment is not a shared plan or was cut off
+    Invalid(String),
+    /// Made by a newer version of the app
+    Newer(u32),
+}
+
+impl std::fmt::Display for ShareLinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareLinkError::Invalid(message) => write!(f, "the shared plan link is broken: {}", message),
+            ShareLinkError::Newer(version) => write!(
+                f,
+                "the shared plan link has version {}, newer than this app supports ({})",
+                version, SHARE_LINK_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShareLinkError {}
+
+impl SharedPlan {
+    /// Capture a plan with the enabled flags of the current recipes
+    pub fn new(game: &str, dataset_version: &str, plan: &PlanState, recipes: &[Recipe]) -> Self {
+        SharedPlan {
+            version: SHARE_LINK_VERSION,
+            game: game.to_string(),
+            dataset_version: dataset_version.to_string(),
+            factories: plan.factories.clone(),
+            active: plan.active,
+            enabled: recipes.iter().filter(|r| r.enabled).map(|r| r.id.clone()).collect(),
+            settings: plan.settings.clone(),
+        }
+    }
+
+    /// The plan for a set of recipes; recipes the link does not list are disabled
+    pub fn plan(&self, recipes: &[Recipe]) -> PlanState {
+        let enabled: HashSet<&str> = self.enabled.iter().map(String::as_str).collect();
+        let mut plan = PlanState {
+            factories: self.factories.clone(),
+            active: self.active,
+            enabled: recipes.iter().map(|r| (r.id.clone(), enabled.contains(r.id.as_str()))).collect(),
+            settings: self.settings.clone(),
+        };
+        plan.normalize();
+        plan
+    }
+
+    /// The URL fragment, without the `#`
+    pub fn to_fragment(&self) -> String {
+        let json = serde_json::to_vec(self).expect("shared plan serializes");
+        let deflated = miniz_oxide::deflate::compress_to_vec(&json, 9);
+        format!("{}{}", SHARE_FRAGMENT_PREFIX, URL_SAFE_NO_PAD.encode(deflated))
+    }
+
+    /// Whether a URL fragment, with or without the `#`, holds a shared plan
+    pub fn is_fragment(fragment: &str) -> bool {
+        fragment.trim_start_matches('#').starts_with(SHARE_FRAGMENT_PREFIX)
+    }
+
+    /// Read a URL fragment, with or without the `#`
+    pub fn from_fragment(fragment: &str) -> Result<Self, ShareLinkError> {
+        let encoded = fragment
+            .trim_start_matches('#')
+            .strip_prefix(SHARE_FRAGMENT_PREFIX)
+            .ok_or_else(|| ShareLinkError::Invalid("not a plan link".to_string()))?;
+        let deflated = URL_SAFE_NO_PAD
+            .decode(encoded.trim())
+            .map_err(|e| ShareLinkError::Invalid(e.to_string()))?;
+        let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&deflated, MAX_SHARED_PLAN_BYTES)
+            .map_err(|e| ShareLinkError::Invalid(e.to_string()))?;
+        let mut value: serde_json::Value =
+            serde_json::from_slice(&json).map_err(|e| ShareLinkError::Invalid(e.to_string()))?;
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| ShareLinkError::Invalid("missing version".to_string()))?;
+        if version > SHARE_LINK_VERSION {
+            return Err(ShareLinkError::Newer(version));
+        }
+        for migrate in &SHARE_LINK_MIGRATIONS[version as usize - 1..] {
+            migrate(&mut value);
+        }
+        value["version"] = SHARE_LINK_VERSION.into();
+        serde_json::from_value(value).map_err(|e| ShareLinkError::Invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::model::recipe::test_recipe;
+
+    #[test]
+    fn test_shared_plan_fragment_round_trip() {
+        let mut recipes = vec![
+            test_recipe("Recipe_IngotIron_C", "Desc_OreIron_C", "Desc_IronIngot_C"),
+            test_recipe("Recipe_Alternate_PureIronIngot_C", "Desc_OreIron_C", "Desc_IronIngot_C"),
+        ];
+        recipes[1].enabled = false;
+        let plan = PlanState {
+            factories: vec![FactoryPlan {
+                outputs: vec![("Desc_IronIngot_C".to_string(), 90.0)],
+                recipe_choices: BTreeMap::from([("Desc_IronIngot_C".to_string(), "Recipe_IngotIron_C".to_string())]),
+                ..FactoryPlan::named("Smelting")
+            }],
+            settings: PlanSettings {
+                tab: "recipes".to_string(),
+                recipe_filter: "ingot".to_string(),
+            },
+            ..Default::default()
+        };
+        let shared = SharedPlan::new("satisfactory", "1.0", &plan, &recipes);
+        let fragment = shared.to_fragment();
+        assert!(SharedPlan::is_fragment(&format!("#{}", fragment)));
+        assert!(fragment.bytes().all(|b| b.is_ascii_alphanumeric() || b"=-_".contains(&b)));
+        let read = SharedPlan::from_fragment(&format!("#{}", fragment)).unwrap();
+        assert_eq!(read, shared);
+
+        // The receiver's recipes take exactly the shared flags, whatever they were before
+        let mut theirs = recipes.clone();
+        theirs[0].enabled = false;
+        theirs[1].enabled = true;
+        let restored = read.plan(&theirs);
+        assert!(restored.enabled["Recipe_IngotIron_C"]);
+        assert!(!restored.enabled["Recipe_Alternate_PureIronIngot_C"]);
+        assert_eq!(restored.settings, plan.settings);
+        assert_eq!(restored.factories, plan.factories);
+
+        // Links from before projects open as a single factory
+        let v1 = serde_json::json!({
+            "version": 1, "game": "satisfactory", "dataset_version": "1.0",
+            "outputs": [["Desc_IronIngot_C", 30.0]], "enabled": ["Recipe_IngotIron_C"],
+        });
+        let v1 = format!("{}{}", SHARE_FRAGMENT_PREFIX, URL_SAFE_NO_PAD.encode(miniz_oxide::deflate::compress_to_vec(v1.to_string().as_bytes(), 9)));
+        let v1 = SharedPlan::from_fragment(&v1).unwrap().plan(&theirs);
+        assert_eq!(v1.active_factory().outputs, vec![("Desc_IronIngot_C".to_string(), 30.0)]);
+
+        assert!(matches!(SharedPlan::from_fragment("plan=AAAA"), Err(ShareLinkError::Invalid(_))));
+        let newer = SharedPlan { version: SHARE_LINK_VERSION + 1, ..shared };
+        assert_eq!(SharedPlan::from_fragment(&newer.to_fragment()), Err(ShareLinkError::Newer(SHARE_LINK_VERSION + 1)));
+    }
+}